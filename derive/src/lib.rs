@@ -0,0 +1,347 @@
+#![deny(clippy::pedantic, clippy::panic, clippy::expect_used, clippy::unwrap_used)]
+
+//! Derive macro for `zkp_service_helper::helper::SerializationAttributes`.
+//!
+//! The macro expands to paths into `zkp_service_helper`, so its examples are compiled there: see the documentation of
+//! `SerializationAttributes`, and `src/tests/derive.rs` of that crate for every attribute in use.
+//!
+//! Field attributes:
+//!
+//! - `#[zkp(bytes)]`: the field is sent as a single binary multipart field rather than one field per element. Fields of
+//!   type `Vec<u8>` or `Option<Vec<u8>>` (or any `Default` type convertible into `bytes::Bytes`) are moved into the
+//!   form rather than copied,
+//! - `#[zkp(skip_signing)]`: the field is left out of the canonical message.
+//!
+//! Container attributes:
+//!
+//! - `#[zkp(json_body)]`: the request is sent as a JSON body rather than a multipart form,
+//! - `#[zkp(message = "json")]`: the signed message is the JSON document of the request rather than the canonical
+//!   concatenation of its values.
+//!
+//! Attributes name the field they are placed on, so they cannot refer to a field which does not exist. Fields use
+//! their serialized name, i.e. `#[serde(rename = "..")]` is taken into account. The attributes of `#[serde(flatten)]`
+//! fields and of the newtype variants of enums are taken from the `SerializationAttributes` implementation of their
+//! type, which therefore has to implement the trait as well.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse_macro_input;
+use syn::spanned::Spanned;
+use syn::Attribute;
+use syn::Data;
+use syn::DataEnum;
+use syn::DeriveInput;
+use syn::Fields;
+use syn::Ident;
+use syn::LitStr;
+use syn::Type;
+
+#[proc_macro_derive(SerializationAttributes, attributes(zkp))]
+pub fn derive_serialization_attributes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+#[derive(Default)]
+struct Container {
+    json_body: bool,
+    json_message: bool,
+}
+
+#[derive(Default)]
+struct FieldAttributes {
+    bytes: bool,
+    skip_signing: bool,
+}
+
+#[derive(Default)]
+struct SerdeAttributes {
+    flatten: bool,
+    rename: Option<String>,
+}
+
+/// Field names of a type, and the types whose attributes are merged into them.
+#[derive(Default)]
+struct Lists {
+    bytes: Vec<String>,
+    ignore: Vec<String>,
+    nested: Vec<Type>,
+}
+
+/// Statements of `take_byte_fields` for the fields of a struct or variant, pushing onto `fields`.
+#[derive(Default)]
+struct Takes {
+    statements: Vec<TokenStream2>,
+    /// Fields the statements refer to, bound by the pattern of an enum variant.
+    bindings: Vec<Ident>,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let container = container_attributes(&input.attrs)?;
+    let mut lists = Lists::default();
+    let take = match &input.data {
+        Data::Struct(data) => {
+            let takes = collect_fields(&data.fields, &mut lists, |ident| quote!(self.#ident))?;
+            let statements = takes.statements;
+            quote!(#(#statements)*)
+        }
+        Data::Enum(data) => take_from_variants(data, &mut lists)?,
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "SerializationAttributes cannot be derived for unions",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let Lists {
+        bytes,
+        ignore,
+        nested,
+    } = lists;
+    let create_message = container.json_message.then(|| {
+        quote! {
+            fn create_message<T: ::serde::Serialize>(obj: &T) -> ::zkp_service_helper::helper::Result<::std::string::String> {
+                ::zkp_service_helper::helper::json_message(obj)
+            }
+        }
+    });
+    let requires_json_body = container.json_body.then(|| {
+        quote! {
+            fn requires_json_body() -> bool {
+                true
+            }
+        }
+    });
+    Ok(quote! {
+        impl #impl_generics ::zkp_service_helper::helper::SerializationAttributes for #name #ty_generics #where_clause {
+            fn fields_which_are_bytes() -> ::std::vec::Vec<::std::string::String> {
+                let mut fields: ::std::vec::Vec<::std::string::String> =
+                    ::std::vec![#(::std::string::String::from(#bytes)),*];
+                #(
+                    for field in <#nested as ::zkp_service_helper::helper::SerializationAttributes>::fields_which_are_bytes() {
+                        if !fields.contains(&field) {
+                            fields.push(field);
+                        }
+                    }
+                )*
+                fields
+            }
+
+            fn fields_to_ignore() -> ::std::vec::Vec<::std::string::String> {
+                let mut fields: ::std::vec::Vec<::std::string::String> =
+                    ::std::vec![#(::std::string::String::from(#ignore)),*];
+                #(
+                    for field in <#nested as ::zkp_service_helper::helper::SerializationAttributes>::fields_to_ignore() {
+                        if !fields.contains(&field) {
+                            fields.push(field);
+                        }
+                    }
+                )*
+                fields
+            }
+
+            #create_message
+
+            #requires_json_body
+
+            #[allow(unused_mut)]
+            fn take_byte_fields(&mut self) -> ::std::vec::Vec<::zkp_service_helper::helper::MultipartField> {
+                let mut fields = ::std::vec::Vec::new();
+                #take
+                fields
+            }
+        }
+    })
+}
+
+/// Collects the attributes of the variants of an enum into `lists` and returns the `take_byte_fields` body matching on
+/// the variant.
+fn take_from_variants(data: &DataEnum, lists: &mut Lists) -> syn::Result<TokenStream2> {
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        reject_zkp(&variant.attrs)?;
+        let variant_name = &variant.ident;
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field = &fields.unnamed[0];
+                reject_zkp(&field.attrs)?;
+                lists.nested.push(field.ty.clone());
+                arms.push(quote! {
+                    Self::#variant_name(inner) => {
+                        fields.extend(::zkp_service_helper::helper::SerializationAttributes::take_byte_fields(inner));
+                    }
+                });
+            }
+            fields => {
+                let takes = collect_fields(fields, lists, |ident| quote!((*#ident)))?;
+                if !takes.statements.is_empty() {
+                    let Takes { statements, bindings } = takes;
+                    arms.push(quote! {
+                        Self::#variant_name { #(#bindings,)* .. } => {
+                            #(#statements)*
+                        }
+                    });
+                }
+            }
+        }
+    }
+    Ok(quote! {
+        #[allow(unreachable_patterns)]
+        match self {
+            #(#arms)*
+            _ => {}
+        }
+    })
+}
+
+/// Collects the attributes of `fields` into `lists` and returns the statements moving their byte fields out, with
+/// `access` giving the place expression of a field.
+fn collect_fields(fields: &Fields, lists: &mut Lists, access: impl Fn(&Ident) -> TokenStream2) -> syn::Result<Takes> {
+    let mut takes = Takes::default();
+    for field in fields {
+        let attributes = field_attributes(&field.attrs)?;
+        let serde = serde_attributes(&field.attrs)?;
+        if serde.flatten {
+            if attributes.bytes || attributes.skip_signing {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "`#[zkp(..)]` cannot be used on flattened fields, annotate the fields of the flattened type",
+                ));
+            }
+            let Some(ident) = &field.ident else {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "`#[serde(flatten)]` can only be used on named fields",
+                ));
+            };
+            let place = access(ident);
+            takes.statements.push(quote! {
+                fields.extend(::zkp_service_helper::helper::SerializationAttributes::take_byte_fields(&mut #place));
+            });
+            takes.bindings.push(ident.clone());
+            lists.nested.push(field.ty.clone());
+            continue;
+        }
+        if !attributes.bytes && !attributes.skip_signing {
+            continue;
+        }
+        let Some(ident) = &field.ident else {
+            return Err(syn::Error::new(field.span(), "`#[zkp(..)]` can only be used on named fields"));
+        };
+        let name = serde.rename.unwrap_or_else(|| ident.to_string());
+        let name = name.strip_prefix("r#").map(str::to_string).unwrap_or(name);
+        if attributes.bytes {
+            takes.statements.push(take_bytes(&access(ident), &field.ty, &name));
+            takes.bindings.push(ident.clone());
+            lists.bytes.push(name.clone());
+        }
+        if attributes.skip_signing {
+            lists.ignore.push(name);
+        }
+    }
+    Ok(takes)
+}
+
+/// Moves the value of a `#[zkp(bytes)]` field at `place` into a multipart field named `name`.
+fn take_bytes(place: &TokenStream2, ty: &Type, name: &str) -> TokenStream2 {
+    let push = quote! {
+        fields.push(::zkp_service_helper::helper::MultipartField {
+            name: ::std::string::String::from(#name),
+            value: ::zkp_service_helper::helper::MultipartValue::Bytes(::std::convert::Into::into(value)),
+        });
+    };
+    if is_option(ty) {
+        quote! {
+            if let ::std::option::Option::Some(value) = #place.take() {
+                #push
+            }
+        }
+    } else {
+        quote! {
+            let value = ::std::mem::take(&mut #place);
+            #push
+        }
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+fn container_attributes(attrs: &[Attribute]) -> syn::Result<Container> {
+    let mut container = Container::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("zkp")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("json_body") {
+                container.json_body = true;
+                Ok(())
+            } else if meta.path.is_ident("message") {
+                let format: LitStr = meta.value()?.parse()?;
+                match format.value().as_str() {
+                    "json" => container.json_message = true,
+                    "concat" => container.json_message = false,
+                    _ => return Err(syn::Error::new(format.span(), "expected `\"json\"` or `\"concat\"`")),
+                }
+                Ok(())
+            } else {
+                Err(meta.error("expected `json_body` or `message = \"..\"`"))
+            }
+        })?;
+    }
+    Ok(container)
+}
+
+fn field_attributes(attrs: &[Attribute]) -> syn::Result<FieldAttributes> {
+    let mut field = FieldAttributes::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("zkp")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bytes") {
+                field.bytes = true;
+                Ok(())
+            } else if meta.path.is_ident("skip_signing") {
+                field.skip_signing = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `bytes` or `skip_signing`"))
+            }
+        })?;
+    }
+    Ok(field)
+}
+
+fn reject_zkp(attrs: &[Attribute]) -> syn::Result<()> {
+    match attrs.iter().find(|attr| attr.path().is_ident("zkp")) {
+        Some(attr) => Err(syn::Error::new(
+            attr.span(),
+            "`#[zkp(..)]` is not supported here, annotate the fields of the variant's type",
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Reads the serde attributes which affect field names, skipping over all others.
+fn serde_attributes(attrs: &[Attribute]) -> syn::Result<SerdeAttributes> {
+    let mut serde = SerdeAttributes::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("flatten") {
+                serde.flatten = true;
+            } else if meta.path.is_ident("rename") && meta.input.peek(syn::Token![=]) {
+                serde.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.input.parse::<proc_macro2::TokenTree>()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(serde)
+}
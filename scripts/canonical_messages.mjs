@@ -0,0 +1,43 @@
+// Regenerates data/canonical_messages.json with the sign messages of the TypeScript helper.
+//
+//     npm install --no-save zkwasm-service-helper ethers@5
+//     node scripts/canonical_messages.mjs
+//
+// The params of every vector are kept, the message comes from the helper's `create*SignMessage` function for the
+// vector's kind and the signature from an ethers wallet, as the helper signs requests. `source` records the helper
+// version so `cargo test` then checks this crate against that version.
+import { readFileSync, writeFileSync } from "node:fs";
+import { createRequire } from "node:module";
+
+import { Wallet } from "ethers";
+import { ZkWasmUtil } from "zkwasm-service-helper";
+
+const require = createRequire(import.meta.url);
+const { version } = require("zkwasm-service-helper/package.json");
+
+const VECTORS = new URL("../data/canonical_messages.json", import.meta.url);
+
+const MESSAGES = {
+  prove: "createProvingSignMessage",
+  setup: "createAddImageSignMessage",
+  reset: "createResetImageSignMessage",
+  modify: "createModifyImageSignMessage",
+  logs: "createLogsMesssage",
+  set_maintenance_mode: "createSetMaintenanceModeSignMessage",
+  force_unprovable_to_reprocess: "createForceUnprovableToReprocessSignMessage",
+  force_dryrun_fails_to_reprocess: "createForceDryrunFailsToReprocessSignMessage",
+};
+
+const file = JSON.parse(readFileSync(VECTORS, "utf8"));
+const wallet = new Wallet(file.private_key);
+for (const vector of file.vectors) {
+  const kind = vector.name.split(":")[0];
+  const create = ZkWasmUtil[MESSAGES[kind]];
+  if (typeof create !== "function") {
+    throw new Error(`zkwasm-service-helper ${version} has no ${MESSAGES[kind]} for vector ${vector.name}`);
+  }
+  vector.message = create.call(ZkWasmUtil, vector.params);
+  vector.signature = (await wallet.signMessage(vector.message)).replace(/^0x/, "");
+}
+file.source = `zkwasm-service-helper ${version}, scripts/canonical_messages.mjs`;
+writeFileSync(VECTORS, JSON.stringify(file, null, 2) + "\n");
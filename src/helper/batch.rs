@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use ethers::types::U256;
+use futures::StreamExt;
+use tokio::time::Instant;
+
+use super::error::Result;
+use super::error::ServiceError;
+use super::helper::ZkWasmServiceHelper;
+use super::monitor::MonitorOptions;
+use super::monitor::TaskMonitor;
+use super::session::AuthenticatedSession;
+use super::signer::RequestSigner;
+use super::task_request::ProveRequest;
+use super::task_request::ProveRequestBuilder;
+use super::watch::is_terminal;
+use super::watch::TaskOutcome;
+use crate::interface::AddTaskResult;
+use crate::interface::ProofSubmitMode;
+use crate::interface::TaskStatus;
+
+/// Concurrency, spend cap and waiting of [`ZkWasmServiceHelper::submit_prove_batch`].
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct ProveBatchOptions {
+    max_concurrency: Option<usize>,
+    max_spend: Option<U256>,
+    wait: Option<(Duration, MonitorOptions)>,
+}
+
+impl ProveBatchOptions {
+    /// Largest number of tasks submitted at once, [`ZkWasmServiceHelper::max_concurrency`] by default.
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = Some(max_concurrency.max(1));
+        self
+    }
+
+    /// Caps the sum of the estimated fees of the submitted tasks, see [`ZkWasmServiceHelper::submit_prove_batch`].
+    pub fn max_spend(mut self, max_spend: U256) -> Self {
+        self.max_spend = Some(max_spend);
+        self
+    }
+
+    /// Waits up to `timeout` for the submitted tasks to reach a terminal status, polling them with a [`TaskMonitor`].
+    ///
+    /// Waiting stops early once [`MAX_FAILED_POLLS`] polls in a row fail, see [`ProveBatchReport::wait_error`].
+    pub fn wait(mut self, timeout: Duration, options: MonitorOptions) -> Self {
+        self.wait = Some((timeout, options));
+        self
+    }
+}
+
+/// Number of polls in a row which may fail before [`ZkWasmServiceHelper::submit_prove_batch`] stops waiting.
+pub const MAX_FAILED_POLLS: u32 = 3;
+
+/// Why a request of a batch has no task.
+#[derive(Debug, thiserror::Error)]
+pub enum ProveBatchError {
+    /// The request could not be built or submitted.
+    #[error(transparent)]
+    Request(#[from] ServiceError),
+    /// The request was not submitted, its estimated fee exceeds what is left of the spend cap.
+    #[error("estimated fee {fee} exceeds the remaining spend cap of {remaining}")]
+    SpendCapExceeded { fee: U256, remaining: U256 },
+}
+
+/// Result of one request of a batch.
+pub struct ProveBatchItem {
+    pub result: std::result::Result<AddTaskResult, ProveBatchError>,
+    /// How the task ended, if waiting was requested and the task was added.
+    pub outcome: Option<TaskOutcome>,
+}
+
+/// Result of [`ZkWasmServiceHelper::submit_prove_batch`].
+pub struct ProveBatchReport {
+    /// One item per request, in the order of the requests.
+    pub items: Vec<ProveBatchItem>,
+    /// Sum of the estimated fees of the submitted requests, `None` without a spend cap.
+    pub estimated_spend: Option<U256>,
+    /// Error of the last poll while waiting, if it failed. Explains why tasks timed out, e.g. when waiting stopped early
+    /// because polls kept failing.
+    pub wait_error: Option<ServiceError>,
+}
+
+impl ProveBatchReport {
+    /// The tasks which were added.
+    pub fn added(&self) -> impl Iterator<Item = &AddTaskResult> {
+        self.items.iter().filter_map(|item| item.result.as_ref().ok())
+    }
+}
+
+impl ZkWasmServiceHelper {
+    /// Signs and submits many prove tasks, at most [`ProveBatchOptions::max_concurrency`] at once.
+    ///
+    /// With a [`ProveBatchOptions::max_spend`] cap the fee of every request is estimated up front with
+    /// [`Self::query_estimated_proof_fee`], once per image, user and submit mode, using the upper bound of the
+    /// estimate. Requests are admitted in order while their fees fit into what is left of the cap, the others are not
+    /// submitted and fail with [`ProveBatchError::SpendCapExceeded`].
+    ///
+    /// A failed submission does not stop the batch, it is reported in the item of its request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if a fee cannot be estimated, in which case nothing is submitted.
+    pub async fn submit_prove_batch(
+        &self,
+        requests: impl IntoIterator<Item = ProveRequest>,
+        signer: impl RequestSigner,
+        options: ProveBatchOptions,
+    ) -> Result<ProveBatchReport> {
+        self.submit_batch(requests.into_iter().map(Ok).collect(), signer, options).await
+    }
+
+    async fn submit_batch(
+        &self,
+        requests: Vec<Result<ProveRequest>>,
+        signer: impl RequestSigner,
+        options: ProveBatchOptions,
+    ) -> Result<ProveBatchReport> {
+        let (admitted, estimated_spend) = match options.max_spend {
+            Some(max_spend) => {
+                let (admitted, spend) = self.admit(requests, max_spend).await?;
+                (admitted, Some(spend))
+            }
+            None => (
+                requests
+                    .into_iter()
+                    .map(|request| request.map_err(ProveBatchError::from))
+                    .collect(),
+                None,
+            ),
+        };
+
+        let signer = &signer;
+        let results: Vec<_> = futures::stream::iter(admitted)
+            .map(|request| async move {
+                let request = request?;
+                Ok(self.add_prove_with(request, signer).await?)
+            })
+            .buffered(options.max_concurrency.unwrap_or(self.max_concurrency()))
+            .collect()
+            .await;
+
+        let (mut outcomes, wait_error) = match options.wait {
+            Some((timeout, monitor_options)) => self.wait_for_batch(&results, timeout, monitor_options).await,
+            None => (HashMap::new(), None),
+        };
+        let items = results
+            .into_iter()
+            .map(|result| ProveBatchItem {
+                outcome: result.as_ref().ok().and_then(|added| outcomes.remove(&added.id)),
+                result,
+            })
+            .collect();
+        Ok(ProveBatchReport {
+            items,
+            estimated_spend,
+            wait_error,
+        })
+    }
+
+    /// Checks the requests against the spend cap, returns them with the ones over the cap replaced by errors and the
+    /// estimated spend of the admitted ones.
+    async fn admit(
+        &self,
+        requests: Vec<Result<ProveRequest>>,
+        max_spend: U256,
+    ) -> Result<(Vec<std::result::Result<ProveRequest, ProveBatchError>>, U256)> {
+        let mut fees: Vec<(String, String, ProofSubmitMode, U256)> = vec![];
+        let mut remaining = max_spend;
+        let mut admitted = Vec::with_capacity(requests.len());
+        for request in requests {
+            let Ok(request) = request else {
+                admitted.push(request.map_err(ProveBatchError::from));
+                continue;
+            };
+            let base = &request.params().base;
+            let fee = if let Some((.., fee)) = fees.iter().find(|(user_address, md5, mode, _)| {
+                *user_address == base.user_address && *md5 == base.md5 && *mode == base.proof_submit_mode
+            }) {
+                *fee
+            } else {
+                let fee = self
+                    .estimate_fee(base.user_address.clone(), base.md5.clone(), base.proof_submit_mode.clone())
+                    .await?;
+                fees.push((base.user_address.clone(), base.md5.clone(), base.proof_submit_mode.clone(), fee));
+                fee
+            };
+            if fee <= remaining {
+                remaining -= fee;
+                admitted.push(Ok(request));
+            } else {
+                admitted.push(Err(ProveBatchError::SpendCapExceeded { fee, remaining }));
+            }
+        }
+        Ok((admitted, max_spend - remaining))
+    }
+
+    async fn estimate_fee(
+        &self,
+        user_address: String,
+        md5: String,
+        proof_submit_mode: ProofSubmitMode,
+    ) -> Result<U256> {
+        let estimate = self
+            .query_estimated_proof_fee(user_address, md5.clone(), proof_submit_mode)
+            .await?;
+        estimate
+            .max
+            .or(estimate.min)
+            .ok_or_else(|| ServiceError::request(format!("no fee estimate for image {md5}: {}", estimate.msg)))
+    }
+
+    /// Waits for the added tasks to finish, returns the outcome of every added task by id and the error of the last
+    /// poll if it failed.
+    async fn wait_for_batch(
+        &self,
+        results: &[std::result::Result<AddTaskResult, ProveBatchError>],
+        timeout: Duration,
+        options: MonitorOptions,
+    ) -> (HashMap<String, TaskOutcome>, Option<ServiceError>) {
+        let interval = options.poll_interval();
+        let monitor = TaskMonitor::new(self.clone(), options);
+        let mut pending: HashMap<String, Option<TaskStatus>> = HashMap::new();
+        for added in results.iter().flatten() {
+            monitor.watch(added.id.clone());
+            pending.insert(added.id.clone(), None);
+        }
+        let deadline = Instant::now().checked_add(timeout);
+        let mut outcomes = HashMap::new();
+        let mut last_error = None;
+        let mut failed_polls = 0;
+        while !pending.is_empty() {
+            let Some(events) = until(deadline, monitor.poll()).await else {
+                break;
+            };
+            last_error = None;
+            let mut changed = false;
+            for event in events {
+                match event {
+                    Ok(event) => {
+                        changed = true;
+                        if is_terminal(&event.new_status) {
+                            pending.remove(&event.id);
+                            outcomes.insert(event.id, TaskOutcome::finished(*event.task));
+                        } else if let Some(last_status) = pending.get_mut(&event.id) {
+                            *last_status = Some(event.new_status);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "failed to poll the tasks of a prove batch");
+                        last_error = Some(e);
+                    }
+                }
+            }
+            failed_polls = if last_error.is_some() && !changed {
+                failed_polls + 1
+            } else {
+                0
+            };
+            if pending.is_empty() || failed_polls == MAX_FAILED_POLLS {
+                break;
+            }
+            if until(deadline, tokio::time::sleep(interval)).await.is_none() {
+                break;
+            }
+        }
+        outcomes.extend(
+            pending
+                .into_iter()
+                .map(|(id, last_status)| (id, TaskOutcome::TimedOut { last_status })),
+        );
+        (outcomes, last_error)
+    }
+}
+
+/// Runs `future` to completion, or until `deadline` if there is one.
+async fn until<F: Future>(deadline: Option<Instant>, future: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+impl<S: RequestSigner> AuthenticatedSession<S> {
+    /// Submits many prove tasks, see [`ZkWasmServiceHelper::submit_prove_batch`]. `user_address` defaults to the
+    /// signer, a request which cannot be built fails in its item without being submitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if a fee cannot be estimated, in which case nothing is submitted.
+    pub async fn submit_prove_batch(
+        &self,
+        requests: impl IntoIterator<Item = ProveRequestBuilder>,
+        options: ProveBatchOptions,
+    ) -> Result<ProveBatchReport> {
+        let requests = requests
+            .into_iter()
+            .map(|request| request.signed_by(self.user_address())?.build())
+            .collect();
+        self.helper().submit_batch(requests, self.signer(), options).await
+    }
+}
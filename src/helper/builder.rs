@@ -0,0 +1,206 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::Proxy;
+
+use super::endpoint::ZkWasmServiceEndpoint;
+use super::error::Result;
+use super::helper::ZkWasmServiceHelper;
+use super::retry::RetryPolicy;
+use super::transport::Transport;
+
+/// The `User-Agent` sent when none is configured on the builder.
+pub const DEFAULT_USER_AGENT: &str = concat!("zkp-service-helper/", env!("CARGO_PKG_VERSION"));
+
+/// The [`ZkWasmServiceHelperBuilder::max_concurrency`] used when none is configured on the builder.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+/// Builder for [`ZkWasmServiceHelper`] which configures the shared HTTP client.
+///
+/// A single pooled [`reqwest::Client`] is built from these settings and reused for every request made by the helper
+/// (and by all of its clones), so connections are kept alive between calls instead of being re-established each time.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # fn example(endpoint: String) -> zkp_service_helper::helper::Result<()> {
+/// let helper = ZkWasmServiceHelper::builder(endpoint)
+///     .connect_timeout(Duration::from_secs(5))
+///     .read_timeout(Duration::from_secs(30))
+///     .user_agent("my-service/1.0")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub struct ZkWasmServiceHelperBuilder {
+    endpoint: String,
+    client: Option<reqwest::Client>,
+    transport: Option<Arc<dyn Transport>>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxies: Vec<Proxy>,
+    user_agent: String,
+    default_headers: HeaderMap,
+    tcp_keepalive: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    retry_policy: RetryPolicy,
+    max_concurrency: usize,
+}
+
+impl ZkWasmServiceHelperBuilder {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            endpoint,
+            client: None,
+            transport: None,
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
+            proxies: vec![],
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            default_headers: HeaderMap::new(),
+            tcp_keepalive: None,
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
+            retry_policy: RetryPolicy::default(),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+        }
+    }
+
+    /// Uses an already configured [`reqwest::Client`]. All other client settings on the builder are ignored.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sends requests through a custom [`Transport`] instead of HTTP. The endpoint URL and all client settings on the
+    /// builder are ignored, only the retry policy still applies.
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Timeout for establishing a connection to the server.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for each read on an established connection.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Total timeout for a request, from connecting until the response body has been read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a proxy. May be called multiple times, proxies are tried in the order they were added.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Overrides the `User-Agent` header, which defaults to [`DEFAULT_USER_AGENT`].
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Headers sent with every request. Calling this again extends the existing set.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers.extend(headers);
+        self
+    }
+
+    /// Enables TCP keep-alive probes with the given interval.
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// How long an idle connection is kept in the pool before it is closed.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Maximum number of idle connections kept in the pool for the server.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// How failed requests are retried. Defaults to [`RetryPolicy::default`], which retries GET requests only.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Largest number of requests a bulk query such as [`ZkWasmServiceHelper::query_tasks_from_ids`] sends at once.
+    /// Defaults to [`DEFAULT_MAX_CONCURRENCY`], a limit of 0 is treated as 1.
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+
+    fn build_client(self) -> reqwest::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(self.user_agent)
+            .default_headers(self.default_headers);
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        for proxy in self.proxies {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(interval) = self.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        builder.build()
+    }
+
+    /// Builds the HTTP client and the [`ZkWasmServiceHelper`] which uses it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`super::ServiceError::Transport`] if the underlying [`reqwest::Client`] cannot be built, e.g. when the
+    /// TLS backend fails to initialise.
+    pub fn build(mut self) -> Result<ZkWasmServiceHelper> {
+        let retry_policy = std::mem::take(&mut self.retry_policy);
+        let max_concurrency = self.max_concurrency;
+        let endpoint = if let Some(transport) = self.transport.take() {
+            ZkWasmServiceEndpoint::with_transport(transport)
+        } else {
+            let endpoint = std::mem::take(&mut self.endpoint);
+            let client = match self.client.take() {
+                Some(client) => client,
+                None => self.build_client()?,
+            };
+            ZkWasmServiceEndpoint::with_client(endpoint, client)
+        };
+        Ok(
+            ZkWasmServiceHelper::from_endpoint(Arc::new(endpoint.with_retry_policy(retry_policy)))
+                .with_max_concurrency(max_concurrency),
+        )
+    }
+}
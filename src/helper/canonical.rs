@@ -0,0 +1,372 @@
+use std::fmt;
+
+use serde::ser;
+use serde::Serialize;
+
+use super::error::Result;
+use super::error::ServiceError;
+
+/// Version of the canonical message specification implemented by [`super::canonical_message`].
+///
+/// The canonical message of a request is the string which is signed for it. Unless a type overrides
+/// [`super::SerializationAttributes::create_message`], it is the concatenation, without separators, of the values of
+/// the request's fields:
+///
+/// - fields are taken in declaration order. Fields of `#[serde(flatten)]` structs are inlined at the position of the
+///   flattened field, fields which are not serialized (e.g. `None` with `skip_serializing_if`) are left out,
+/// - fields named in [`super::SerializationAttributes::fields_to_ignore`] are left out at any depth,
+/// - strings are taken as they are, integers and booleans in their decimal and `true`/`false` forms,
+/// - sequences, e.g. inputs or network ids, contribute their elements in order. Byte arrays thus become the decimal
+///   values of their bytes,
+/// - `None` and unit values contribute nothing, unit enum variants their serialized name and other variants their
+///   content.
+///
+/// Log and statistics queries as well as image modifications sign the JSON document of the request instead, with
+/// fields in declaration order.
+///
+/// The golden vectors in `data/canonical_messages.json` pin down the messages of every signed request. Any change to
+/// the messages must bump this version and the vectors.
+pub const CANONICAL_MESSAGE_VERSION: u32 = 1;
+
+/// Builds the canonical message of `obj`, skipping the fields in `ignore`. See [`CANONICAL_MESSAGE_VERSION`].
+pub(crate) fn concat_message<T: Serialize + ?Sized>(obj: &T, ignore: &[String]) -> Result<String> {
+    let mut message = String::new();
+    obj.serialize(MessageSerializer {
+        out: &mut message,
+        ignore,
+    })
+    .map_err(ServiceError::request)?;
+    Ok(message)
+}
+
+#[derive(Debug)]
+struct MessageError(String);
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot build canonical message: {}", self.0)
+    }
+}
+
+impl std::error::Error for MessageError {}
+
+impl ser::Error for MessageError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+struct MessageSerializer<'a> {
+    out: &'a mut String,
+    ignore: &'a [String],
+}
+
+impl MessageSerializer<'_> {
+    fn reborrow(&mut self) -> MessageSerializer<'_> {
+        MessageSerializer {
+            out: self.out,
+            ignore: self.ignore,
+        }
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn push(self, value: impl fmt::Display) -> std::result::Result<(), MessageError> {
+        self.out.push_str(&value.to_string());
+        Ok(())
+    }
+
+    fn is_ignored(&self, name: &str) -> bool {
+        self.ignore.iter().any(|field| field == name)
+    }
+}
+
+impl<'a> ser::Serializer for MessageSerializer<'a> {
+    type Ok = ();
+    type Error = MessageError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> std::result::Result<(), MessageError> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> std::result::Result<(), MessageError> {
+        let v = serde_json::to_string(&v).map_err(|e| MessageError(e.to_string()))?;
+        self.push(v)
+    }
+
+    fn serialize_char(self, v: char) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_str(self, v: &str) -> std::result::Result<(), MessageError> {
+        self.push(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> std::result::Result<(), MessageError> {
+        for byte in v {
+            self.out.push_str(&byte.to_string());
+        }
+        Ok(())
+    }
+
+    fn serialize_none(self) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> std::result::Result<(), MessageError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> std::result::Result<(), MessageError> {
+        self.push(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), MessageError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), MessageError> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> std::result::Result<Self, MessageError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> std::result::Result<Self, MessageError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> std::result::Result<Self, MessageError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self, MessageError> {
+        Ok(self)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> std::result::Result<MapSerializer<'a>, MessageError> {
+        Ok(MapSerializer {
+            inner: self,
+            skip_value: false,
+        })
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> std::result::Result<Self, MessageError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self, MessageError> {
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for MessageSerializer<'_> {
+    type Ok = ();
+    type Error = MessageError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MessageError> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for MessageSerializer<'_> {
+    type Ok = ();
+    type Error = MessageError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MessageError> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for MessageSerializer<'_> {
+    type Ok = ();
+    type Error = MessageError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MessageError> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for MessageSerializer<'_> {
+    type Ok = ();
+    type Error = MessageError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MessageError> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for MessageSerializer<'_> {
+    type Ok = ();
+    type Error = MessageError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), MessageError> {
+        if self.is_ignored(key) {
+            return Ok(());
+        }
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for MessageSerializer<'_> {
+    type Ok = ();
+    type Error = MessageError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), MessageError> {
+        if self.is_ignored(key) {
+            return Ok(());
+        }
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+}
+
+/// Serializes maps, which is also how serde serializes structs with `#[serde(flatten)]` fields. Keys are only used to
+/// decide whether the value is ignored.
+struct MapSerializer<'a> {
+    inner: MessageSerializer<'a>,
+    skip_value: bool,
+}
+
+impl ser::SerializeMap for MapSerializer<'_> {
+    type Ok = ();
+    type Error = MessageError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> std::result::Result<(), MessageError> {
+        let mut name = String::new();
+        key.serialize(MessageSerializer {
+            out: &mut name,
+            ignore: &[],
+        })?;
+        self.skip_value = self.inner.is_ignored(&name);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MessageError> {
+        if self.skip_value {
+            return Ok(());
+        }
+        value.serialize(self.inner.reborrow())
+    }
+
+    fn end(self) -> std::result::Result<(), MessageError> {
+        Ok(())
+    }
+}
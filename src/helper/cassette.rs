@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use reqwest::header::HeaderName;
+use reqwest::header::HeaderValue;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::error::Result;
+use super::transport::Transport;
+use super::transport::TransportRequest;
+use super::transport::TransportResponse;
+
+/// Response headers kept in a cassette. Other headers, e.g. `date`, change on every request and are dropped.
+const RECORDED_HEADERS: [&str; 2] = ["content-type", "retry-after"];
+
+/// The body of a recorded response. JSON bodies are stored as documents so that fixture files stay readable and diffable.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedBody {
+    Json(serde_json::Value),
+    Text(String),
+}
+
+impl RecordedBody {
+    fn new(body: &str) -> Self {
+        serde_json::from_str(body).map_or_else(|_| Self::Text(body.to_string()), Self::Json)
+    }
+
+    fn to_text(&self) -> String {
+        match self {
+            Self::Json(v) => v.to_string(),
+            Self::Text(v) => v.clone(),
+        }
+    }
+}
+
+/// A request and the response the server gave to it.
+///
+/// Requests are identified by method, path and query only. Request bodies and signatures are not recorded.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Interaction {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub query: String,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    pub body: RecordedBody,
+}
+
+impl Interaction {
+    fn new(request: &TransportRequest, response: &TransportResponse) -> Self {
+        let headers = RECORDED_HEADERS
+            .iter()
+            .filter_map(|name| {
+                let value = response.headers.get(*name)?.to_str().ok()?;
+                Some(((*name).to_string(), value.to_string()))
+            })
+            .collect();
+        Self {
+            method: request.method.to_string(),
+            path: request.path(),
+            query: request.query.clone(),
+            status: response.status.as_u16(),
+            headers,
+            body: RecordedBody::new(&response.body),
+        }
+    }
+
+    fn matches(&self, request: &TransportRequest) -> bool {
+        self.method == request.method.as_str() && self.path == request.path() && self.query == request.query
+    }
+
+    fn to_response(&self) -> TransportResponse {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+                headers.insert(name, value);
+            }
+        }
+        TransportResponse {
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            headers,
+            body: self.body.to_text(),
+        }
+    }
+}
+
+/// A sequence of recorded [`Interaction`]s, stored as a pretty printed JSON file.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Reads a cassette from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        serde_json::from_reader(io::BufReader::new(file)).map_err(io::Error::other)
+    }
+
+    /// Writes the cassette to a JSON file, creating its parent directories if necessary.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        json.push('\n');
+        std::fs::write(path, json)
+    }
+}
+
+enum Mode {
+    Record(Arc<dyn Transport>),
+    Replay { used: Mutex<Vec<bool>> },
+}
+
+/// [`Transport`] which records the traffic of another transport to a [`Cassette`] file, or replays a cassette without
+/// touching the network.
+///
+/// In record mode every request is forwarded to the wrapped transport and the cassette is written to disk after each
+/// response, so it is complete even if the test fails half way. In replay mode a request is answered by the first
+/// unused interaction with the same method, path and query. Once all matching interactions have been used the last one
+/// keeps being replayed, so code polling a task sees its final recorded state. Requests without any matching
+/// interaction get a `501 Not Implemented` reply.
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use zkp_service_helper::helper::CassetteTransport;
+/// # use zkp_service_helper::helper::ReqwestTransport;
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # fn example(url: String, client: reqwest::Client) -> std::io::Result<()> {
+/// // Record against a real server once ...
+/// let transport = CassetteTransport::record(Arc::new(ReqwestTransport::new(url, client)), "data/cassettes/tasks.json");
+/// // ... then replay in CI.
+/// let transport = CassetteTransport::replay("data/cassettes/tasks.json")?;
+/// let helper = ZkWasmServiceHelper::with_transport(Arc::new(transport));
+/// # Ok(())
+/// # }
+/// ```
+pub struct CassetteTransport {
+    path: PathBuf,
+    mode: Mode,
+    cassette: Mutex<Cassette>,
+}
+
+impl CassetteTransport {
+    /// Records the traffic sent through `inner` to the cassette at `path`, replacing any existing cassette.
+    pub fn record(inner: Arc<dyn Transport>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: Mode::Record(inner),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    /// Replays the cassette at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let cassette = Cassette::load(&path)?;
+        let used = Mutex::new(vec![false; cassette.interactions.len()]);
+        Ok(Self {
+            path,
+            mode: Mode::Replay { used },
+            cassette: Mutex::new(cassette),
+        })
+    }
+
+    /// The file the cassette is read from or recorded to.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the interactions recorded or loaded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    fn replay_response(&self, used: &Mutex<Vec<bool>>, request: &TransportRequest) -> TransportResponse {
+        let cassette = self.cassette.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut used = used.lock().unwrap_or_else(PoisonError::into_inner);
+        let matching: Vec<usize> = (0..cassette.interactions.len())
+            .filter(|idx| cassette.interactions[*idx].matches(request))
+            .collect();
+        let idx = matching.iter().find(|idx| !used[**idx]).or_else(|| matching.last()).copied();
+        if let Some(idx) = idx {
+            used[idx] = true;
+            return cassette.interactions[idx].to_response();
+        }
+        let query = if request.query.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", request.query)
+        };
+        TransportResponse::new(
+            StatusCode::NOT_IMPLEMENTED,
+            format!(
+                "CassetteTransport: no recorded interaction for {} {}{query} in {}",
+                request.method,
+                request.path(),
+                self.path.display()
+            ),
+        )
+    }
+}
+
+#[async_trait]
+impl Transport for CassetteTransport {
+    async fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+        match &self.mode {
+            Mode::Replay { used } => Ok(self.replay_response(used, request)),
+            Mode::Record(inner) => {
+                let response = inner.send(request).await?;
+                let mut cassette = self.cassette.lock().unwrap_or_else(PoisonError::into_inner);
+                cassette.interactions.push(Interaction::new(request, &response));
+                if let Err(e) = cassette.save(&self.path) {
+                    tracing::warn!(error = %e, path = %self.path.display(), "failed to write cassette");
+                }
+                Ok(response)
+            }
+        }
+    }
+}
@@ -1,12 +1,26 @@
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
 use serde::Deserialize;
 use serde::Serialize;
+use tracing::Instrument;
 
+use super::error::Result;
+use super::error::ServiceError;
+use super::retry::RetryPolicy;
+use super::transport::MultipartField;
+use super::transport::RequestBody;
+use super::transport::ReqwestTransport;
+use super::transport::Transport;
+use super::transport::TransportRequest;
 use super::util::IntoMultipartForm;
 use super::util::SerializationAttributes;
 
 /// Represents all available API endpoint paths for the `ZkWasm` service.
 ///
 /// Each variant corresponds to a specific endpoint and provides a way to build parameterized request paths.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TaskEndpoint {
     /// `/image`
     Image,
@@ -167,44 +181,205 @@ pub struct RequestResult<T: Serialize> {
     pub result: T,
 }
 
+/// The parts of a response envelope which describe a failed request.
+///
+/// The server reports errors either as a string or as an object with a `message` and `code`, e.g.
+/// `{"success": false, "error": {"code": 400, "message": "Invalid signature"}}`. The `result` field is ignored.
+#[derive(Deserialize)]
+struct FailureEnvelope {
+    success: Option<bool>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+impl FailureEnvelope {
+    /// Converts the envelope into a [`ServiceError::ServerRejected`] if it describes a failure.
+    fn into_error(self, status: reqwest::StatusCode) -> Option<ServiceError> {
+        let failed = self.success == Some(false) || (!status.is_success() && self.error.is_some());
+        if !failed {
+            return None;
+        }
+
+        let (code, message) = match self.error {
+            Some(serde_json::Value::String(message)) => (None, Some(message)),
+            Some(serde_json::Value::Object(mut obj)) => {
+                let code = obj.remove("code").map(|c| match c {
+                    serde_json::Value::String(c) => c,
+                    c => c.to_string(),
+                });
+                let message = ["message", "msg", "reason"]
+                    .iter()
+                    .find_map(|k| obj.remove(*k))
+                    .map(|m| match m {
+                        serde_json::Value::String(m) => m,
+                        m => m.to_string(),
+                    })
+                    .or_else(|| (!obj.is_empty()).then(|| serde_json::Value::Object(obj).to_string()));
+                (code, message)
+            }
+            Some(serde_json::Value::Null) | None => (None, None),
+            Some(other) => (None, Some(other.to_string())),
+        };
+
+        Some(ServiceError::ServerRejected {
+            status,
+            code,
+            message: message
+                .or(self.message)
+                .unwrap_or_else(|| "server replied with `success: false`".to_string()),
+        })
+    }
+}
+
 /// A client for interacting with `ZkWasm` service endpoints.
 ///
-/// This struct holds the base URL of the service and provides implementations for common HTTP operations such as `GET`
-/// and `POST`.
+/// This struct builds requests for the service and hands them to a [`Transport`], by default a [`ReqwestTransport`]
+/// holding the base URL of the service and a pooled [`reqwest::Client`] which is shared by every request sent through
+/// the endpoint. It provides implementations for common HTTP operations such as `GET` and `POST`, decodes responses
+/// and retries failed requests according to the endpoint's [`RetryPolicy`].
+#[derive(Clone)]
 pub struct ZkWasmServiceEndpoint {
-    endpoint: String,
+    transport: Arc<dyn Transport>,
+    retry: RetryPolicy,
 }
 
 impl ZkWasmServiceEndpoint {
     #[must_use]
     pub fn new(endpoint: String) -> Self {
-        Self { endpoint }
+        Self::with_client(endpoint, reqwest::Client::new())
     }
 
-    fn to_path(&self, path: &TaskEndpoint) -> String {
-        format!("{}/{}{}", self.endpoint, path.as_path(), path.path_params())
+    #[must_use]
+    pub fn with_client(endpoint: String, client: reqwest::Client) -> Self {
+        Self::with_transport(Arc::new(ReqwestTransport::new(endpoint, client)))
     }
 
-    async fn execute<V: for<'de> Deserialize<'de> + Serialize>(
-        mut req: reqwest::RequestBuilder,
-        signature: Option<String>,
-    ) -> anyhow::Result<V> {
-        if let Some(sig) = signature {
-            req = req.header("x-eth-signature", sig);
+    #[must_use]
+    pub fn with_transport(transport: Arc<dyn Transport>) -> Self {
+        Self {
+            transport,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Deserializes a response body, recording the JSON path at which decoding failed.
+    fn decode<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T> {
+        let de = &mut serde_json::Deserializer::from_str(body);
+        serde_path_to_error::deserialize(de).map_err(|e| ServiceError::Decode {
+            path: e.path().to_string(),
+            body: body.to_string(),
+            source: e.into_inner(),
+        })
+    }
+
+    /// Returns the error described by a failure envelope in `body`, if it contains one.
+    fn server_error(status: reqwest::StatusCode, body: &str) -> Option<ServiceError> {
+        serde_json::from_str::<FailureEnvelope>(body)
+            .ok()
+            .and_then(|envelope| envelope.into_error(status))
+    }
+
+    /// Turns an HTTP response into the `result` of its [`RequestResult`] envelope.
+    ///
+    /// The HTTP status is checked before the body is decoded. A failure envelope (`success: false`) is reported as
+    /// [`ServiceError::ServerRejected`], any other non-success status as [`ServiceError::HttpStatus`] with the raw body,
+    /// and a successful response which does not match `V` as [`ServiceError::Decode`].
+    pub(crate) fn decode_response<V: for<'de> Deserialize<'de> + Serialize>(
+        status: reqwest::StatusCode,
+        body: &str,
+    ) -> Result<V> {
+        if !status.is_success() {
+            return Err(Self::server_error(status, body).unwrap_or_else(|| ServiceError::HttpStatus {
+                status,
+                body: body.to_string(),
+            }));
         }
 
-        Ok(req
-            .send()
-            .await?
-            .text()
-            .await
-            .map(|resp| {
-                serde_json::from_str::<RequestResult<V>>(&resp).inspect_err(|e| {
-                    println!("Error: {e}");
-                    println!("Response: {resp}");
-                })
-            })??
-            .result)
+        let decoded = match Self::decode::<RequestResult<V>>(body) {
+            Ok(res) if res.success => return Ok(res.result),
+            Ok(_) => None,
+            Err(e) => Some(e),
+        };
+        Err(Self::server_error(status, body)
+            .or(decoded)
+            .unwrap_or_else(|| ServiceError::ServerRejected {
+                status,
+                code: None,
+                message: "server replied with `success: false`".to_string(),
+            }))
+    }
+
+    /// Creates the span which covers a single request.
+    ///
+    /// The signature itself is never recorded, only whether the request is signed. `status`, `latency_ms` and
+    /// `response_size` are filled in by [`Self::send`] once the response has been read, and `retries` by
+    /// [`Self::execute`] whenever a request is retried.
+    fn request_span(request: &TransportRequest) -> tracing::Span {
+        tracing::debug_span!(
+            "zkwasm_request",
+            method = request.method.as_str(),
+            endpoint = request.endpoint.as_path(),
+            signed = request.signature.is_some(),
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            response_size = tracing::field::Empty,
+            retries = 0u32,
+        )
+    }
+
+    /// Sends a single request and decodes its response, also returning the delay requested through `Retry-After`.
+    async fn send<V: for<'de> Deserialize<'de> + Serialize>(
+        &self,
+        request: &TransportRequest,
+    ) -> (Result<V>, Option<Duration>) {
+        let started = Instant::now();
+        let resp = match self.transport.send(request).await {
+            Ok(resp) => resp,
+            Err(e) => return (Err(e), None),
+        };
+        let retry_after = RetryPolicy::retry_after(&resp.headers);
+
+        let span = tracing::Span::current();
+        span.record("status", resp.status.as_u16());
+        span.record("latency_ms", u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX));
+        span.record("response_size", resp.body.len());
+        tracing::debug!("response received");
+        #[cfg(feature = "log-bodies")]
+        tracing::trace!(body = %super::redact::response(&resp.body), "response body");
+
+        (Self::decode_response(resp.status, &resp.body), retry_after)
+    }
+
+    /// Sends the request through the transport, retrying transient failures according to the endpoint's
+    /// [`RetryPolicy`]. Must be called within the span from [`Self::request_span`].
+    async fn execute<V: for<'de> Deserialize<'de> + Serialize>(&self, request: TransportRequest) -> Result<V> {
+        tracing::debug!(path = %request.path(), query = %request.query, "sending request");
+        let attempts = self.retry.attempts_for(&request.method);
+        let mut retries = 0;
+        loop {
+            match self.send(&request).await {
+                (Err(e), retry_after) if retries + 1 < attempts && RetryPolicy::is_retryable(&e) => {
+                    retries += 1;
+                    let delay = self.retry.delay(retries, retry_after);
+                    tracing::Span::current().record("retries", retries);
+                    tracing::warn!(
+                        error = %e,
+                        retries,
+                        delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+                        "retrying request"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                (res, _) => return res.inspect_err(|e| tracing::warn!(error = %e, "request failed")),
+            }
+        }
     }
 
     /// Sends a GET request to the given [`TaskEndpoint`] with optional parameters and signature.
@@ -223,8 +398,8 @@ impl ZkWasmServiceEndpoint {
     ///
     /// # Returns
     ///
-    /// Returns [`anyhow::Result`] containing the deserialized response of type `V` on success,
-    /// or an error if the request fails or the response cannot be deserialized.
+    /// Returns [`Result`] containing the deserialized response of type `V` on success,
+    /// or a [`ServiceError`] if the request fails or the response cannot be deserialized.
     ///
     /// # Errors
     ///
@@ -237,14 +412,16 @@ impl ZkWasmServiceEndpoint {
         path: TaskEndpoint,
         params: U,
         signature: Option<String>,
-    ) -> anyhow::Result<V> {
-        let base = self.to_path(&path);
-        let encoded = serde_urlencoded::to_string(params)?;
-        let url = format!("{}{}{}", base, if encoded.is_empty() { "" } else { "?" }, encoded);
-        println!("GET {url}");
-
-        let req = reqwest::Client::new().get(url);
-        Self::execute(req, signature).await
+    ) -> Result<V> {
+        let request = TransportRequest {
+            method: reqwest::Method::GET,
+            endpoint: path,
+            query: serde_urlencoded::to_string(params).map_err(ServiceError::request)?,
+            body: RequestBody::Empty,
+            signature,
+        };
+        let span = Self::request_span(&request);
+        self.execute(request).instrument(span).await
     }
 
     /// Sends a POST request to the given [`TaskEndpoint`] with parameters and an optional signature.
@@ -264,8 +441,8 @@ impl ZkWasmServiceEndpoint {
     ///
     /// # Returns
     ///
-    /// Returns [`anyhow::Result`] containing the deserialized response of type `V` on success,
-    /// or an error if the request fails or the response cannot be deserialized.
+    /// Returns [`Result`] containing the deserialized response of type `V` on success,
+    /// or a [`ServiceError`] if the request fails or the response cannot be deserialized.
     ///
     /// # Errors
     ///
@@ -279,17 +456,47 @@ impl ZkWasmServiceEndpoint {
         path: TaskEndpoint,
         body: U,
         signature: Option<String>,
-    ) -> anyhow::Result<V> {
-        let url = self.to_path(&path);
-        println!("POST {url}");
-
-        let post = reqwest::Client::new().post(url);
-        let req = if U::requires_json_body() {
-            post.json(&body)
-        } else {
-            post.multipart(IntoMultipartForm::into_multipart_form(body)?)
+    ) -> Result<V> {
+        // Rendered before the body is encoded, since encoding moves the byte fields out of it.
+        #[cfg(feature = "log-bodies")]
+        let logged_body = super::redact::request(&body);
+        let request = TransportRequest {
+            method: reqwest::Method::POST,
+            endpoint: path,
+            query: String::new(),
+            body: if U::requires_json_body() {
+                RequestBody::Json(serde_json::to_vec(&body).map_err(ServiceError::request)?.into())
+            } else {
+                RequestBody::Multipart(IntoMultipartForm::into_multipart_fields(body)?)
+            },
+            signature,
         };
+        let span = Self::request_span(&request);
+        async move {
+            #[cfg(feature = "log-bodies")]
+            tracing::trace!(body = %logged_body, "request body");
+            self.execute(request).await
+        }
+        .instrument(span)
+        .await
+    }
 
-        Self::execute(req, signature).await
+    /// Sends a POST request with a multipart form whose fields are already built, e.g. with parts streamed from an
+    /// [`super::UploadSource`]. Behaves like [`Self::post`] otherwise, except that the body is not logged.
+    pub(crate) async fn post_multipart<V: for<'de> Deserialize<'de> + Serialize>(
+        &self,
+        path: TaskEndpoint,
+        fields: Vec<MultipartField>,
+        signature: Option<String>,
+    ) -> Result<V> {
+        let request = TransportRequest {
+            method: reqwest::Method::POST,
+            endpoint: path,
+            query: String::new(),
+            body: RequestBody::Multipart(fields),
+            signature,
+        };
+        let span = Self::request_span(&request);
+        self.execute(request).instrument(span).await
     }
 }
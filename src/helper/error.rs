@@ -0,0 +1,59 @@
+/// Boxed error used as the source of variants which can originate from several underlying error types.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Result type returned by the helper and endpoint functions.
+pub type Result<T> = std::result::Result<T, ServiceError>;
+
+/// Error returned by every request made through [`crate::helper::ZkWasmServiceHelper`].
+///
+/// Each variant corresponds to the stage at which the request failed, so callers can decide whether a request is worth
+/// retrying or should be reported without inspecting error strings.
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    /// The request could not be sent or the response could not be read, e.g. connection refused or timed out.
+    #[error("transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// The server answered with a non-success HTTP status code.
+    #[error("server responded with HTTP {status}: {body}")]
+    HttpStatus { status: reqwest::StatusCode, body: String },
+
+    /// The response body could not be decoded into the expected type.
+    ///
+    /// `path` is the location within the JSON document at which decoding failed and `body` is the raw response.
+    #[error("failed to decode response at `{path}`: {source}")]
+    Decode {
+        path: String,
+        body: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// The server replied with a failure envelope, i.e. `success: false` and an `error` payload.
+    ///
+    /// `status` is the HTTP status of the reply, `code` and `message` are taken from the server's `error` payload.
+    #[error("server rejected request (HTTP {status}{}): {message}", code.as_ref().map(|c| format!(", code {c}")).unwrap_or_default())]
+    ServerRejected {
+        status: reqwest::StatusCode,
+        code: Option<String>,
+        message: String,
+    },
+
+    /// The request payload could not be signed.
+    #[error("failed to sign request: {0}")]
+    Signing(#[source] BoxError),
+
+    /// The request could not be built, e.g. its parameters could not be serialized.
+    #[error("failed to build request: {0}")]
+    Request(#[source] BoxError),
+}
+
+impl ServiceError {
+    pub(crate) fn request(err: impl Into<BoxError>) -> Self {
+        Self::Request(err.into())
+    }
+
+    pub(crate) fn signing(err: impl Into<BoxError>) -> Self {
+        Self::Signing(err.into())
+    }
+}
@@ -1,6 +1,23 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures::TryStreamExt;
+
+use super::builder::ZkWasmServiceHelperBuilder;
+use super::builder::DEFAULT_MAX_CONCURRENCY;
 use super::endpoint::TaskEndpoint;
 use super::endpoint::ZkWasmServiceEndpoint;
-use super::util::sign_object;
+use super::error::Result;
+use super::signer::RequestSigner;
+use super::task_request::ImageSetupRequest;
+use super::task_request::ProveRequest;
+use super::task_request::ResetRequest;
+use super::transport::MultipartValue;
+use super::transport::Transport;
+use super::util::sign_request;
+use super::util::IntoMultipartForm;
 use crate::interface::AddImageParams;
 use crate::interface::AddProveTaskRestrictions;
 use crate::interface::AddTaskResult;
@@ -9,12 +26,14 @@ use crate::interface::AppConfig;
 use crate::interface::ArchiveMetadataOverview;
 use crate::interface::ArchiveQuery;
 use crate::interface::ArchiveServerConfig;
+use crate::interface::ArchiveTaskQuery;
 use crate::interface::ArchiveVolumeMetadata;
 use crate::interface::ArchivedFinalBatchProof;
 use crate::interface::ArchivedFinalProofNetworkInfo;
 use crate::interface::AutoSubmitProof;
 use crate::interface::AutoSubmitProofQuery;
 use crate::interface::AutoSubmitProofStatus;
+use crate::interface::AutoSubmitQuery;
 use crate::interface::BaseAddImageParams;
 use crate::interface::BaseProvingParams;
 use crate::interface::BaseResetImageParams;
@@ -52,9 +71,11 @@ use crate::interface::ResetContext;
 use crate::interface::ResetImageParams;
 use crate::interface::Round1Info;
 use crate::interface::Round1InfoQuery;
+use crate::interface::Round1Query;
 use crate::interface::Round1Status;
 use crate::interface::Round2Info;
 use crate::interface::Round2InfoQuery;
+use crate::interface::Round2Query;
 use crate::interface::Round2Status;
 use crate::interface::SetMaintenanceModeParams;
 use crate::interface::StatisticsInfo;
@@ -65,6 +86,7 @@ use crate::interface::SubscriptionType;
 use crate::interface::Task;
 use crate::interface::TaskExternalHostTable;
 use crate::interface::TaskExternalHostTableParams;
+use crate::interface::TaskQuery;
 use crate::interface::TaskStatus;
 use crate::interface::TaskType;
 use crate::interface::TransactionInfo;
@@ -78,48 +100,93 @@ use crate::interface::VolumeListQuery;
 /// A helper struct for interacting with the `ZkWasm` service endpoint.
 ///
 /// This struct encapsulates a [`ZkWasmServiceEndpoint`] and provides convenience functions for interacting with the API
-/// endpoints.
+/// endpoints. The endpoint and its pooled HTTP client are reference counted, so cloning the helper is cheap and clones
+/// can be moved freely across tokio tasks.
+#[derive(Clone)]
 pub struct ZkWasmServiceHelper {
-    endpoint: ZkWasmServiceEndpoint,
+    endpoint: Arc<ZkWasmServiceEndpoint>,
+    max_concurrency: usize,
 }
 
 impl ZkWasmServiceHelper {
     #[must_use]
     pub fn new(endpoint: String) -> Self {
+        Self::from_endpoint(Arc::new(ZkWasmServiceEndpoint::new(endpoint)))
+    }
+
+    /// Returns a [`ZkWasmServiceHelperBuilder`] for configuring timeouts, proxies, headers and connection pooling.
+    pub fn builder(endpoint: String) -> ZkWasmServiceHelperBuilder {
+        ZkWasmServiceHelperBuilder::new(endpoint)
+    }
+
+    /// Creates a helper which sends its requests through `transport` with the default [`super::RetryPolicy`], e.g. a
+    /// [`super::MockTransport`] in tests. Use [`ZkWasmServiceHelperBuilder::transport`] to also configure retries.
+    #[must_use]
+    pub fn with_transport(transport: Arc<dyn Transport>) -> Self {
+        Self::from_endpoint(Arc::new(ZkWasmServiceEndpoint::with_transport(transport)))
+    }
+
+    pub(super) fn from_endpoint(endpoint: Arc<ZkWasmServiceEndpoint>) -> Self {
         Self {
-            endpoint: ZkWasmServiceEndpoint::new(endpoint),
+            endpoint,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
         }
     }
 
-    pub async fn query_image(&self, md5: String) -> anyhow::Result<Option<Image>> {
-        self.endpoint
-            .get::<_, Vec<Option<Image>>>(TaskEndpoint::Image, QueryImageParams { md5 }, None)
+    pub(super) fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Largest number of requests a bulk query sends at once, see [`ZkWasmServiceHelperBuilder::max_concurrency`].
+    #[must_use]
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    pub async fn query_image(&self, md5: String) -> Result<Option<Image>> {
+        let mut images = self.query_images(vec![md5.clone()]).await?;
+        Ok(images.remove(&md5).flatten())
+    }
+
+    /// Queries the images with the given md5s. Duplicates are queried once, one request per md5 with at most
+    /// [`Self::max_concurrency`] at once.
+    ///
+    /// The result maps every md5 to its image, `None` where the server returned no image.
+    pub async fn query_images(&self, md5s: Vec<String>) -> Result<HashMap<String, Option<Image>>> {
+        let md5s: HashSet<String> = md5s.into_iter().collect();
+        futures::stream::iter(md5s)
+            .map(|md5| async move {
+                let images: Vec<Option<Image>> = self
+                    .endpoint
+                    .get(TaskEndpoint::Image, QueryImageParams { md5: md5.clone() }, None)
+                    .await?;
+                Ok((md5, images.into_iter().next().flatten()))
+            })
+            .buffer_unordered(self.max_concurrency)
+            .try_collect()
             .await
-            .map(|mut res| res.remove(0))
     }
 
-    pub async fn query_image_binary(&self, md5: String) -> anyhow::Result<Vec<u8>> {
+    pub async fn query_image_binary(&self, md5: String) -> Result<Vec<u8>> {
         self.endpoint
             .get(TaskEndpoint::ImageBinary, QueryImageParams { md5 }, None)
             .await
     }
 
-    pub async fn query_user(&self, user_address: String) -> anyhow::Result<Option<User>> {
+    pub async fn query_user(&self, user_address: String) -> Result<Option<User>> {
         self.endpoint
             .get(TaskEndpoint::User, UserQueryParams { user_address }, None)
             .await
     }
 
-    pub async fn query_user_subscription(&self, user_address: String) -> anyhow::Result<Option<Subscription>> {
+    pub async fn query_user_subscription(&self, user_address: String) -> Result<Option<Subscription>> {
         self.endpoint
             .get(TaskEndpoint::UserSubscription, UserQueryParams { user_address }, None)
             .await
     }
 
-    pub async fn query_tx_history(
-        &self,
-        user_address: String,
-    ) -> anyhow::Result<PaginationResult<Vec<TransactionInfo>>> {
+    pub async fn query_tx_history(&self, user_address: String) -> Result<PaginationResult<Vec<TransactionInfo>>> {
         self.endpoint
             .get(
                 TaskEndpoint::Transactions,
@@ -133,10 +200,7 @@ impl ZkWasmServiceHelper {
             .await
     }
 
-    pub async fn query_deposit_history(
-        &self,
-        user_address: String,
-    ) -> anyhow::Result<PaginationResult<Vec<ERC20DepositInfo>>> {
+    pub async fn query_deposit_history(&self, user_address: String) -> Result<PaginationResult<Vec<ERC20DepositInfo>>> {
         self.endpoint
             .get(
                 TaskEndpoint::Deposits,
@@ -150,11 +214,11 @@ impl ZkWasmServiceHelper {
             .await
     }
 
-    pub async fn query_config(&self) -> anyhow::Result<AppConfig> {
+    pub async fn query_config(&self) -> Result<AppConfig> {
         self.endpoint.get(TaskEndpoint::Config, EmptyParams {}, None).await
     }
 
-    pub async fn query_statistics(&self) -> anyhow::Result<StatisticsInfo> {
+    pub async fn query_statistics(&self) -> Result<StatisticsInfo> {
         self.endpoint.get(TaskEndpoint::Statistics, EmptyParams {}, None).await
     }
 
@@ -163,7 +227,7 @@ impl ZkWasmServiceHelper {
         address: Option<String>,
         start: Option<u64>,
         total: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<ProverNode>>> {
+    ) -> Result<PaginationResult<Vec<ProverNode>>> {
         self.endpoint
             .get(
                 TaskEndpoint::NodeStatistics,
@@ -177,17 +241,17 @@ impl ZkWasmServiceHelper {
             .await
     }
 
-    pub async fn query_prover_node_summary(&self) -> anyhow::Result<ProverNodesSummary> {
+    pub async fn query_prover_node_summary(&self) -> Result<ProverNodesSummary> {
         self.endpoint.get(TaskEndpoint::ProverNodeSummary, EmptyParams {}, None).await
     }
 
-    pub async fn query_online_node_summary(&self) -> anyhow::Result<OnlineNodesSummary> {
+    pub async fn query_online_node_summary(&self) -> Result<OnlineNodesSummary> {
         self.endpoint.get(TaskEndpoint::OnlineNodesSummary, EmptyParams {}, None).await
     }
 
-    pub async fn query_logs(&self, id: String, user_address: String, private_key: String) -> anyhow::Result<String> {
+    pub async fn query_logs(&self, id: String, user_address: String, signer: impl RequestSigner) -> Result<String> {
         let params = LogQuery { id, user_address };
-        let signature = sign_object(&params, private_key).await?;
+        let signature = sign_request(&params, &params.user_address, &signer).await?;
         self.endpoint.get(TaskEndpoint::Logs, params, Some(signature)).await
     }
 
@@ -196,7 +260,7 @@ impl ZkWasmServiceHelper {
         user_address: String,
         md5: String,
         proof_submit_mode: ProofSubmitMode,
-    ) -> anyhow::Result<EstimatedProofFee> {
+    ) -> Result<EstimatedProofFee> {
         self.endpoint
             .get(
                 TaskEndpoint::EstimatedProofFee,
@@ -213,10 +277,15 @@ impl ZkWasmServiceHelper {
     pub async fn query_prover_node_timerange_stats(
         &self,
         query: ProverNodeTimeRangeStatsParams,
-    ) -> anyhow::Result<Vec<ProverNodeTimeRangeStats>> {
+    ) -> Result<Vec<ProverNodeTimeRangeStats>> {
         self.endpoint.post(TaskEndpoint::ProverNodeTimerangeStats, query, None).await
     }
 
+    /// Queries the tasks matching `query`.
+    pub async fn query_tasks_with(&self, query: TaskQuery) -> Result<PaginationResult<Vec<Task>>> {
+        self.endpoint.get(TaskEndpoint::Tasks, QueryParams::from(query), None).await
+    }
+
     pub async fn query_tasks(
         &self,
         user_address: Option<String>,
@@ -226,50 +295,47 @@ impl ZkWasmServiceHelper {
         taskstatus: Option<TaskStatus>,
         start: Option<u64>,
         total: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<Task>>> {
-        self.endpoint
-            .get(
-                TaskEndpoint::Tasks,
-                QueryParams {
-                    user_address,
-                    md5,
-                    id,
-                    tasktype,
-                    taskstatus,
-                    start,
-                    total,
-                },
-                None,
-            )
+    ) -> Result<PaginationResult<Vec<Task>>> {
+        self.query_tasks_with(TaskQuery::from(QueryParams {
+            user_address,
+            md5,
+            id,
+            tasktype,
+            taskstatus,
+            start,
+            total,
+        }))
+        .await
+    }
+
+    /// Queries the tasks with the given ids, any number of them.
+    ///
+    /// The tasks endpoint filters on a single id, so every id is its own request, and at most
+    /// [`Self::max_concurrency`] of them run at once.
+    ///
+    /// The result has one entry per id in the order of `ids`: the task, `None` where no task has the id, or the error of
+    /// its query. A failed query does not affect the other ids.
+    pub async fn query_tasks_from_ids(&self, ids: Vec<String>) -> Vec<(String, Result<Option<Task>>)> {
+        futures::stream::iter(ids)
+            .map(|id| async move {
+                let task = self.query_task_from_id(id.clone()).await;
+                (id, task)
+            })
+            .buffered(self.max_concurrency)
+            .collect()
             .await
     }
 
-    pub async fn query_tasks_from_ids(&self, ids: Vec<String>) -> anyhow::Result<Vec<Task>> {
-        const QUERY_TASKS_FROM_IDS_MAX_SIZE_INPUT: usize = 10;
-
-        if ids.len() > QUERY_TASKS_FROM_IDS_MAX_SIZE_INPUT {
-            return Err(anyhow::anyhow!(
-                "Cannot be larger than max {QUERY_TASKS_FROM_IDS_MAX_SIZE_INPUT}"
-            ));
-        }
-        let mut out = vec![];
-        for id in ids {
-            let mut tasks = self.query_tasks(None, None, Some(id), None, None, None, Some(1)).await?.data;
-            if tasks.is_empty() {
-                continue;
-            }
-            out.push(tasks.remove(0));
-        }
-        Ok(out)
+    pub async fn query_task_from_id(&self, id: String) -> Result<Option<Task>> {
+        let tasks = self.query_tasks_with(TaskQuery::new().id(id).page(0, 1)).await?.data;
+        Ok(tasks.into_iter().next())
     }
 
-    pub async fn query_task_from_id(&self, id: String) -> anyhow::Result<Option<Task>> {
-        let mut tasks = self.query_tasks_from_ids(vec![id]).await?;
-        if tasks.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(tasks.remove(0)))
-        }
+    /// Queries the concise tasks matching `query`.
+    pub async fn query_concise_tasks_with(&self, query: TaskQuery) -> Result<PaginationResult<Vec<ConciseTask>>> {
+        self.endpoint
+            .get(TaskEndpoint::ConciseTasks, QueryParams::from(query), None)
+            .await
     }
 
     pub async fn query_concise_tasks(
@@ -281,27 +347,32 @@ impl ZkWasmServiceHelper {
         taskstatus: Option<TaskStatus>,
         start: Option<u64>,
         total: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<ConciseTask>>> {
+    ) -> Result<PaginationResult<Vec<ConciseTask>>> {
+        self.query_concise_tasks_with(TaskQuery::from(QueryParams {
+            user_address,
+            md5,
+            id,
+            tasktype,
+            taskstatus,
+            start,
+            total,
+        }))
+        .await
+    }
+
+    pub async fn get_task_external_host_table(&self, id: String) -> Result<TaskExternalHostTable> {
         self.endpoint
-            .get(
-                TaskEndpoint::ConciseTasks,
-                QueryParams {
-                    user_address,
-                    md5,
-                    id,
-                    tasktype,
-                    taskstatus,
-                    start,
-                    total,
-                },
-                None,
-            )
+            .get(TaskEndpoint::TaskExternalHostTable, TaskExternalHostTableParams { id }, None)
             .await
     }
 
-    pub async fn get_task_external_host_table(&self, id: String) -> anyhow::Result<TaskExternalHostTable> {
+    /// Queries the auto submit proofs matching `query`.
+    pub async fn query_auto_submit_proofs_with(
+        &self,
+        query: AutoSubmitQuery,
+    ) -> Result<PaginationResult<Vec<AutoSubmitProof>>> {
         self.endpoint
-            .get(TaskEndpoint::TaskExternalHostTable, TaskExternalHostTableParams { id }, None)
+            .get(TaskEndpoint::Round1Batch, PaginatedQuery::from(query), None)
             .await
     }
 
@@ -314,22 +385,24 @@ impl ZkWasmServiceHelper {
         chain_id: Option<u32>,
         start: Option<u64>,
         total: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<AutoSubmitProof>>> {
+    ) -> Result<PaginationResult<Vec<AutoSubmitProof>>> {
+        self.query_auto_submit_proofs_with(AutoSubmitQuery::from(PaginatedQuery {
+            query: AutoSubmitProofQuery {
+                id,
+                task_id,
+                status,
+                circuit_size,
+                chain_id,
+            },
+            pagination: PaginationParams { total, start },
+        }))
+        .await
+    }
+
+    /// Queries the round 1 batches matching `query`.
+    pub async fn query_round1_info_with(&self, query: Round1Query) -> Result<PaginationResult<Vec<Round1Info>>> {
         self.endpoint
-            .get(
-                TaskEndpoint::Round1Batch,
-                PaginatedQuery {
-                    query: AutoSubmitProofQuery {
-                        id,
-                        task_id,
-                        status,
-                        circuit_size,
-                        chain_id,
-                    },
-                    pagination: PaginationParams { total, start },
-                },
-                None,
-            )
+            .get(TaskEndpoint::Round2Batch, PaginatedQuery::from(query), None)
             .await
     }
 
@@ -343,24 +416,26 @@ impl ZkWasmServiceHelper {
         chain_id: Option<u32>,
         start: Option<u64>,
         total: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<Round1Info>>> {
+    ) -> Result<PaginationResult<Vec<Round1Info>>> {
+        self.query_round1_info_with(Round1Query::from(PaginatedQuery {
+            query: Round1InfoQuery {
+                id,
+                // Note: `round_1_id` should never be used because it's never exposed to the user.
+                round_1_id: auto_submit_queue_id,
+                task_id,
+                status,
+                circuit_size,
+                chain_id,
+            },
+            pagination: PaginationParams { total, start },
+        }))
+        .await
+    }
+
+    /// Queries the round 2 batches matching `query`.
+    pub async fn query_round2_info_with(&self, query: Round2Query) -> Result<PaginationResult<Vec<Round2Info>>> {
         self.endpoint
-            .get(
-                TaskEndpoint::Round2Batch,
-                PaginatedQuery {
-                    query: Round1InfoQuery {
-                        id,
-                        // Note: `round_1_id` should never be used because it's never exposed to the user.
-                        round_1_id: auto_submit_queue_id,
-                        task_id,
-                        status,
-                        circuit_size,
-                        chain_id,
-                    },
-                    pagination: PaginationParams { total, start },
-                },
-                None,
-            )
+            .get(TaskEndpoint::FinalBatch, PaginatedQuery::from(query), None)
             .await
     }
 
@@ -373,26 +448,21 @@ impl ZkWasmServiceHelper {
         chain_id: Option<u32>,
         start: Option<u64>,
         total: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<Round2Info>>> {
-        self.endpoint
-            .get(
-                TaskEndpoint::FinalBatch,
-                PaginatedQuery {
-                    query: Round2InfoQuery {
-                        id,
-                        round_2_id,
-                        task_id,
-                        status,
-                        chain_id,
-                    },
-                    pagination: PaginationParams { total, start },
-                },
-                None,
-            )
-            .await
+    ) -> Result<PaginationResult<Vec<Round2Info>>> {
+        self.query_round2_info_with(Round2Query::from(PaginatedQuery {
+            query: Round2InfoQuery {
+                id,
+                round_2_id,
+                task_id,
+                status,
+                chain_id,
+            },
+            pagination: PaginationParams { total, start },
+        }))
+        .await
     }
 
-    pub async fn query_archive_summary(&self) -> anyhow::Result<ArchiveMetadataOverview> {
+    pub async fn query_archive_summary(&self) -> Result<ArchiveMetadataOverview> {
         self.endpoint.get(TaskEndpoint::ArchiveSummary, EmptyParams {}, None).await
     }
 
@@ -400,7 +470,7 @@ impl ZkWasmServiceHelper {
         &self,
         start: Option<u64>,
         limit: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<ArchiveVolumeMetadata>>> {
+    ) -> Result<PaginationResult<Vec<ArchiveVolumeMetadata>>> {
         self.endpoint
             .get(TaskEndpoint::ArchiveTaskVolumeList, VolumeListQuery { start, limit }, None)
             .await
@@ -410,7 +480,7 @@ impl ZkWasmServiceHelper {
         &self,
         start: Option<u64>,
         limit: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<ArchiveVolumeMetadata>>> {
+    ) -> Result<PaginationResult<Vec<ArchiveVolumeMetadata>>> {
         self.endpoint
             .get(
                 TaskEndpoint::ArchiveAutoSubmitTaskVolumeList,
@@ -420,7 +490,7 @@ impl ZkWasmServiceHelper {
             .await
     }
 
-    pub async fn query_archived_task(&self, task_id: String) -> anyhow::Result<Task> {
+    pub async fn query_archived_task(&self, task_id: String) -> Result<Task> {
         self.endpoint
             .get(TaskEndpoint::ArchiveTask(task_id), EmptyParams {}, None)
             .await
@@ -429,7 +499,7 @@ impl ZkWasmServiceHelper {
     pub async fn query_archived_auto_submit_networks_by_task_id(
         &self,
         task_id: String,
-    ) -> anyhow::Result<Vec<ArchivedFinalProofNetworkInfo>> {
+    ) -> Result<Vec<ArchivedFinalProofNetworkInfo>> {
         self.endpoint
             .get(TaskEndpoint::ArchiveAutoSubmitNetworks(task_id), EmptyParams {}, None)
             .await
@@ -439,7 +509,7 @@ impl ZkWasmServiceHelper {
         &self,
         task_id: String,
         chain_id: u32,
-    ) -> anyhow::Result<ArchivedFinalBatchProof> {
+    ) -> Result<ArchivedFinalBatchProof> {
         self.endpoint
             .get(
                 TaskEndpoint::ArchiveAutoSubmitInfoByTask(task_id, chain_id),
@@ -453,13 +523,13 @@ impl ZkWasmServiceHelper {
         &self,
         id: String,
         chain_id: u32,
-    ) -> anyhow::Result<ArchivedFinalBatchProof> {
+    ) -> Result<ArchivedFinalBatchProof> {
         self.endpoint
             .get(TaskEndpoint::ArchiveAutoSubmitInfo(id, chain_id), EmptyParams {}, None)
             .await
     }
 
-    pub async fn query_archive_server_config(&self) -> anyhow::Result<ArchiveServerConfig> {
+    pub async fn query_archive_server_config(&self) -> Result<ArchiveServerConfig> {
         self.endpoint.get(TaskEndpoint::ArchiveConfig, EmptyParams {}, None).await
     }
 
@@ -468,7 +538,7 @@ impl ZkWasmServiceHelper {
         volume_name: String,
         tasks_start: Option<u64>,
         tasks_limit: Option<u64>,
-    ) -> anyhow::Result<VolumeDetailResponse> {
+    ) -> Result<VolumeDetailResponse> {
         self.endpoint
             .get(
                 TaskEndpoint::ArchiveTaskVolume(volume_name),
@@ -486,7 +556,7 @@ impl ZkWasmServiceHelper {
         volume_name: String,
         tasks_start: Option<u64>,
         tasks_limit: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<ArchivedFinalBatchProof>>> {
+    ) -> Result<PaginationResult<Vec<ArchivedFinalBatchProof>>> {
         self.endpoint
             .get(
                 TaskEndpoint::ArchiveAutoSubmitVolume(volume_name),
@@ -499,6 +569,13 @@ impl ZkWasmServiceHelper {
             .await
     }
 
+    /// Queries the archived tasks matching `query`.
+    pub async fn query_archive_with(&self, query: ArchiveTaskQuery) -> Result<PaginationResult<Vec<ConciseTask>>> {
+        self.endpoint
+            .get(TaskEndpoint::ArchiveArchiveQuery, ArchiveQuery::from(query), None)
+            .await
+    }
+
     pub async fn query_archive(
         &self,
         task_id: Option<String>,
@@ -507,24 +584,19 @@ impl ZkWasmServiceHelper {
         end_timestamp: Option<String>,
         start: Option<u64>,
         limit: Option<u64>,
-    ) -> anyhow::Result<PaginationResult<Vec<ConciseTask>>> {
-        self.endpoint
-            .get(
-                TaskEndpoint::ArchiveArchiveQuery,
-                ArchiveQuery {
-                    task_id,
-                    md5,
-                    start_timestamp,
-                    end_timestamp,
-                    start,
-                    limit,
-                },
-                None,
-            )
-            .await
+    ) -> Result<PaginationResult<Vec<ConciseTask>>> {
+        self.query_archive_with(ArchiveTaskQuery::from(ArchiveQuery {
+            task_id,
+            md5,
+            start_timestamp,
+            end_timestamp,
+            start,
+            limit,
+        }))
+        .await
     }
 
-    pub async fn add_payment(&self, txhash: String) -> anyhow::Result<ERC20DepositInfo> {
+    pub async fn add_payment(&self, txhash: String) -> Result<ERC20DepositInfo> {
         self.endpoint.post(TaskEndpoint::Pay, PaymentParams { txhash }, None).await
     }
 
@@ -534,7 +606,7 @@ impl ZkWasmServiceHelper {
         subscription_type: SubscriptionType,
         duration: SubscriptionDuration,
         payment_hash: String,
-    ) -> anyhow::Result<ERC20DepositInfo> {
+    ) -> Result<ERC20DepositInfo> {
         self.endpoint
             .post(
                 TaskEndpoint::Subscribe,
@@ -549,6 +621,27 @@ impl ZkWasmServiceHelper {
             .await
     }
 
+    /// Adds the image described by `request`, see [`ImageSetupRequest::builder`].
+    ///
+    /// An image set with [`super::ImageSetupRequestBuilder::image_source`] is streamed into the request rather than
+    /// read into memory.
+    pub async fn setup_image_with(
+        &self,
+        request: ImageSetupRequest,
+        signer: impl RequestSigner,
+    ) -> Result<AddTaskResult> {
+        let (params, image_source) = request.into_parts();
+        let signature = sign_request(&params, &params.base.user_address, &signer).await?;
+        let Some(image_source) = image_source else {
+            return self.endpoint.post(TaskEndpoint::Setup, params, Some(signature)).await;
+        };
+        let mut fields = IntoMultipartForm::into_multipart_fields(params)?;
+        for field in fields.iter_mut().filter(|field| field.name == "image") {
+            field.value = MultipartValue::Stream(image_source.clone());
+        }
+        self.endpoint.post_multipart(TaskEndpoint::Setup, fields, Some(signature)).await
+    }
+
     pub async fn setup_image(
         &self,
         name: String,
@@ -563,8 +656,8 @@ impl ZkWasmServiceHelper {
         add_prove_task_restrictions: Option<AddProveTaskRestrictions>,
         inherited_merkle_data_md5: Option<String>,
         context: InitialContext,
-        private_key: String,
-    ) -> anyhow::Result<AddTaskResult> {
+        signer: impl RequestSigner,
+    ) -> Result<AddTaskResult> {
         let params = AddImageParams {
             base: BaseAddImageParams {
                 name,
@@ -581,8 +674,14 @@ impl ZkWasmServiceHelper {
             },
             context,
         };
-        let signature = sign_object(&params, private_key).await?;
-        self.endpoint.post(TaskEndpoint::Setup, params, Some(signature)).await
+        self.setup_image_with(ImageSetupRequest::from(params), signer).await
+    }
+
+    /// Adds the prove task described by `request`, see [`ProveRequest::builder`].
+    pub async fn add_prove_with(&self, request: ProveRequest, signer: impl RequestSigner) -> Result<AddTaskResult> {
+        let params = ProvingParams::from(request);
+        let signature = sign_request(&params, &params.base.user_address, &signer).await?;
+        self.endpoint.post(TaskEndpoint::Prove, params, Some(signature)).await
     }
 
     pub async fn add_prove(
@@ -593,8 +692,8 @@ impl ZkWasmServiceHelper {
         private_inputs: Vec<String>,
         proof_submit_mode: ProofSubmitMode,
         context: CustomContext,
-        private_key: String,
-    ) -> anyhow::Result<AddTaskResult> {
+        signer: impl RequestSigner,
+    ) -> Result<AddTaskResult> {
         let params = ProvingParams {
             base: BaseProvingParams {
                 user_address,
@@ -605,8 +704,7 @@ impl ZkWasmServiceHelper {
             },
             context,
         };
-        let signature = sign_object(&params, private_key).await?;
-        self.endpoint.post(TaskEndpoint::Prove, params, Some(signature)).await
+        self.add_prove_with(ProveRequest::from(params), signer).await
     }
 
     #[deprecated]
@@ -615,17 +713,24 @@ impl ZkWasmServiceHelper {
         user_address: String,
         md5: String,
         chain_id: u32,
-        private_key: String,
-    ) -> anyhow::Result<()> {
+        signer: impl RequestSigner,
+    ) -> Result<()> {
         let params = DeployParams {
             user_address,
             md5,
             chain_id,
         };
-        let signature = sign_object(&params, private_key).await?;
+        let signature = sign_request(&params, &params.user_address, &signer).await?;
         self.endpoint.post(TaskEndpoint::Deploy, params, Some(signature)).await
     }
 
+    /// Adds the reset task described by `request`, see [`ResetRequest::builder`].
+    pub async fn add_reset_with(&self, request: ResetRequest, signer: impl RequestSigner) -> Result<AddTaskResult> {
+        let params = ResetImageParams::from(request);
+        let signature = sign_request(&params, &params.base.user_address, &signer).await?;
+        self.endpoint.post(TaskEndpoint::Reset, params, Some(signature)).await
+    }
+
     pub async fn add_reset(
         &self,
         md5: String,
@@ -635,8 +740,8 @@ impl ZkWasmServiceHelper {
         auto_submit_network_ids: Vec<u32>,
         add_prove_task_restrictions: Option<AddProveTaskRestrictions>,
         context: ResetContext,
-        private_key: String,
-    ) -> anyhow::Result<AddTaskResult> {
+        signer: impl RequestSigner,
+    ) -> Result<AddTaskResult> {
         let params = ResetImageParams {
             base: BaseResetImageParams {
                 md5,
@@ -648,8 +753,7 @@ impl ZkWasmServiceHelper {
             },
             context,
         };
-        let signature = sign_object(&params, private_key).await?;
-        self.endpoint.post(TaskEndpoint::Reset, params, Some(signature)).await
+        self.add_reset_with(ResetRequest::from(params), signer).await
     }
 
     pub async fn modify_image(
@@ -658,15 +762,15 @@ impl ZkWasmServiceHelper {
         user_address: String,
         description_url: String,
         avator_url: String,
-        private_key: String,
-    ) -> anyhow::Result<String> {
+        signer: impl RequestSigner,
+    ) -> Result<String> {
         let params = ModifyImageParams {
             md5,
             user_address,
             description_url,
             avator_url,
         };
-        let signature = sign_object(&params, private_key).await?;
+        let signature = sign_request(&params, &params.user_address, &signer).await?;
         self.endpoint.post(TaskEndpoint::Modify, params, Some(signature)).await
     }
 
@@ -677,15 +781,15 @@ impl ZkWasmServiceHelper {
         nonce: u64,
         request_type: AdminRequestType,
         user_address: String,
-        private_key: String,
-    ) -> anyhow::Result<String> {
+        signer: impl RequestSigner,
+    ) -> Result<String> {
         let params = SetMaintenanceModeParams {
             mode,
             nonce,
             request_type,
             user_address,
         };
-        let signature = sign_object(&params, private_key).await?;
+        let signature = sign_request(&params, &params.user_address, &signer).await?;
         self.endpoint
             .post(TaskEndpoint::SetMaintenanceMode, params, Some(signature))
             .await
@@ -695,8 +799,8 @@ impl ZkWasmServiceHelper {
         &self,
         task_ids: Vec<String>,
         user_address: String,
-        private_key: String,
-    ) -> anyhow::Result<Vec<ObjectId>> {
+        signer: impl RequestSigner,
+    ) -> Result<Vec<ObjectId>> {
         let nonce = 0;
         let request_type = AdminRequestType::ForceTaskToReprocess;
         let params = ForceUnprovableToReprocessParams {
@@ -705,7 +809,7 @@ impl ZkWasmServiceHelper {
             request_type,
             user_address,
         };
-        let signature = sign_object(&params, private_key).await?;
+        let signature = sign_request(&params, &params.user_address, &signer).await?;
         self.endpoint
             .post(TaskEndpoint::ForceUnprovableToReprocess, params, Some(signature))
             .await
@@ -715,8 +819,8 @@ impl ZkWasmServiceHelper {
         &self,
         task_ids: Vec<String>,
         user_address: String,
-        private_key: String,
-    ) -> anyhow::Result<Vec<ObjectId>> {
+        signer: impl RequestSigner,
+    ) -> Result<Vec<ObjectId>> {
         let nonce = 0;
         let request_type = AdminRequestType::ForceTaskToReprocess;
         let params = ForceDryrunFailsToReprocessParams {
@@ -725,7 +829,7 @@ impl ZkWasmServiceHelper {
             request_type,
             user_address,
         };
-        let signature = sign_object(&params, private_key).await?;
+        let signature = sign_request(&params, &params.user_address, &signer).await?;
         self.endpoint
             .post(TaskEndpoint::ForceDryrunFailsToReprocess, params, Some(signature))
             .await
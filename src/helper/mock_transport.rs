@@ -0,0 +1,117 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Serialize;
+
+use super::endpoint::TaskEndpoint;
+use super::error::Result;
+use super::transport::Transport;
+use super::transport::TransportRequest;
+use super::transport::TransportResponse;
+
+struct Scripted {
+    /// Only requests to this endpoint path (see [`TaskEndpoint::as_path`]) match, any request matches if `None`.
+    path: Option<&'static str>,
+    response: TransportResponse,
+}
+
+/// In-memory [`Transport`] which answers requests from a script, for testing code built on the helper.
+///
+/// Each scripted response is used once. A request takes the first response scripted for its endpoint, or for any
+/// endpoint, in the order they were added. Requests without a matching response get a `501 Not Implemented` reply.
+/// All requests are recorded and can be inspected with [`Self::requests`].
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use zkp_service_helper::helper::MockTransport;
+/// # use zkp_service_helper::helper::TaskEndpoint;
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # use zkp_service_helper::interface::PaginationResult;
+/// # use zkp_service_helper::interface::Task;
+/// # fn example(task: Task) {
+/// let mock = Arc::new(MockTransport::new());
+/// mock.respond_ok_to(&TaskEndpoint::Tasks, &PaginationResult { data: vec![task], total: 1 });
+/// let helper = ZkWasmServiceHelper::with_transport(mock.clone());
+/// # }
+/// ```
+#[derive(Default)]
+pub struct MockTransport {
+    script: Mutex<VecDeque<Scripted>>,
+    requests: Mutex<Vec<TransportRequest>>,
+}
+
+impl MockTransport {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&self, path: Option<&'static str>, response: TransportResponse) -> &Self {
+        self.script
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back(Scripted { path, response });
+        self
+    }
+
+    /// Scripts a raw response for the next request to any endpoint.
+    pub fn respond(&self, response: TransportResponse) -> &Self {
+        self.push(None, response)
+    }
+
+    /// Scripts a raw response for the next request to `endpoint`.
+    pub fn respond_to(&self, endpoint: &TaskEndpoint, response: TransportResponse) -> &Self {
+        self.push(Some(endpoint.as_path()), response)
+    }
+
+    /// Scripts a successful `{"success": true, "result": ...}` reply for the next request to `endpoint`.
+    pub fn respond_ok_to<T: Serialize>(&self, endpoint: &TaskEndpoint, result: &T) -> &Self {
+        let body = serde_json::json!({ "success": true, "result": result });
+        self.respond_to(endpoint, TransportResponse::new(StatusCode::OK, body.to_string()))
+    }
+
+    /// Scripts a failure envelope `{"success": false, "error": ...}` for the next request to `endpoint`.
+    pub fn respond_error_to(&self, endpoint: &TaskEndpoint, status: StatusCode, message: &str) -> &Self {
+        let body = serde_json::json!({ "success": false, "error": { "message": message } });
+        self.respond_to(endpoint, TransportResponse::new(status, body.to_string()))
+    }
+
+    /// Returns every request received so far, in order.
+    pub fn requests(&self) -> Vec<TransportRequest> {
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Number of scripted responses which have not been used yet.
+    pub fn remaining(&self) -> usize {
+        self.script.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(request.clone());
+
+        let path = request.endpoint.as_path();
+        let mut script = self.script.lock().unwrap_or_else(PoisonError::into_inner);
+        let scripted = script
+            .iter()
+            .position(|s| s.path.is_none_or(|p| p == path))
+            .and_then(|idx| script.remove(idx));
+        Ok(scripted.map_or_else(
+            || {
+                TransportResponse::new(
+                    StatusCode::NOT_IMPLEMENTED,
+                    format!("MockTransport: no scripted response for {} {}", request.method, request.path()),
+                )
+            },
+            |s| s.response,
+        ))
+    }
+}
@@ -1,6 +1,93 @@
 pub(super) mod endpoint;
+pub use endpoint::TaskEndpoint;
 
 pub(super) mod util;
+pub use util::canonical_message;
+pub use util::json_message;
+pub use util::verify_signed_request;
+pub use util::SerializationAttributes;
+pub use zkp_service_helper_derive::SerializationAttributes;
 
+#[cfg(feature = "log-bodies")]
+pub(super) mod redact;
+
+mod canonical;
+pub use canonical::CANONICAL_MESSAGE_VERSION;
+mod error;
+pub use error::BoxError;
+pub use error::Result;
+pub use error::ServiceError;
+mod retry;
+pub use retry::RetryPolicy;
+mod batch;
+pub use batch::ProveBatchError;
+pub use batch::ProveBatchItem;
+pub use batch::ProveBatchOptions;
+pub use batch::ProveBatchReport;
+pub use batch::MAX_FAILED_POLLS;
+mod builder;
+pub use builder::ZkWasmServiceHelperBuilder;
+pub use builder::DEFAULT_MAX_CONCURRENCY;
+pub use builder::DEFAULT_USER_AGENT;
 mod helper;
 pub use helper::ZkWasmServiceHelper;
+mod monitor;
+pub use monitor::MonitorOptions;
+pub use monitor::TaskEvent;
+pub use monitor::TaskMonitor;
+mod multipart;
+mod paginate;
+pub use paginate::Paginator;
+mod pipeline;
+pub use pipeline::ImagePipeline;
+pub use pipeline::PipelineError;
+pub use pipeline::PipelineFailure;
+pub use pipeline::PipelineProgress;
+pub use pipeline::PipelineReport;
+pub use pipeline::PipelineStep;
+pub use pipeline::StepReport;
+pub use pipeline::StepState;
+mod session;
+pub use session::AuthenticatedSession;
+mod signer;
+pub use signer::ExternalSigner;
+pub use signer::RequestSigner;
+pub use signer::SignerRequest;
+pub use signer::SignerResponse;
+pub use signer::WalletSigner;
+mod task_request;
+pub use task_request::ImageSetupRequest;
+pub use task_request::ImageSetupRequestBuilder;
+pub use task_request::ProveRequest;
+pub use task_request::ProveRequestBuilder;
+pub use task_request::ResetRequest;
+pub use task_request::ResetRequestBuilder;
+mod transport;
+pub use transport::MultipartField;
+pub use transport::MultipartValue;
+pub use transport::RequestBody;
+pub use transport::ReqwestTransport;
+pub use transport::Transport;
+pub use transport::TransportRequest;
+pub use transport::TransportResponse;
+mod upload;
+pub use upload::UploadProgress;
+pub use upload::UploadSource;
+mod watch;
+pub use watch::StatusTransition;
+pub use watch::TaskFailure;
+pub use watch::TaskOutcome;
+pub use watch::TaskWatcher;
+pub use watch::WatchOptions;
+mod wasm_image;
+pub use wasm_image::WasmImage;
+pub use wasm_image::WasmValidator;
+pub use wasm_image::DEFAULT_MAX_IMAGE_SIZE;
+pub use wasm_image::ZKWASM_ENTRYPOINT;
+mod mock_transport;
+pub use mock_transport::MockTransport;
+mod cassette;
+pub use cassette::Cassette;
+pub use cassette::CassetteTransport;
+pub use cassette::Interaction;
+pub use cassette::RecordedBody;
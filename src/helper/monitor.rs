@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use futures::stream;
+use futures::Stream;
+use futures::TryStreamExt;
+
+use super::error::Result;
+use super::helper::ZkWasmServiceHelper;
+use super::paginate::Paginator;
+use super::watch::is_terminal;
+use crate::interface::Task;
+use crate::interface::TaskQuery;
+use crate::interface::TaskStatus;
+
+/// A change of the status of a task watched by a [`TaskMonitor`].
+pub struct TaskEvent {
+    pub id: String,
+    /// The status before the change, `None` when the task is seen for the first time.
+    pub old_status: Option<TaskStatus>,
+    pub new_status: TaskStatus,
+    /// The task as fetched after the change.
+    pub task: Box<Task>,
+}
+
+/// Controls how often a [`TaskMonitor`] polls.
+#[derive(Clone, Copy, Debug)]
+#[must_use]
+pub struct MonitorOptions {
+    interval: Duration,
+    page_size: u64,
+}
+
+impl Default for MonitorOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            page_size: 100,
+        }
+    }
+}
+
+impl MonitorOptions {
+    /// Delay between two polls of [`TaskMonitor::events`], 5 seconds by default.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub(crate) fn poll_interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Number of concise tasks requested per page, 100 by default.
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+}
+
+struct Watched {
+    user_address: Option<String>,
+    status: Option<TaskStatus>,
+}
+
+/// Watched tasks of one user which had the same status at the last poll.
+struct Group {
+    user_address: String,
+    status: TaskStatus,
+    ids: Vec<String>,
+}
+
+/// Watches the status of many tasks at once.
+///
+/// A task is fetched in full when it is first watched. Afterwards the watched tasks are grouped by user address and
+/// status, and each poll lists the concise tasks of every group with [`ZkWasmServiceHelper::stream_concise_tasks`],
+/// stopping as soon as all tasks of the group are found. Only tasks missing from the listing of their last status are
+/// fetched in full again, so a poll costs about one request per group rather than one per task. Tasks are unwatched
+/// once they reach a terminal status.
+///
+/// The monitor is a cheap handle, clones share the set of watched tasks, so tasks can be added while a clone is
+/// streaming events.
+///
+/// ```no_run
+/// # use futures::StreamExt;
+/// # use zkp_service_helper::helper::MonitorOptions;
+/// # use zkp_service_helper::helper::TaskMonitor;
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # use zkp_service_helper::interface::AddTaskResult;
+/// # use zkp_service_helper::interface::TaskStatus;
+/// # async fn example(helper: ZkWasmServiceHelper, res: AddTaskResult) -> zkp_service_helper::helper::Result<()> {
+/// let monitor = TaskMonitor::new(helper.clone(), MonitorOptions::default());
+/// monitor.watch(res.id);
+/// let mut events = std::pin::pin!(monitor.events());
+/// while let Some(event) = events.next().await {
+///     let event = event?;
+///     if event.new_status == TaskStatus::Done {
+///         println!("task {} is done", event.id);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TaskMonitor {
+    helper: ZkWasmServiceHelper,
+    options: MonitorOptions,
+    watched: Arc<Mutex<BTreeMap<String, Watched>>>,
+}
+
+impl TaskMonitor {
+    #[must_use]
+    pub fn new(helper: ZkWasmServiceHelper, options: MonitorOptions) -> Self {
+        Self {
+            helper,
+            options,
+            watched: Arc::default(),
+        }
+    }
+
+    /// Starts watching the task `id`. Watching a task twice has no effect.
+    pub fn watch(&self, id: impl Into<String>) {
+        self.lock().entry(id.into()).or_insert(Watched {
+            user_address: None,
+            status: None,
+        });
+    }
+
+    /// Stops watching the task `id`.
+    pub fn unwatch(&self, id: &str) {
+        self.lock().remove(id);
+    }
+
+    /// The ids of the watched tasks, in ascending order.
+    #[must_use]
+    pub fn watched(&self) -> Vec<String> {
+        self.lock().keys().cloned().collect()
+    }
+
+    /// Streams the status changes of the watched tasks, polling right away and then every
+    /// [`MonitorOptions::interval`].
+    ///
+    /// The stream does not end on its own, even when no tasks are watched. A failed request is yielded as an error and
+    /// the stream goes on with the next request.
+    pub fn events(&self) -> impl Stream<Item = Result<TaskEvent>> + Send + 'static {
+        stream::unfold(
+            (self.clone(), VecDeque::new(), false),
+            |(monitor, mut pending, mut polled)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (monitor, pending, polled)));
+                    }
+                    if polled {
+                        tokio::time::sleep(monitor.options.poll_interval()).await;
+                    }
+                    pending.extend(monitor.poll().await);
+                    polled = true;
+                }
+            },
+        )
+    }
+
+    /// Polls the watched tasks once and returns their status changes, see [`Self::events`].
+    pub async fn poll(&self) -> Vec<Result<TaskEvent>> {
+        let mut results = Vec::new();
+        let (mut fetch, groups) = self.plan();
+        for group in groups {
+            match self.changed_in(&group).await {
+                Ok(changed) => fetch.extend(changed),
+                Err(e) => results.push(Err(e)),
+            }
+        }
+        for (_, task) in self.helper.query_tasks_from_ids(fetch).await {
+            match task {
+                Ok(task) => results.extend(task.and_then(|task| self.update(task)).map(Ok)),
+                Err(e) => results.push(Err(e)),
+            }
+        }
+        results
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, Watched>> {
+        self.watched.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Splits the watched tasks into the ones never fetched and groups of the others.
+    fn plan(&self) -> (Vec<String>, Vec<Group>) {
+        let mut unknown = Vec::new();
+        let mut groups: Vec<Group> = Vec::new();
+        for (id, watched) in self.lock().iter() {
+            let (Some(user_address), Some(status)) = (&watched.user_address, &watched.status) else {
+                unknown.push(id.clone());
+                continue;
+            };
+            match groups
+                .iter_mut()
+                .find(|g| g.user_address == *user_address && g.status == *status)
+            {
+                Some(group) => group.ids.push(id.clone()),
+                None => groups.push(Group {
+                    user_address: user_address.clone(),
+                    status: status.clone(),
+                    ids: vec![id.clone()],
+                }),
+            }
+        }
+        (unknown, groups)
+    }
+
+    /// The ids of the group which are no longer listed with the status of the group.
+    async fn changed_in(&self, group: &Group) -> Result<Vec<String>> {
+        let query = TaskQuery::new().user(group.user_address.clone()).status(group.status.clone());
+        let tasks = self
+            .helper
+            .stream_concise_tasks(query, Paginator::default().page_size(self.options.page_size));
+        let mut tasks = std::pin::pin!(tasks);
+        let mut missing: HashSet<&str> = group.ids.iter().map(String::as_str).collect();
+        while !missing.is_empty() {
+            let Some(task) = tasks.try_next().await? else {
+                break;
+            };
+            missing.remove(task._id.oid.as_str());
+        }
+        Ok(group.ids.iter().filter(|id| missing.contains(id.as_str())).cloned().collect())
+    }
+
+    /// Records the status of a fetched task, returns the event if it changed.
+    fn update(&self, task: Task) -> Option<TaskEvent> {
+        let mut watched = self.lock();
+        let id = task._id.oid.clone();
+        let entry = watched.get_mut(&id)?;
+        entry.user_address = Some(task.user_address.clone());
+        if entry.status.as_ref() == Some(&task.status) {
+            return None;
+        }
+        let old_status = entry.status.replace(task.status.clone());
+        if is_terminal(&task.status) {
+            watched.remove(&id);
+        }
+        Some(TaskEvent {
+            id,
+            old_status,
+            new_status: task.status.clone(),
+            task: Box::new(task),
+        })
+    }
+}
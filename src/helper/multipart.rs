@@ -0,0 +1,871 @@
+use std::fmt;
+
+use serde::ser;
+use serde::ser::Impossible;
+use serde::Serialize;
+
+use super::error::Result;
+use super::error::ServiceError;
+use super::transport::MultipartField;
+use super::transport::MultipartValue;
+
+/// Converts `params` into the fields of a multipart form, in declaration order.
+///
+/// - primitive fields become one text part, `None` fields no part at all,
+/// - sequences become one part per element, so repeated fields carry arrays such as inputs or network ids,
+/// - nested structs, maps and enum variants with data, including those found in sequences, become a JSON text part,
+/// - fields named in `bytes_fields` become a single binary part. Parts already taken out of `params` (see
+///   [`super::SerializationAttributes::take_byte_fields`]) are used as they are, otherwise the bytes are collected
+///   from the serialized sequence.
+pub(crate) fn to_fields<T: Serialize + ?Sized>(
+    params: &T,
+    bytes_fields: &[String],
+    taken: Vec<MultipartField>,
+) -> Result<Vec<MultipartField>> {
+    let mut form = FormSerializer {
+        fields: Vec::new(),
+        bytes_fields,
+        taken,
+        key: None,
+    };
+    params.serialize(&mut form).map_err(ServiceError::request)?;
+    // Taken fields which are skipped once empty, e.g. `Option`s with `skip_serializing_if`, are still sent.
+    form.fields.append(&mut form.taken);
+    Ok(form.fields)
+}
+
+#[derive(Debug)]
+enum MultipartError {
+    /// The value is not a primitive, it is sent as JSON text instead.
+    Compound,
+    Message(String),
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compound => f.write_str("value is not a primitive"),
+            Self::Message(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for MultipartError {}
+
+impl ser::Error for MultipartError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Message(msg.to_string())
+    }
+}
+
+fn top_level_error() -> MultipartError {
+    MultipartError::Message("Top level of json must be Object type".to_string())
+}
+
+fn json_text<T: Serialize + ?Sized>(value: &T) -> std::result::Result<MultipartValue, MultipartError> {
+    serde_json::to_string(value)
+        .map(MultipartValue::Text)
+        .map_err(|e| MultipartError::Message(e.to_string()))
+}
+
+struct FormSerializer<'a> {
+    fields: Vec<MultipartField>,
+    bytes_fields: &'a [String],
+    taken: Vec<MultipartField>,
+    key: Option<String>,
+}
+
+impl FormSerializer<'_> {
+    fn push_field<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> std::result::Result<(), MultipartError> {
+        if self.bytes_fields.iter().any(|field| field == name) {
+            if let Some(idx) = self.taken.iter().position(|field| field.name == name) {
+                let field = self.taken.remove(idx);
+                self.fields.push(field);
+            } else if let Some(bytes) = value.serialize(ByteSerializer)? {
+                self.push(name, MultipartValue::Bytes(bytes.into()));
+            }
+            return Ok(());
+        }
+        match value.serialize(ValueSerializer { nested: false }) {
+            Ok(values) => values.into_iter().for_each(|value| self.push(name, value)),
+            Err(MultipartError::Compound) => {
+                let value = json_text(value)?;
+                self.push(name, value);
+            }
+            Err(e) => return Err(e),
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, name: &str, value: MultipartValue) {
+        self.fields.push(MultipartField {
+            name: name.to_string(),
+            value,
+        });
+    }
+}
+
+impl ser::Serializer for &mut FormSerializer<'_> {
+    type Ok = ();
+    type Error = MultipartError;
+    type SerializeSeq = Impossible<(), MultipartError>;
+    type SerializeTuple = Impossible<(), MultipartError>;
+    type SerializeTupleStruct = Impossible<(), MultipartError>;
+    type SerializeTupleVariant = Impossible<(), MultipartError>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), MultipartError>;
+
+    fn serialize_bool(self, _: bool) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_i8(self, _: i8) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_i16(self, _: i16) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_i32(self, _: i32) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_i64(self, _: i64) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_u8(self, _: u8) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_u16(self, _: u16) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_u32(self, _: u32) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_u64(self, _: u64) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_f32(self, _: f32) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_f64(self, _: f64) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_char(self, _: char) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_str(self, _: &str) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_none(self) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> std::result::Result<(), MultipartError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    // Unit structs, such as `EmptyParams`, have no fields.
+    fn serialize_unit_struct(self, _: &'static str) -> std::result::Result<(), MultipartError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), MultipartError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> std::result::Result<(), MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> std::result::Result<Self::SerializeSeq, MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_tuple(self, _: usize) -> std::result::Result<Self::SerializeTuple, MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeTupleStruct, MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeTupleVariant, MultipartError> {
+        Err(top_level_error())
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> std::result::Result<Self, MultipartError> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> std::result::Result<Self, MultipartError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeStructVariant, MultipartError> {
+        Err(top_level_error())
+    }
+}
+
+impl ser::SerializeStruct for &mut FormSerializer<'_> {
+    type Ok = ();
+    type Error = MultipartError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), MultipartError> {
+        self.push_field(key, value)
+    }
+
+    fn end(self) -> std::result::Result<(), MultipartError> {
+        Ok(())
+    }
+}
+
+/// Maps are how serde serializes structs with `#[serde(flatten)]` fields, their keys are the field names.
+impl ser::SerializeMap for &mut FormSerializer<'_> {
+    type Ok = ();
+    type Error = MultipartError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> std::result::Result<(), MultipartError> {
+        let Some(MultipartValue::Text(key)) = key.serialize(ValueSerializer { nested: true })?.pop() else {
+            return Err(MultipartError::Message("Field names must be strings".to_string()));
+        };
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MultipartError> {
+        let key = self.key.take().unwrap_or_default();
+        self.push_field(&key, value)
+    }
+
+    fn end(self) -> std::result::Result<(), MultipartError> {
+        Ok(())
+    }
+}
+
+/// Serializes the value of a field into its parts. Nested values, i.e. elements of a sequence, must be primitives.
+struct ValueSerializer {
+    nested: bool,
+}
+
+impl ValueSerializer {
+    #[allow(clippy::unnecessary_wraps)]
+    fn text(value: String) -> std::result::Result<Vec<MultipartValue>, MultipartError> {
+        Ok(vec![MultipartValue::Text(value)])
+    }
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Vec<MultipartValue>;
+    type Error = MultipartError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = Impossible<Vec<MultipartValue>, MultipartError>;
+    type SerializeMap = Impossible<Vec<MultipartValue>, MultipartError>;
+    type SerializeStruct = Impossible<Vec<MultipartValue>, MultipartError>;
+    type SerializeStructVariant = Impossible<Vec<MultipartValue>, MultipartError>;
+
+    fn serialize_bool(self, v: bool) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> std::result::Result<Self::Ok, MultipartError> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> std::result::Result<Self::Ok, MultipartError> {
+        Ok(vec![json_text(&v)?])
+    }
+
+    fn serialize_char(self, v: char) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(v.to_string())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> std::result::Result<Self::Ok, MultipartError> {
+        Ok(vec![MultipartValue::Bytes(v.to_vec().into())])
+    }
+
+    fn serialize_none(self) -> std::result::Result<Self::Ok, MultipartError> {
+        if self.nested {
+            Err(MultipartError::Compound)
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> std::result::Result<Self::Ok, MultipartError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> std::result::Result<Self::Ok, MultipartError> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> std::result::Result<Self::Ok, MultipartError> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> std::result::Result<Self::Ok, MultipartError> {
+        Self::text(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> std::result::Result<Self::Ok, MultipartError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(MultipartError::Compound)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> std::result::Result<SeqSerializer, MultipartError> {
+        if self.nested {
+            return Err(MultipartError::Compound);
+        }
+        Ok(SeqSerializer {
+            values: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> std::result::Result<SeqSerializer, MultipartError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> std::result::Result<SeqSerializer, MultipartError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeTupleVariant, MultipartError> {
+        Err(MultipartError::Compound)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> std::result::Result<Self::SerializeMap, MultipartError> {
+        Err(MultipartError::Compound)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> std::result::Result<Self::SerializeStruct, MultipartError> {
+        Err(MultipartError::Compound)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeStructVariant, MultipartError> {
+        Err(MultipartError::Compound)
+    }
+}
+
+/// Collects the elements of a sequence, sending elements which are not primitives as JSON text.
+struct SeqSerializer {
+    values: Vec<MultipartValue>,
+}
+
+impl SeqSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MultipartError> {
+        match value.serialize(ValueSerializer { nested: true }) {
+            Ok(values) => self.values.extend(values),
+            Err(MultipartError::Compound) => self.values.push(json_text(value)?),
+            Err(e) => return Err(e),
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Vec<MultipartValue>;
+    type Error = MultipartError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MultipartError> {
+        self.push(value)
+    }
+
+    fn end(self) -> std::result::Result<Self::Ok, MultipartError> {
+        Ok(self.values)
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Vec<MultipartValue>;
+    type Error = MultipartError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MultipartError> {
+        self.push(value)
+    }
+
+    fn end(self) -> std::result::Result<Self::Ok, MultipartError> {
+        Ok(self.values)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Vec<MultipartValue>;
+    type Error = MultipartError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MultipartError> {
+        self.push(value)
+    }
+
+    fn end(self) -> std::result::Result<Self::Ok, MultipartError> {
+        Ok(self.values)
+    }
+}
+
+/// Collects the bytes of a byte field which was not taken out of the params. `None` if the field is `None`.
+struct ByteSerializer;
+
+fn not_a_byte() -> MultipartError {
+    MultipartError::Message("Bytes field can only have u8 values".to_string())
+}
+
+impl ser::Serializer for ByteSerializer {
+    type Ok = Option<Vec<u8>>;
+    type Error = MultipartError;
+    type SerializeSeq = ByteSeqSerializer;
+    type SerializeTuple = Impossible<Self::Ok, MultipartError>;
+    type SerializeTupleStruct = Impossible<Self::Ok, MultipartError>;
+    type SerializeTupleVariant = Impossible<Self::Ok, MultipartError>;
+    type SerializeMap = Impossible<Self::Ok, MultipartError>;
+    type SerializeStruct = Impossible<Self::Ok, MultipartError>;
+    type SerializeStructVariant = Impossible<Self::Ok, MultipartError>;
+
+    fn serialize_bool(self, _: bool) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_i8(self, _: i8) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_i16(self, _: i16) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_i32(self, _: i32) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_i64(self, _: i64) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_u8(self, _: u8) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_u16(self, _: u16) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_u32(self, _: u32) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_u64(self, _: u64) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_f32(self, _: f32) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_f64(self, _: f64) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_char(self, _: char) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_str(self, _: &str) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> std::result::Result<Self::Ok, MultipartError> {
+        Ok(Some(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> std::result::Result<Self::Ok, MultipartError> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> std::result::Result<Self::Ok, MultipartError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> std::result::Result<Self::Ok, MultipartError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> std::result::Result<Self::Ok, MultipartError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> std::result::Result<Self::Ok, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> std::result::Result<ByteSeqSerializer, MultipartError> {
+        Ok(ByteSeqSerializer {
+            bytes: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, _: usize) -> std::result::Result<Self::SerializeTuple, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeTupleStruct, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeTupleVariant, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> std::result::Result<Self::SerializeMap, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> std::result::Result<Self::SerializeStruct, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeStructVariant, MultipartError> {
+        Err(not_a_byte())
+    }
+}
+
+struct ByteSeqSerializer {
+    bytes: Vec<u8>,
+}
+
+impl ser::SerializeSeq for ByteSeqSerializer {
+    type Ok = Option<Vec<u8>>;
+    type Error = MultipartError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), MultipartError> {
+        // Elements are serialized through `serialize_u8`, any other kind of value is rejected by `u8`'s serializer.
+        let byte = value.serialize(ByteElementSerializer)?;
+        self.bytes.push(byte);
+        Ok(())
+    }
+
+    fn end(self) -> std::result::Result<Self::Ok, MultipartError> {
+        Ok(Some(self.bytes))
+    }
+}
+
+/// Accepts a single `u8`.
+struct ByteElementSerializer;
+
+impl ser::Serializer for ByteElementSerializer {
+    type Ok = u8;
+    type Error = MultipartError;
+    type SerializeSeq = Impossible<u8, MultipartError>;
+    type SerializeTuple = Impossible<u8, MultipartError>;
+    type SerializeTupleStruct = Impossible<u8, MultipartError>;
+    type SerializeTupleVariant = Impossible<u8, MultipartError>;
+    type SerializeMap = Impossible<u8, MultipartError>;
+    type SerializeStruct = Impossible<u8, MultipartError>;
+    type SerializeStructVariant = Impossible<u8, MultipartError>;
+
+    fn serialize_bool(self, _: bool) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_i8(self, v: i8) -> std::result::Result<u8, MultipartError> {
+        u8::try_from(v).map_err(|_| not_a_byte())
+    }
+
+    fn serialize_i16(self, v: i16) -> std::result::Result<u8, MultipartError> {
+        u8::try_from(v).map_err(|_| not_a_byte())
+    }
+
+    fn serialize_i32(self, v: i32) -> std::result::Result<u8, MultipartError> {
+        u8::try_from(v).map_err(|_| not_a_byte())
+    }
+
+    fn serialize_i64(self, v: i64) -> std::result::Result<u8, MultipartError> {
+        u8::try_from(v).map_err(|_| not_a_byte())
+    }
+
+    fn serialize_u8(self, v: u8) -> std::result::Result<u8, MultipartError> {
+        Ok(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> std::result::Result<u8, MultipartError> {
+        u8::try_from(v).map_err(|_| not_a_byte())
+    }
+
+    fn serialize_u32(self, v: u32) -> std::result::Result<u8, MultipartError> {
+        u8::try_from(v).map_err(|_| not_a_byte())
+    }
+
+    fn serialize_u64(self, v: u64) -> std::result::Result<u8, MultipartError> {
+        u8::try_from(v).map_err(|_| not_a_byte())
+    }
+
+    fn serialize_f32(self, _: f32) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_f64(self, _: f64) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_char(self, _: char) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_str(self, _: &str) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_none(self) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_unit(self) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> std::result::Result<u8, MultipartError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> std::result::Result<u8, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> std::result::Result<Self::SerializeSeq, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_tuple(self, _: usize) -> std::result::Result<Self::SerializeTuple, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeTupleStruct, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeTupleVariant, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> std::result::Result<Self::SerializeMap, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> std::result::Result<Self::SerializeStruct, MultipartError> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> std::result::Result<Self::SerializeStructVariant, MultipartError> {
+        Err(not_a_byte())
+    }
+}
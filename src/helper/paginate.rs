@@ -0,0 +1,207 @@
+use std::future::Future;
+
+use futures::stream;
+use futures::Stream;
+use futures::TryStreamExt;
+
+use super::error::Result;
+use super::error::ServiceError;
+use super::helper::ZkWasmServiceHelper;
+use crate::interface::ArchiveTaskQuery;
+use crate::interface::ArchiveVolumeMetadata;
+use crate::interface::AutoSubmitProof;
+use crate::interface::AutoSubmitQuery;
+use crate::interface::ConciseTask;
+use crate::interface::PaginationResult;
+use crate::interface::ProverNode;
+use crate::interface::Round1Info;
+use crate::interface::Round1Query;
+use crate::interface::Round2Info;
+use crate::interface::Round2Query;
+use crate::interface::Task;
+use crate::interface::TaskQuery;
+
+/// Page size and item limit of a paginated stream, see [`ZkWasmServiceHelper::stream_tasks`] and friends.
+///
+/// Pages are fetched lazily, one request per page, as the stream is polled. The stream ends once the server returns an
+/// empty page or the items fetched reach the `total` of the latest page, so it copes with records being added or removed
+/// while iterating. Offsets are not stable across such changes: on lists sorted newest first an item may be yielded
+/// twice when records are added concurrently, or skipped when records are removed.
+#[derive(Clone, Copy, Debug)]
+pub struct Paginator {
+    page_size: u64,
+    max_items: Option<u64>,
+}
+
+impl Default for Paginator {
+    fn default() -> Self {
+        Self {
+            page_size: 50,
+            max_items: None,
+        }
+    }
+}
+
+struct PageState<F> {
+    fetch: F,
+    start: u64,
+    yielded: u64,
+    done: bool,
+}
+
+impl Paginator {
+    /// Sets the number of items requested per page. Defaults to 50, a page size of 0 is treated as 1.
+    #[must_use]
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Stops the stream after `max_items` items, without fetching more than needed.
+    #[must_use]
+    pub fn max_items(mut self, max_items: u64) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Streams the items of any paginated query.
+    ///
+    /// `fetch` is called with the `start` offset and the number of items to fetch for each page. The stream ends after
+    /// the first error.
+    pub fn stream<T, F, Fut>(self, fetch: F) -> impl Stream<Item = Result<T>>
+    where
+        F: FnMut(u64, u64) -> Fut,
+        Fut: Future<Output = Result<PaginationResult<Vec<T>>>>,
+    {
+        let state = PageState {
+            fetch,
+            start: 0,
+            yielded: 0,
+            done: false,
+        };
+        stream::try_unfold(state, move |mut state| {
+            let remaining = self.max_items.map_or(u64::MAX, |max| max.saturating_sub(state.yielded));
+            let limit = self.page_size.min(remaining);
+            let page = (!state.done && limit > 0).then(|| (state.fetch)(state.start, limit));
+            async move {
+                let Some(page) = page else {
+                    return Ok::<_, ServiceError>(None);
+                };
+                let page = page.await?;
+                let mut data = page.data;
+                data.truncate(usize::try_from(limit).unwrap_or(usize::MAX));
+                let fetched = u64::try_from(data.len()).unwrap_or(u64::MAX);
+                state.start += fetched;
+                state.yielded += fetched;
+                state.done = fetched == 0 || state.start >= page.total;
+                Ok(Some((stream::iter(data.into_iter().map(Ok)), state)))
+            }
+        })
+        .try_flatten()
+    }
+}
+
+impl ZkWasmServiceHelper {
+    /// Streams the tasks matching `query`. The page set on `query` is ignored, pages are fetched as set by `paginator`.
+    pub fn stream_tasks(
+        &self,
+        query: TaskQuery,
+        paginator: Paginator,
+    ) -> impl Stream<Item = Result<Task>> + Send + 'static {
+        let zkh = self.clone();
+        paginator.stream(move |start, total| {
+            let (zkh, query) = (zkh.clone(), query.clone().page(start, total));
+            async move { zkh.query_tasks_with(query).await }
+        })
+    }
+
+    /// Streams the concise tasks matching `query`, see [`Self::stream_tasks`].
+    pub fn stream_concise_tasks(
+        &self,
+        query: TaskQuery,
+        paginator: Paginator,
+    ) -> impl Stream<Item = Result<ConciseTask>> + Send + 'static {
+        let zkh = self.clone();
+        paginator.stream(move |start, total| {
+            let (zkh, query) = (zkh.clone(), query.clone().page(start, total));
+            async move { zkh.query_concise_tasks_with(query).await }
+        })
+    }
+
+    /// Streams the prover nodes matching the filters of [`Self::query_node_statistics`].
+    pub fn stream_node_statistics(
+        &self,
+        address: Option<String>,
+        paginator: Paginator,
+    ) -> impl Stream<Item = Result<ProverNode>> + Send + 'static {
+        let zkh = self.clone();
+        paginator.stream(move |start, total| {
+            let (zkh, address) = (zkh.clone(), address.clone());
+            async move { zkh.query_node_statistics(address, Some(start), Some(total)).await }
+        })
+    }
+
+    /// Streams the auto submit proofs matching `query`, see [`Self::stream_tasks`].
+    pub fn stream_auto_submit_proofs(
+        &self,
+        query: AutoSubmitQuery,
+        paginator: Paginator,
+    ) -> impl Stream<Item = Result<AutoSubmitProof>> + Send + 'static {
+        let zkh = self.clone();
+        paginator.stream(move |start, total| {
+            let (zkh, query) = (zkh.clone(), query.clone().page(start, total));
+            async move { zkh.query_auto_submit_proofs_with(query).await }
+        })
+    }
+
+    /// Streams the round 1 batches matching `query`, see [`Self::stream_tasks`].
+    pub fn stream_round1_info(
+        &self,
+        query: Round1Query,
+        paginator: Paginator,
+    ) -> impl Stream<Item = Result<Round1Info>> + Send + 'static {
+        let zkh = self.clone();
+        paginator.stream(move |start, total| {
+            let (zkh, query) = (zkh.clone(), query.clone().page(start, total));
+            async move { zkh.query_round1_info_with(query).await }
+        })
+    }
+
+    /// Streams the round 2 batches matching `query`, see [`Self::stream_tasks`].
+    pub fn stream_round2_info(
+        &self,
+        query: Round2Query,
+        paginator: Paginator,
+    ) -> impl Stream<Item = Result<Round2Info>> + Send + 'static {
+        let zkh = self.clone();
+        paginator.stream(move |start, total| {
+            let (zkh, query) = (zkh.clone(), query.clone().page(start, total));
+            async move { zkh.query_round2_info_with(query).await }
+        })
+    }
+
+    /// Streams the archive task volumes, see [`Self::query_archive_task_volume_list`].
+    pub fn stream_archive_task_volume_list(
+        &self,
+        paginator: Paginator,
+    ) -> impl Stream<Item = Result<ArchiveVolumeMetadata>> + Send + 'static {
+        let zkh = self.clone();
+        paginator.stream(move |start, limit| {
+            let zkh = zkh.clone();
+            async move { zkh.query_archive_task_volume_list(Some(start), Some(limit)).await }
+        })
+    }
+
+    /// Streams the archived tasks matching `query`, see [`Self::stream_tasks`].
+    pub fn stream_archive(
+        &self,
+        query: ArchiveTaskQuery,
+        paginator: Paginator,
+    ) -> impl Stream<Item = Result<ConciseTask>> + Send + 'static {
+        let zkh = self.clone();
+        paginator.stream(move |start, limit| {
+            let (zkh, query) = (zkh.clone(), query.clone().page(start, limit));
+            async move { zkh.query_archive_with(query).await }
+        })
+    }
+}
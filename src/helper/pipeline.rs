@@ -0,0 +1,421 @@
+use std::fmt;
+use std::sync::Arc;
+
+use super::error::ServiceError;
+use super::session::AuthenticatedSession;
+use super::signer::RequestSigner;
+use super::task_request::ImageSetupRequestBuilder;
+use super::task_request::ProveRequestBuilder;
+use super::task_request::ResetRequestBuilder;
+use super::watch::status_name;
+use super::watch::TaskFailure;
+use super::watch::TaskOutcome;
+use super::watch::TaskWatcher;
+use super::watch::WatchOptions;
+use crate::interface::Task;
+use crate::interface::TaskQuery;
+use crate::interface::TaskStatus;
+use crate::interface::TaskType;
+
+/// The kind of a step of an [`ImagePipeline`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PipelineStep {
+    Setup,
+    Prove,
+    Reset,
+    Modify,
+}
+
+impl fmt::Display for PipelineStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Setup => "setup",
+            Self::Prove => "prove",
+            Self::Reset => "reset",
+            Self::Modify => "modify",
+        })
+    }
+}
+
+/// What happened to a step, reported to [`ImagePipeline::on_progress`].
+pub enum StepState {
+    Started,
+    /// The setup step found the image already added, nothing was submitted. The step waits for the Setup task of the
+    /// image instead.
+    Skipped,
+    /// The task of the step was added.
+    Submitted {
+        task_id: String,
+    },
+    /// The status of the task of the step changed while waiting for it.
+    StatusChanged {
+        task_id: String,
+        status: TaskStatus,
+    },
+    /// The step finished, after its task is done if it added one.
+    Finished,
+}
+
+/// Progress of an [`ImagePipeline`], `step` is the index of the step in the order the steps were added.
+pub struct PipelineProgress {
+    pub step: usize,
+    pub kind: PipelineStep,
+    pub state: StepState,
+}
+
+/// Result of a step of a successful pipeline run.
+pub struct StepReport {
+    pub kind: PipelineStep,
+    /// The finished task of the step, the Setup task of the image for a skipped setup, `None` for modify steps which add
+    /// no task.
+    pub task: Option<Box<Task>>,
+}
+
+/// Result of a successful [`ImagePipeline::run`].
+pub struct PipelineReport {
+    /// The md5 of the image the pipeline worked on, empty if it has neither a setup step nor [`ImagePipeline::md5`].
+    pub md5: String,
+    /// One report per step, in order.
+    pub steps: Vec<StepReport>,
+}
+
+/// Why a step of an [`ImagePipeline`] failed.
+pub enum PipelineFailure {
+    /// A request of the step failed.
+    Request(ServiceError),
+    /// The task of the step ended in a failed status.
+    TaskFailed { task_id: String, failure: TaskFailure },
+    /// The task of the step did not finish within [`WatchOptions::timeout`].
+    TimedOut {
+        task_id: String,
+        last_status: Option<TaskStatus>,
+    },
+}
+
+/// Error of [`ImagePipeline::run`], the pipeline stops at the first step which fails.
+pub struct PipelineError {
+    pub step: usize,
+    pub kind: PipelineStep,
+    pub failure: PipelineFailure,
+    /// Reports of the steps which finished before the failure.
+    pub completed: Vec<StepReport>,
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pipeline step {} ({}) failed: ", self.step, self.kind)?;
+        match &self.failure {
+            PipelineFailure::Request(e) => write!(f, "{e}"),
+            PipelineFailure::TaskFailed { task_id, failure } => {
+                write!(f, "task {task_id} ended with status {}", status_name(&failure.status))?;
+                if let Some(message) = &failure.status_message {
+                    write!(f, ": {message}")?;
+                }
+                Ok(())
+            }
+            PipelineFailure::TimedOut { task_id, last_status } => write!(
+                f,
+                "task {task_id} did not finish in time, last status {}",
+                last_status.as_ref().map_or("unknown", status_name)
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PipelineError")
+            .field("step", &self.step)
+            .field("kind", &self.kind)
+            .field("message", &self.to_string())
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::error::Error for PipelineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.failure {
+            PipelineFailure::Request(e) => Some(e),
+            PipelineFailure::TaskFailed { .. } | PipelineFailure::TimedOut { .. } => None,
+        }
+    }
+}
+
+enum Step {
+    Setup(ImageSetupRequestBuilder),
+    Prove(ProveRequestBuilder),
+    Reset(ResetRequestBuilder),
+    Modify {
+        description_url: String,
+        avator_url: String,
+    },
+}
+
+impl Step {
+    fn kind(&self) -> PipelineStep {
+        match self {
+            Self::Setup(_) => PipelineStep::Setup,
+            Self::Prove(_) => PipelineStep::Prove,
+            Self::Reset(_) => PipelineStep::Reset,
+            Self::Modify { .. } => PipelineStep::Modify,
+        }
+    }
+}
+
+type ProgressCallback = Arc<dyn Fn(&PipelineProgress) + Send + Sync>;
+
+/// Runs a sequence of image operations, waiting for the task of each step to finish before starting the next one.
+///
+/// Prove and reset steps without an md5 work on the image of the setup step, or the image set with [`Self::md5`]. A
+/// setup step is skipped if [`super::ZkWasmServiceHelper::query_image`] finds the image already added, it then waits for
+/// the Setup task of that image. The pipeline stops at the first failed request or task.
+///
+/// ```no_run
+/// # use zkp_service_helper::helper::AuthenticatedSession;
+/// # use zkp_service_helper::helper::ImagePipeline;
+/// # use zkp_service_helper::helper::ImageSetupRequest;
+/// # use zkp_service_helper::helper::ProveRequest;
+/// # use zkp_service_helper::helper::StepState;
+/// # use zkp_service_helper::helper::WalletSigner;
+/// # use zkp_service_helper::helper::WasmImage;
+/// # async fn example(
+/// #     session: AuthenticatedSession<WalletSigner>,
+/// #     image: WasmImage,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// let report = ImagePipeline::new()
+///     .setup(ImageSetupRequest::builder().name("my image").wasm_image(image).circuit_size(22))
+///     .prove(ProveRequest::builder().public_input("0x1:i64"))
+///     .prove(ProveRequest::builder().public_input("0x2:i64"))
+///     .on_progress(|p| println!("step {} ({}): {:?}", p.step, p.kind, matches!(p.state, StepState::Finished)))
+///     .run(&session)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub struct ImagePipeline {
+    md5: Option<String>,
+    steps: Vec<Step>,
+    watch_options: WatchOptions,
+    on_progress: Option<ProgressCallback>,
+}
+
+impl Default for ImagePipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImagePipeline {
+    pub fn new() -> Self {
+        Self {
+            md5: None,
+            steps: vec![],
+            watch_options: WatchOptions::default(),
+            on_progress: None,
+        }
+    }
+
+    /// The image the steps work on when the pipeline has no setup step.
+    pub fn md5(mut self, md5: impl Into<String>) -> Self {
+        self.md5 = Some(md5.into());
+        self
+    }
+
+    /// Adds the image unless it already exists, and waits for its Setup task. `user_address` defaults to the signer.
+    pub fn setup(mut self, request: ImageSetupRequestBuilder) -> Self {
+        self.steps.push(Step::Setup(request));
+        self
+    }
+
+    /// Adds a prove task. `md5` defaults to the image of the pipeline, `user_address` to the signer.
+    pub fn prove(mut self, request: ProveRequestBuilder) -> Self {
+        self.steps.push(Step::Prove(request));
+        self
+    }
+
+    /// Adds a prove step for each request, see [`Self::prove`].
+    pub fn prove_all(mut self, requests: impl IntoIterator<Item = ProveRequestBuilder>) -> Self {
+        self.steps.extend(requests.into_iter().map(Step::Prove));
+        self
+    }
+
+    /// Resets the image. `md5` defaults to the image of the pipeline, `user_address` to the signer.
+    pub fn reset(mut self, request: ResetRequestBuilder) -> Self {
+        self.steps.push(Step::Reset(request));
+        self
+    }
+
+    /// Changes the description and avatar of the image.
+    pub fn modify(mut self, description_url: impl Into<String>, avator_url: impl Into<String>) -> Self {
+        self.steps.push(Step::Modify {
+            description_url: description_url.into(),
+            avator_url: avator_url.into(),
+        });
+        self
+    }
+
+    /// How the task of each step is waited for. With [`Self::on_progress`] set, the transition callback of `options` is
+    /// replaced and status changes are reported as [`StepState::StatusChanged`] instead, otherwise it is kept.
+    pub fn watch_options(mut self, options: WatchOptions) -> Self {
+        self.watch_options = options;
+        self
+    }
+
+    /// Calls `callback` as the steps progress.
+    pub fn on_progress(mut self, callback: impl Fn(&PipelineProgress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Runs the steps in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PipelineError`] for the first step whose request fails, e.g. a prove step without an md5, or whose
+    /// task fails or times out. The remaining steps are not run.
+    pub async fn run<S: RequestSigner>(
+        self,
+        session: &AuthenticatedSession<S>,
+    ) -> std::result::Result<PipelineReport, PipelineError> {
+        let mut md5 = self.md5.clone();
+        let mut completed = Vec::with_capacity(self.steps.len());
+        for (index, step) in self.steps.into_iter().enumerate() {
+            let kind = step.kind();
+            let run = StepRun {
+                index,
+                kind,
+                session,
+                watch_options: &self.watch_options,
+                on_progress: self.on_progress.as_ref(),
+            };
+            run.report(StepState::Started);
+            match run.execute(step, &mut md5).await {
+                Ok(task) => {
+                    if task.is_some() || kind == PipelineStep::Modify {
+                        run.report(StepState::Finished);
+                    }
+                    completed.push(StepReport { kind, task });
+                }
+                Err(failure) => {
+                    return Err(PipelineError {
+                        step: index,
+                        kind,
+                        failure,
+                        completed,
+                    })
+                }
+            }
+        }
+        Ok(PipelineReport {
+            md5: md5.unwrap_or_default(),
+            steps: completed,
+        })
+    }
+}
+
+struct StepRun<'a, S> {
+    index: usize,
+    kind: PipelineStep,
+    session: &'a AuthenticatedSession<S>,
+    watch_options: &'a WatchOptions,
+    on_progress: Option<&'a ProgressCallback>,
+}
+
+impl<S: RequestSigner> StepRun<'_, S> {
+    fn report(&self, state: StepState) {
+        if let Some(callback) = self.on_progress {
+            callback(&PipelineProgress {
+                step: self.index,
+                kind: self.kind,
+                state,
+            });
+        }
+    }
+
+    /// Runs the step, returns its finished task or `None` if it added no task.
+    async fn execute(
+        &self,
+        step: Step,
+        md5: &mut Option<String>,
+    ) -> std::result::Result<Option<Box<Task>>, PipelineFailure> {
+        let session = self.session;
+        let helper = session.helper();
+        let added = match step {
+            Step::Setup(request) => {
+                let request = request
+                    .signed_by(session.user_address())
+                    .and_then(ImageSetupRequestBuilder::build)
+                    .map_err(PipelineFailure::Request)?;
+                let image_md5 = md5.insert(request.params().base.image_md5.clone()).clone();
+                let existing = helper.query_image(image_md5.clone()).await.map_err(PipelineFailure::Request)?;
+                if existing.is_some() {
+                    self.report(StepState::Skipped);
+                    let task_id = self.setup_task(image_md5).await?;
+                    return self.wait(task_id).await.map(Some);
+                }
+                helper.setup_image_with(request, session.signer()).await
+            }
+            Step::Prove(request) => session.add_prove(request.default_md5(md5.as_deref())).await,
+            Step::Reset(request) => session.add_reset(request.default_md5(md5.as_deref())).await,
+            Step::Modify {
+                description_url,
+                avator_url,
+            } => {
+                let md5 = md5
+                    .clone()
+                    .ok_or_else(|| PipelineFailure::Request(ServiceError::request("`md5` is required")))?;
+                session
+                    .modify_image(md5, description_url, avator_url)
+                    .await
+                    .map_err(PipelineFailure::Request)?;
+                return Ok(None);
+            }
+        };
+        let task_id = added.map_err(PipelineFailure::Request)?.id;
+        self.report(StepState::Submitted {
+            task_id: task_id.clone(),
+        });
+        self.wait(task_id).await.map(Some)
+    }
+
+    /// The id of the Setup task of an image which was already added.
+    async fn setup_task(&self, md5: String) -> std::result::Result<String, PipelineFailure> {
+        let query = TaskQuery::new().image(md5.clone()).kind(TaskType::Setup).page(0, 1);
+        let tasks = self
+            .session
+            .helper()
+            .query_tasks_with(query)
+            .await
+            .map_err(PipelineFailure::Request)?;
+        tasks.data.into_iter().next().map(|task| task._id.oid).ok_or_else(|| {
+            PipelineFailure::Request(ServiceError::request(format!("image {md5} exists but has no setup task")))
+        })
+    }
+
+    async fn wait(&self, task_id: String) -> std::result::Result<Box<Task>, PipelineFailure> {
+        let mut options = self.watch_options.clone();
+        if let Some(callback) = self.on_progress.cloned() {
+            let (step, kind, id) = (self.index, self.kind, task_id.clone());
+            options = options.on_transition(move |transition| {
+                callback(&PipelineProgress {
+                    step,
+                    kind,
+                    state: StepState::StatusChanged {
+                        task_id: id.clone(),
+                        status: transition.task.status.clone(),
+                    },
+                });
+            });
+        }
+        let outcome = TaskWatcher::new(self.session.helper().clone(), options)
+            .wait(&task_id)
+            .await
+            .map_err(PipelineFailure::Request)?;
+        match outcome {
+            TaskOutcome::Done(task) => Ok(task),
+            TaskOutcome::Failed(failure) => Err(PipelineFailure::TaskFailed { task_id, failure }),
+            TaskOutcome::TimedOut { last_status } => Err(PipelineFailure::TimedOut { task_id, last_status }),
+        }
+    }
+}
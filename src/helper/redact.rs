@@ -0,0 +1,48 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use super::util::SerializationAttributes;
+
+/// Fields whose values are never written to logs.
+const REDACTED_FIELDS: &[&str] = &["private_inputs", "private_key", "signature"];
+
+/// Replaces sensitive fields with a placeholder and collapses byte arrays into their length.
+fn redact_value(value: &mut Value, bytes_fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if REDACTED_FIELDS.contains(&k.as_str()) {
+                    *v = Value::String("<redacted>".to_string());
+                } else if let (true, Value::Array(bytes)) = (bytes_fields.contains(k), &v) {
+                    *v = Value::String(format!("<{} bytes>", bytes.len()));
+                } else {
+                    redact_value(v, bytes_fields);
+                }
+            }
+        }
+        Value::Array(vs) => vs.iter_mut().for_each(|v| redact_value(v, bytes_fields)),
+        _ => {}
+    }
+}
+
+/// Renders a request body for logging, with sensitive fields and byte fields redacted.
+pub(crate) fn request<U: Serialize + SerializationAttributes>(body: &U) -> String {
+    match serde_json::to_value(body) {
+        Ok(mut value) => {
+            redact_value(&mut value, &U::fields_which_are_bytes());
+            value.to_string()
+        }
+        Err(e) => format!("<unserializable body: {e}>"),
+    }
+}
+
+/// Renders a response body for logging. Non-JSON bodies are logged as they are.
+pub(crate) fn response(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact_value(&mut value, &[]);
+            value.to_string()
+        }
+        Err(_) => body.to_string(),
+    }
+}
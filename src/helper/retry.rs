@@ -0,0 +1,134 @@
+use std::time::Duration;
+use std::time::SystemTime;
+
+use rand::Rng;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+
+use super::error::ServiceError;
+
+/// Controls how failed requests are retried by the endpoint layer.
+///
+/// Requests are retried on transport errors (connection refused or reset, timeouts) and on the transient HTTP statuses
+/// `408`, `429`, `502`, `503` and `504`. Between attempts the policy waits with exponential backoff starting at
+/// [`Self::initial_backoff`], doubling each attempt up to [`Self::max_backoff`]. When the server sends a `Retry-After`
+/// header its delay is used instead, capped at [`Self::max_retry_after`].
+///
+/// GET requests are retried by default. POST requests such as `/prove` and `/setup` are not idempotent, a retried
+/// request may create a second task, so they are only retried when enabled with [`Self::retry_posts`].
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_retry_after: Duration,
+    jitter: bool,
+    retry_posts: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            max_retry_after: Duration::from_secs(60),
+            jitter: true,
+            retry_posts: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which sends every request exactly once.
+    pub fn none() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// Total number of attempts, including the first. Values below `1` are treated as `1`.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry.
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Upper bound for the exponential backoff delay.
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Upper bound for delays requested by the server through `Retry-After`.
+    pub fn max_retry_after(mut self, delay: Duration) -> Self {
+        self.max_retry_after = delay;
+        self
+    }
+
+    /// Randomises each backoff delay to between half and all of its value, so clients don't retry in lockstep.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Also retry POST requests, including the signed `/prove` and `/setup` submissions.
+    pub fn retry_posts(mut self, retry_posts: bool) -> Self {
+        self.retry_posts = retry_posts;
+        self
+    }
+
+    pub(crate) fn attempts_for(&self, method: &reqwest::Method) -> u32 {
+        if *method == reqwest::Method::GET || self.retry_posts {
+            self.max_attempts
+        } else {
+            1
+        }
+    }
+
+    /// Whether the error is likely to be transient.
+    pub(crate) fn is_retryable(err: &ServiceError) -> bool {
+        match err {
+            ServiceError::Transport(e) => !e.is_builder() && !e.is_decode(),
+            ServiceError::HttpStatus { status, .. } | ServiceError::ServerRejected { status, .. } => matches!(
+                *status,
+                StatusCode::REQUEST_TIMEOUT
+                    | StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            _ => false,
+        }
+    }
+
+    /// Delay before the next attempt, after `retry` failed attempts (starting at `1`).
+    pub(crate) fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(delay) = retry_after {
+            return delay.min(self.max_retry_after);
+        }
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff);
+        if self.jitter {
+            rand::thread_rng().gen_range(backoff / 2..=backoff)
+        } else {
+            backoff
+        }
+    }
+
+    /// Parses a `Retry-After` header given either in seconds or as an HTTP date.
+    pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+        let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+        if let Ok(secs) = value.parse::<u64>() {
+            return Some(Duration::from_secs(secs));
+        }
+        let at = httpdate::parse_http_date(value).ok()?;
+        Some(at.duration_since(SystemTime::now()).unwrap_or_default())
+    }
+}
@@ -0,0 +1,134 @@
+use super::error::Result;
+use super::helper::ZkWasmServiceHelper;
+use super::signer::RequestSigner;
+use super::task_request::ImageSetupRequestBuilder;
+use super::task_request::ProveRequestBuilder;
+use super::task_request::ResetRequestBuilder;
+use crate::interface::AddTaskResult;
+use crate::interface::AdminRequestType;
+use crate::interface::MaintenanceModeType;
+use crate::interface::ObjectId;
+
+/// The signed operations of a [`ZkWasmServiceHelper`], bound to one signer.
+///
+/// The `user_address` of every request is the address of the signer, so requests can neither be signed for the wrong
+/// address nor be rejected by the server for a mismatching signature. Requests built with a `user_address` of another
+/// account are refused with [`super::ServiceError::Signing`] before anything is signed.
+///
+/// ```no_run
+/// # use zkp_service_helper::helper::ProveRequest;
+/// # use zkp_service_helper::helper::WalletSigner;
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # async fn example(
+/// #     helper: ZkWasmServiceHelper,
+/// #     path: &str,
+/// #     password: &str,
+/// #     md5: String,
+/// # ) -> zkp_service_helper::helper::Result<()> {
+/// let session = helper.with_signer(WalletSigner::from_keystore(path, password)?).await?;
+/// let res = session.add_prove(ProveRequest::builder().md5(md5).public_input("0x2a:i64")).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AuthenticatedSession<S> {
+    helper: ZkWasmServiceHelper,
+    signer: S,
+    user_address: String,
+}
+
+impl ZkWasmServiceHelper {
+    /// Binds `signer` to a clone of this helper, see [`AuthenticatedSession`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`super::ServiceError::Signing`] if the address of the signer cannot be determined.
+    pub async fn with_signer<S: RequestSigner>(&self, signer: S) -> Result<AuthenticatedSession<S>> {
+        let user_address = format!("{:#x}", signer.address().await?);
+        Ok(AuthenticatedSession {
+            helper: self.clone(),
+            signer,
+            user_address,
+        })
+    }
+}
+
+impl<S: RequestSigner> AuthenticatedSession<S> {
+    /// The address of the signer, lower case with `0x` prefix.
+    #[must_use]
+    pub fn user_address(&self) -> &str {
+        &self.user_address
+    }
+
+    #[must_use]
+    pub fn signer(&self) -> &S {
+        &self.signer
+    }
+
+    /// The helper used for the requests, e.g. for queries which need no signature.
+    #[must_use]
+    pub fn helper(&self) -> &ZkWasmServiceHelper {
+        &self.helper
+    }
+
+    pub async fn query_logs(&self, id: String) -> Result<String> {
+        self.helper.query_logs(id, self.user_address.clone(), &self.signer).await
+    }
+
+    /// Adds an image, `user_address` defaults to the signer.
+    pub async fn setup_image(&self, request: ImageSetupRequestBuilder) -> Result<AddTaskResult> {
+        let request = request.signed_by(&self.user_address)?.build()?;
+        self.helper.setup_image_with(request, &self.signer).await
+    }
+
+    /// Adds a prove task, `user_address` defaults to the signer.
+    pub async fn add_prove(&self, request: ProveRequestBuilder) -> Result<AddTaskResult> {
+        let request = request.signed_by(&self.user_address)?.build()?;
+        self.helper.add_prove_with(request, &self.signer).await
+    }
+
+    /// Deploys the image with the given md5 to the chain with id `chain_id`.
+    #[deprecated]
+    #[allow(deprecated)]
+    pub async fn add_deploy(&self, md5: String, chain_id: u32) -> Result<()> {
+        self.helper
+            .add_deploy(self.user_address.clone(), md5, chain_id, &self.signer)
+            .await
+    }
+
+    /// Adds a reset task, `user_address` defaults to the signer.
+    pub async fn add_reset(&self, request: ResetRequestBuilder) -> Result<AddTaskResult> {
+        let request = request.signed_by(&self.user_address)?.build()?;
+        self.helper.add_reset_with(request, &self.signer).await
+    }
+
+    pub async fn modify_image(&self, md5: String, description_url: String, avator_url: String) -> Result<String> {
+        self.helper
+            .modify_image(md5, self.user_address.clone(), description_url, avator_url, &self.signer)
+            .await
+    }
+
+    #[deprecated]
+    #[allow(deprecated)]
+    pub async fn set_maintenance_mode(
+        &self,
+        mode: MaintenanceModeType,
+        nonce: u64,
+        request_type: AdminRequestType,
+    ) -> Result<String> {
+        self.helper
+            .set_maintenance_mode(mode, nonce, request_type, self.user_address.clone(), &self.signer)
+            .await
+    }
+
+    pub async fn force_unprovable_to_reprocess(&self, task_ids: Vec<String>) -> Result<Vec<ObjectId>> {
+        self.helper
+            .force_unprovable_to_reprocess(task_ids, self.user_address.clone(), &self.signer)
+            .await
+    }
+
+    pub async fn force_dryrun_fails_to_reprocess(&self, task_ids: Vec<String>) -> Result<Vec<ObjectId>> {
+        self.helper
+            .force_dryrun_fails_to_reprocess(task_ids, self.user_address.clone(), &self.signer)
+            .await
+    }
+}
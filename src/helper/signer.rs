@@ -0,0 +1,276 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::signers::coins_bip39::English;
+use ethers::signers::LocalWallet;
+use ethers::signers::MnemonicBuilder;
+use ethers::signers::Signer;
+use ethers::types::Address;
+use ethers::types::Signature;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use super::error::Result;
+use super::error::ServiceError;
+
+/// Signs the messages of requests which require a signature, e.g. [`super::ZkWasmServiceHelper::add_prove`].
+///
+/// Messages are signed as [EIP-191](https://eips.ethereum.org/EIPS/eip-191) personal messages, as done by
+/// `personal_sign`. Implementations are provided for
+///
+/// - [`WalletSigner`], a key held in memory, loaded from a hex private key, an encrypted JSON keystore or a mnemonic,
+/// - [`ExternalSigner`], a separate process which holds the key.
+///
+/// Plain private key strings are not signers, parse them once with [`WalletSigner::from_private_key`].
+#[async_trait]
+pub trait RequestSigner: Send + Sync {
+    /// The address of the account signing the requests.
+    async fn address(&self) -> Result<Address>;
+
+    /// Signs `message` as an EIP-191 personal message.
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature>;
+}
+
+#[async_trait]
+impl<S: RequestSigner + ?Sized> RequestSigner for &S {
+    async fn address(&self) -> Result<Address> {
+        (**self).address().await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        (**self).sign_message(message).await
+    }
+}
+
+#[async_trait]
+impl<S: RequestSigner + ?Sized> RequestSigner for Arc<S> {
+    async fn address(&self) -> Result<Address> {
+        (**self).address().await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        (**self).sign_message(message).await
+    }
+}
+
+/// [`RequestSigner`] holding its key in memory.
+///
+/// ```no_run
+/// # use zkp_service_helper::helper::ProveRequest;
+/// # use zkp_service_helper::helper::WalletSigner;
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # async fn example(helper: ZkWasmServiceHelper, request: ProveRequest) -> Result<(), Box<dyn std::error::Error>> {
+/// let signer = WalletSigner::from_keystore("keys/prover.json", std::env::var("KEYSTORE_PASSWORD")?)?;
+/// let res = helper.add_prove_with(request, &signer).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct WalletSigner {
+    wallet: LocalWallet,
+}
+
+impl WalletSigner {
+    #[must_use]
+    pub fn new(wallet: LocalWallet) -> Self {
+        Self { wallet }
+    }
+
+    /// Parses a hex encoded private key, with or without `0x` prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Signing`] if the key is malformed.
+    pub fn from_private_key(private_key: &str) -> Result<Self> {
+        private_key.parse::<LocalWallet>().map(Self::new).map_err(ServiceError::signing)
+    }
+
+    /// Decrypts an encrypted JSON keystore file, as written by `geth` or `cast wallet import`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Signing`] if the file cannot be read or the password is wrong.
+    pub fn from_keystore(path: impl AsRef<Path>, password: impl AsRef<[u8]>) -> Result<Self> {
+        LocalWallet::decrypt_keystore(path, password)
+            .map(Self::new)
+            .map_err(ServiceError::signing)
+    }
+
+    /// Derives the key at `derivation_path` from a BIP-39 mnemonic in English, e.g. `m/44'/60'/0'/0/0` for the first
+    /// account of most wallets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Signing`] if the mnemonic or the derivation path is invalid.
+    pub fn from_mnemonic(phrase: &str, derivation_path: &str) -> Result<Self> {
+        MnemonicBuilder::<English>::default()
+            .phrase(phrase)
+            .derivation_path(derivation_path)
+            .and_then(|builder| builder.build())
+            .map(Self::new)
+            .map_err(ServiceError::signing)
+    }
+
+    /// The address of the key, available without a round trip unlike [`RequestSigner::address`].
+    #[must_use]
+    pub fn wallet_address(&self) -> Address {
+        self.wallet.address()
+    }
+}
+
+impl From<LocalWallet> for WalletSigner {
+    fn from(wallet: LocalWallet) -> Self {
+        Self::new(wallet)
+    }
+}
+
+#[async_trait]
+impl RequestSigner for WalletSigner {
+    async fn address(&self) -> Result<Address> {
+        Ok(self.wallet.address())
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        self.wallet.sign_message(message).await.map_err(ServiceError::signing)
+    }
+}
+
+/// A request sent to an [`ExternalSigner`], one JSON document per line.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SignerRequest {
+    /// Asks for the address of the signing account.
+    Address,
+    /// Asks for the EIP-191 personal signature of `message`, hex encoded with `0x` prefix.
+    SignMessage { message: String },
+}
+
+/// The reply of an [`ExternalSigner`], one JSON document per line.
+///
+/// `result` is the `0x` prefixed address or 65 byte signature, `error` the reason the request was refused.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    Result(String),
+    Error(String),
+}
+
+#[derive(Clone, Debug)]
+enum SignerEndpoint {
+    Command {
+        program: PathBuf,
+        args: Vec<String>,
+    },
+    #[cfg(unix)]
+    Socket(PathBuf),
+}
+
+/// [`RequestSigner`] delegating to another process, so that the key never enters this one.
+///
+/// Each [`SignerRequest`] is written as a single line of JSON and answered by a single line holding a
+/// [`SignerResponse`]:
+///
+/// ```text
+/// > {"method":"sign_message","message":"0x6162"}
+/// < {"result":"0x1b2c...1c"}
+/// ```
+///
+/// The signer is either a command, spawned once per request with the request on its stdin and the response read from
+/// its stdout, or a local process listening on a Unix socket, connected to once per request. The signer is expected to
+/// apply the EIP-191 prefix itself, as `personal_sign` does.
+#[derive(Clone, Debug)]
+pub struct ExternalSigner {
+    endpoint: SignerEndpoint,
+}
+
+impl ExternalSigner {
+    /// Spawns `program` with `args` for every request.
+    pub fn command(program: impl Into<PathBuf>, args: Vec<String>) -> Self {
+        Self {
+            endpoint: SignerEndpoint::Command {
+                program: program.into(),
+                args,
+            },
+        }
+    }
+
+    /// Connects to the Unix socket at `path` for every request.
+    #[cfg(unix)]
+    pub fn socket(path: impl Into<PathBuf>) -> Self {
+        Self {
+            endpoint: SignerEndpoint::Socket(path.into()),
+        }
+    }
+
+    async fn call(&self, request: &SignerRequest) -> Result<String> {
+        let mut line = serde_json::to_string(request).map_err(ServiceError::signing)?;
+        line.push('\n');
+        let reply = match &self.endpoint {
+            SignerEndpoint::Command { program, args } => Self::call_command(program, args, &line).await?,
+            #[cfg(unix)]
+            SignerEndpoint::Socket(path) => Self::call_socket(path, &line).await?,
+        };
+        match serde_json::from_str(reply.trim()).map_err(ServiceError::signing)? {
+            SignerResponse::Result(result) => Ok(result),
+            SignerResponse::Error(message) => Err(ServiceError::signing(format!("external signer refused: {message}"))),
+        }
+    }
+
+    async fn call_command(program: &Path, args: &[String], line: &str) -> Result<String> {
+        let mut child = tokio::process::Command::new(program)
+            .args(args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(ServiceError::signing)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(line.as_bytes()).await.map_err(ServiceError::signing)?;
+        }
+        let output = child.wait_with_output().await.map_err(ServiceError::signing)?;
+        if !output.status.success() {
+            return Err(ServiceError::signing(format!(
+                "external signer exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let stdout = String::from_utf8(output.stdout).map_err(ServiceError::signing)?;
+        Ok(stdout.lines().next().unwrap_or_default().to_string())
+    }
+
+    #[cfg(unix)]
+    async fn call_socket(path: &Path, line: &str) -> Result<String> {
+        use tokio::io::AsyncBufReadExt;
+
+        let mut stream = tokio::net::UnixStream::connect(path).await.map_err(ServiceError::signing)?;
+        stream.write_all(line.as_bytes()).await.map_err(ServiceError::signing)?;
+        let mut reply = String::new();
+        tokio::io::BufReader::new(stream)
+            .read_line(&mut reply)
+            .await
+            .map_err(ServiceError::signing)?;
+        Ok(reply)
+    }
+}
+
+#[async_trait]
+impl RequestSigner for ExternalSigner {
+    async fn address(&self) -> Result<Address> {
+        let address = self.call(&SignerRequest::Address).await?;
+        Address::from_str(&address).map_err(ServiceError::signing)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        let request = SignerRequest::SignMessage {
+            message: format!("0x{}", ethers::utils::hex::encode(message)),
+        };
+        let signature = self.call(&request).await?;
+        Signature::from_str(&signature).map_err(ServiceError::signing)
+    }
+}
@@ -0,0 +1,614 @@
+use super::error::Result;
+use super::error::ServiceError;
+use super::upload::UploadSource;
+use super::wasm_image::WasmImage;
+use crate::interface::AddImageParams;
+use crate::interface::AddProveTaskRestrictions;
+use crate::interface::BaseAddImageParams;
+use crate::interface::BaseProvingParams;
+use crate::interface::BaseResetImageParams;
+use crate::interface::CustomContext;
+use crate::interface::InitialContext;
+use crate::interface::InputContextType;
+use crate::interface::ProofSubmitMode;
+use crate::interface::ProvePaymentSrc;
+use crate::interface::ProvingParams;
+use crate::interface::ResetContext;
+use crate::interface::ResetImageParams;
+use crate::interface::WithCustomInputContext;
+use crate::interface::WithInitialContext;
+use crate::interface::WithResetContext;
+
+/// Input types accepted by the service, e.g. `0x2a:i64` or `0x0102:bytes`.
+const INPUT_TYPES: [&str; 3] = ["i64", "bytes", "bytes-packed"];
+
+fn md5_hex(data: &[u8]) -> String {
+    format!("{:X}", md5::compute(data))
+}
+
+fn require<T>(field: Option<T>, name: &str) -> Result<T> {
+    field.ok_or_else(|| ServiceError::request(format!("`{name}` is required")))
+}
+
+/// Sets `user_address` to the address of the signer, or checks that the address already set is the signer's.
+fn bind_address(user_address: &mut Option<String>, signer: &str) -> Result<()> {
+    match user_address {
+        Some(address) if !address.eq_ignore_ascii_case(signer) => Err(ServiceError::signing(format!(
+            "the signer controls {signer}, refusing to sign for {address}"
+        ))),
+        Some(_) => Ok(()),
+        None => {
+            *user_address = Some(signer.to_string());
+            Ok(())
+        }
+    }
+}
+
+fn check_address(user_address: &str) -> Result<()> {
+    let hex = user_address.strip_prefix("0x").unwrap_or_default();
+    if hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(ServiceError::request(format!(
+            "`user_address` must be a 0x prefixed 20 byte hex address, got `{user_address}`"
+        )))
+    }
+}
+
+/// Checks that `md5` is an md5 in hex and returns it in uppercase, as the service and [`WasmImage::md5`] write it.
+fn check_md5(md5: &str) -> Result<String> {
+    if md5.len() == 32 && md5.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(md5.to_ascii_uppercase())
+    } else {
+        Err(ServiceError::request(format!("`md5` must be 32 hex digits, got `{md5}`")))
+    }
+}
+
+fn check_inputs(name: &str, inputs: &[String]) -> Result<()> {
+    for input in inputs {
+        let valid = input
+            .rsplit_once(':')
+            .is_some_and(|(value, ty)| !value.is_empty() && INPUT_TYPES.contains(&ty));
+        if !valid {
+            return Err(ServiceError::request(format!(
+                "`{name}` must have the form `<value>:<i64|bytes|bytes-packed>`, got `{input}`"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// A validated request to add an image, submitted with [`super::ZkWasmServiceHelper::setup_image_with`].
+#[derive(Clone)]
+pub struct ImageSetupRequest {
+    params: AddImageParams,
+    image_source: Option<UploadSource>,
+}
+
+impl ImageSetupRequest {
+    pub fn builder() -> ImageSetupRequestBuilder {
+        ImageSetupRequestBuilder::default()
+    }
+
+    /// The parameters of the request. The image is empty if it is streamed from [`Self::image_source`].
+    #[must_use]
+    pub fn params(&self) -> &AddImageParams {
+        &self.params
+    }
+
+    /// The source the image is streamed from, if it is not held in memory.
+    #[must_use]
+    pub fn image_source(&self) -> Option<&UploadSource> {
+        self.image_source.as_ref()
+    }
+
+    pub(super) fn into_parts(self) -> (AddImageParams, Option<UploadSource>) {
+        (self.params, self.image_source)
+    }
+}
+
+impl From<AddImageParams> for ImageSetupRequest {
+    /// Wraps already built parameters as they are, without validation.
+    fn from(params: AddImageParams) -> Self {
+        Self {
+            params,
+            image_source: None,
+        }
+    }
+}
+
+impl From<ImageSetupRequest> for AddImageParams {
+    /// The parameters of the request, with an empty image if it is streamed.
+    fn from(request: ImageSetupRequest) -> Self {
+        request.params
+    }
+}
+
+enum Image {
+    Bytes(Vec<u8>),
+    Source(UploadSource),
+}
+
+/// Builder for [`ImageSetupRequest`].
+///
+/// `name`, `image` (or `image_source`), `user_address` and `circuit_size` are required. The image md5 is computed
+/// from the image unless set, the prove fees are paid by the prover ([`ProvePaymentSrc::Default`]), anyone may add
+/// prove tasks and the image has no initial context.
+///
+/// ```no_run
+/// # use zkp_service_helper::helper::ImageSetupRequest;
+/// # use zkp_service_helper::helper::WalletSigner;
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # async fn example(
+/// #     helper: ZkWasmServiceHelper,
+/// #     signer: WalletSigner,
+/// #     wasm: Vec<u8>,
+/// #     user_address: String,
+/// #     chain_id: u32,
+/// # ) -> zkp_service_helper::helper::Result<()> {
+/// let request = ImageSetupRequest::builder()
+///     .name("my image")
+///     .image(wasm)
+///     .user_address(user_address)
+///     .circuit_size(22)
+///     .auto_submit_network_ids(vec![chain_id])
+///     .build()?;
+/// let res = helper.setup_image_with(request, &signer).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+#[derive(Default)]
+pub struct ImageSetupRequestBuilder {
+    name: Option<String>,
+    image: Option<Image>,
+    image_md5: Option<String>,
+    user_address: Option<String>,
+    description_url: String,
+    avator_url: String,
+    circuit_size: Option<u32>,
+    prove_payment_src: Option<ProvePaymentSrc>,
+    auto_submit_network_ids: Vec<u32>,
+    add_prove_task_restrictions: Option<AddProveTaskRestrictions>,
+    inherited_merkle_data_md5: Option<String>,
+    initial_context: Option<Vec<u8>>,
+}
+
+impl ImageSetupRequestBuilder {
+    pub(super) fn signed_by(mut self, signer: &str) -> Result<Self> {
+        bind_address(&mut self.user_address, signer)?;
+        Ok(self)
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// The wasm binary of the image.
+    pub fn image(mut self, image: Vec<u8>) -> Self {
+        self.image = Some(Image::Bytes(image));
+        self
+    }
+
+    /// A validated wasm image, setting both the image and its md5. Replaces [`Self::image`].
+    pub fn wasm_image(mut self, image: WasmImage) -> Self {
+        let md5 = image.md5().to_string();
+        self.image = Some(Image::Bytes(image.into_bytes()));
+        self.image_md5 = Some(md5);
+        self
+    }
+
+    /// Streams the image from a file or reader when the request is sent, instead of holding it in memory. Replaces
+    /// [`Self::image`].
+    pub fn image_source(mut self, source: UploadSource) -> Self {
+        self.image = Some(Image::Source(source));
+        self
+    }
+
+    /// The md5 of the image, checked against the image (or the md5 of the image source) when building and written in
+    /// uppercase. Computed from the image if not set.
+    pub fn image_md5(mut self, image_md5: impl Into<String>) -> Self {
+        self.image_md5 = Some(image_md5.into());
+        self
+    }
+
+    pub fn user_address(mut self, user_address: impl Into<String>) -> Self {
+        self.user_address = Some(user_address.into());
+        self
+    }
+
+    pub fn description_url(mut self, description_url: impl Into<String>) -> Self {
+        self.description_url = description_url.into();
+        self
+    }
+
+    pub fn avator_url(mut self, avator_url: impl Into<String>) -> Self {
+        self.avator_url = avator_url.into();
+        self
+    }
+
+    pub fn circuit_size(mut self, circuit_size: u32) -> Self {
+        self.circuit_size = Some(circuit_size);
+        self
+    }
+
+    pub fn prove_payment_src(mut self, prove_payment_src: ProvePaymentSrc) -> Self {
+        self.prove_payment_src = Some(prove_payment_src);
+        self
+    }
+
+    /// Chain ids of the networks the proofs of this image are auto submitted to.
+    pub fn auto_submit_network_ids(mut self, auto_submit_network_ids: Vec<u32>) -> Self {
+        self.auto_submit_network_ids = auto_submit_network_ids;
+        self
+    }
+
+    pub fn add_prove_task_restrictions(mut self, restrictions: AddProveTaskRestrictions) -> Self {
+        self.add_prove_task_restrictions = Some(restrictions);
+        self
+    }
+
+    pub fn inherited_merkle_data_md5(mut self, md5: impl Into<String>) -> Self {
+        self.inherited_merkle_data_md5 = Some(md5.into());
+        self
+    }
+
+    /// Initial context of the image. Its md5 is computed when building.
+    pub fn initial_context(mut self, context: Vec<u8>) -> Self {
+        self.initial_context = Some(context);
+        self
+    }
+
+    /// Validates the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] if a required field is missing or a field is malformed.
+    pub fn build(self) -> Result<ImageSetupRequest> {
+        let name = require(self.name, "name")?;
+        if name.trim().is_empty() {
+            return Err(ServiceError::request("`name` must not be empty"));
+        }
+        let (image, image_source) = match require(self.image, "image")? {
+            Image::Bytes(image) => (image, None),
+            Image::Source(source) => (Vec::new(), Some(source)),
+        };
+        if image.is_empty() && image_source.as_ref().is_none_or(UploadSource::is_empty) {
+            return Err(ServiceError::request("`image` must not be empty"));
+        }
+        let md5 = image_source
+            .as_ref()
+            .map_or_else(|| md5_hex(&image), |source| source.md5().to_string());
+        let image_md5 = match self.image_md5 {
+            Some(image_md5) if !image_md5.eq_ignore_ascii_case(&md5) => {
+                return Err(ServiceError::request(format!(
+                    "`image_md5` {image_md5} does not match the md5 of the image {md5}"
+                )));
+            }
+            Some(image_md5) => check_md5(&image_md5)?,
+            None => md5,
+        };
+        let user_address = require(self.user_address, "user_address")?;
+        check_address(&user_address)?;
+        let circuit_size = require(self.circuit_size, "circuit_size")?;
+        let inherited_merkle_data_md5 = self.inherited_merkle_data_md5.as_deref().map(check_md5).transpose()?;
+
+        let context = match self.initial_context {
+            Some(initial_context) => InitialContext::With(WithInitialContext {
+                initial_context_md5: md5_hex(&initial_context),
+                initial_context,
+            }),
+            None => InitialContext::Without,
+        };
+        Ok(ImageSetupRequest {
+            params: AddImageParams {
+                base: BaseAddImageParams {
+                    name,
+                    image_md5,
+                    image,
+                    user_address,
+                    description_url: self.description_url,
+                    avator_url: self.avator_url,
+                    circuit_size,
+                    prove_payment_src: self.prove_payment_src.unwrap_or(ProvePaymentSrc::Default),
+                    auto_submit_network_ids: self.auto_submit_network_ids,
+                    add_prove_task_restrictions: self.add_prove_task_restrictions,
+                    inherited_merkle_data_md5,
+                },
+                context,
+            },
+            image_source,
+        })
+    }
+}
+
+/// A validated request to add a prove task, submitted with [`super::ZkWasmServiceHelper::add_prove_with`].
+#[derive(Clone)]
+pub struct ProveRequest {
+    params: ProvingParams,
+}
+
+impl ProveRequest {
+    pub fn builder() -> ProveRequestBuilder {
+        ProveRequestBuilder::default()
+    }
+
+    #[must_use]
+    pub fn params(&self) -> &ProvingParams {
+        &self.params
+    }
+}
+
+impl From<ProvingParams> for ProveRequest {
+    /// Wraps already built parameters as they are, without validation.
+    fn from(params: ProvingParams) -> Self {
+        Self { params }
+    }
+}
+
+impl From<ProveRequest> for ProvingParams {
+    fn from(request: ProveRequest) -> Self {
+        request.params
+    }
+}
+
+/// Builder for [`ProveRequest`].
+///
+/// `user_address` and `md5` are required. Inputs default to empty, the proof is submitted manually
+/// ([`ProofSubmitMode::Manual`]) and the task has no input context.
+///
+/// ```no_run
+/// # use zkp_service_helper::helper::ProveRequest;
+/// # use zkp_service_helper::helper::WalletSigner;
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # async fn example(
+/// #     helper: ZkWasmServiceHelper,
+/// #     signer: WalletSigner,
+/// #     user_address: String,
+/// #     md5: String,
+/// # ) -> zkp_service_helper::helper::Result<()> {
+/// let request = ProveRequest::builder()
+///     .user_address(user_address)
+///     .md5(md5)
+///     .public_input("0x2a:i64")
+///     .build()?;
+/// let res = helper.add_prove_with(request, &signer).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+#[derive(Default)]
+pub struct ProveRequestBuilder {
+    user_address: Option<String>,
+    md5: Option<String>,
+    public_inputs: Vec<String>,
+    private_inputs: Vec<String>,
+    proof_submit_mode: Option<ProofSubmitMode>,
+    context: Option<CustomContext>,
+}
+
+impl ProveRequestBuilder {
+    pub(super) fn signed_by(mut self, signer: &str) -> Result<Self> {
+        bind_address(&mut self.user_address, signer)?;
+        Ok(self)
+    }
+
+    /// Sets the md5 of the image unless it is already set.
+    pub(super) fn default_md5(mut self, md5: Option<&str>) -> Self {
+        if self.md5.is_none() {
+            self.md5 = md5.map(str::to_string);
+        }
+        self
+    }
+
+    pub fn user_address(mut self, user_address: impl Into<String>) -> Self {
+        self.user_address = Some(user_address.into());
+        self
+    }
+
+    /// The md5 of the image to prove, written in uppercase when building.
+    pub fn md5(mut self, md5: impl Into<String>) -> Self {
+        self.md5 = Some(md5.into());
+        self
+    }
+
+    /// Appends a public input, e.g. `0x2a:i64`.
+    pub fn public_input(mut self, input: impl Into<String>) -> Self {
+        self.public_inputs.push(input.into());
+        self
+    }
+
+    pub fn public_inputs(mut self, inputs: Vec<String>) -> Self {
+        self.public_inputs = inputs;
+        self
+    }
+
+    /// Appends a private input, e.g. `0x0102:bytes`.
+    pub fn private_input(mut self, input: impl Into<String>) -> Self {
+        self.private_inputs.push(input.into());
+        self
+    }
+
+    pub fn private_inputs(mut self, inputs: Vec<String>) -> Self {
+        self.private_inputs = inputs;
+        self
+    }
+
+    pub fn proof_submit_mode(mut self, proof_submit_mode: ProofSubmitMode) -> Self {
+        self.proof_submit_mode = Some(proof_submit_mode);
+        self
+    }
+
+    /// A custom input context for the task. Its md5 is computed when building.
+    pub fn input_context(mut self, context: Vec<u8>) -> Self {
+        self.context = Some(CustomContext::With(WithCustomInputContext {
+            input_context_type: InputContextType::Custom,
+            input_context_md5: md5_hex(&context),
+            input_context: context,
+        }));
+        self
+    }
+
+    /// Any other [`CustomContext`], e.g. to start from the current context of the image.
+    pub fn context(mut self, context: CustomContext) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Validates the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] if a required field is missing or a field is malformed.
+    pub fn build(self) -> Result<ProveRequest> {
+        let user_address = require(self.user_address, "user_address")?;
+        check_address(&user_address)?;
+        let md5 = check_md5(&require(self.md5, "md5")?)?;
+        check_inputs("public_inputs", &self.public_inputs)?;
+        check_inputs("private_inputs", &self.private_inputs)?;
+        Ok(ProveRequest {
+            params: ProvingParams {
+                base: BaseProvingParams {
+                    user_address,
+                    md5,
+                    public_inputs: self.public_inputs,
+                    private_inputs: self.private_inputs,
+                    proof_submit_mode: self.proof_submit_mode.unwrap_or(ProofSubmitMode::Manual),
+                },
+                context: self.context.unwrap_or(CustomContext::Without),
+            },
+        })
+    }
+}
+
+/// A validated request to reset an image, submitted with [`super::ZkWasmServiceHelper::add_reset_with`].
+#[derive(Clone)]
+pub struct ResetRequest {
+    params: ResetImageParams,
+}
+
+impl ResetRequest {
+    pub fn builder() -> ResetRequestBuilder {
+        ResetRequestBuilder::default()
+    }
+
+    #[must_use]
+    pub fn params(&self) -> &ResetImageParams {
+        &self.params
+    }
+}
+
+impl From<ResetImageParams> for ResetRequest {
+    /// Wraps already built parameters as they are, without validation.
+    fn from(params: ResetImageParams) -> Self {
+        Self { params }
+    }
+}
+
+impl From<ResetRequest> for ResetImageParams {
+    fn from(request: ResetRequest) -> Self {
+        request.params
+    }
+}
+
+/// Builder for [`ResetRequest`].
+///
+/// `md5`, `user_address` and `circuit_size` are required. The defaults are the same as for
+/// [`ImageSetupRequestBuilder`]: the prover pays, no restrictions are set and the image has no reset context.
+#[must_use]
+#[derive(Default)]
+pub struct ResetRequestBuilder {
+    md5: Option<String>,
+    circuit_size: Option<u32>,
+    user_address: Option<String>,
+    prove_payment_src: Option<ProvePaymentSrc>,
+    auto_submit_network_ids: Vec<u32>,
+    add_prove_task_restrictions: Option<AddProveTaskRestrictions>,
+    reset_context: Option<Vec<u8>>,
+}
+
+impl ResetRequestBuilder {
+    pub(super) fn signed_by(mut self, signer: &str) -> Result<Self> {
+        bind_address(&mut self.user_address, signer)?;
+        Ok(self)
+    }
+
+    /// Sets the md5 of the image unless it is already set.
+    pub(super) fn default_md5(mut self, md5: Option<&str>) -> Self {
+        if self.md5.is_none() {
+            self.md5 = md5.map(str::to_string);
+        }
+        self
+    }
+
+    /// The md5 of the image to reset, written in uppercase when building.
+    pub fn md5(mut self, md5: impl Into<String>) -> Self {
+        self.md5 = Some(md5.into());
+        self
+    }
+
+    pub fn circuit_size(mut self, circuit_size: u32) -> Self {
+        self.circuit_size = Some(circuit_size);
+        self
+    }
+
+    pub fn user_address(mut self, user_address: impl Into<String>) -> Self {
+        self.user_address = Some(user_address.into());
+        self
+    }
+
+    pub fn prove_payment_src(mut self, prove_payment_src: ProvePaymentSrc) -> Self {
+        self.prove_payment_src = Some(prove_payment_src);
+        self
+    }
+
+    pub fn auto_submit_network_ids(mut self, auto_submit_network_ids: Vec<u32>) -> Self {
+        self.auto_submit_network_ids = auto_submit_network_ids;
+        self
+    }
+
+    pub fn add_prove_task_restrictions(mut self, restrictions: AddProveTaskRestrictions) -> Self {
+        self.add_prove_task_restrictions = Some(restrictions);
+        self
+    }
+
+    /// New context of the image. Its md5 is computed when building.
+    pub fn reset_context(mut self, context: Vec<u8>) -> Self {
+        self.reset_context = Some(context);
+        self
+    }
+
+    /// Validates the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] if a required field is missing or a field is malformed.
+    pub fn build(self) -> Result<ResetRequest> {
+        let md5 = check_md5(&require(self.md5, "md5")?)?;
+        let user_address = require(self.user_address, "user_address")?;
+        check_address(&user_address)?;
+        let circuit_size = require(self.circuit_size, "circuit_size")?;
+
+        let context = match self.reset_context {
+            Some(reset_context) => ResetContext::With(WithResetContext {
+                reset_context_md5: md5_hex(&reset_context),
+                reset_context,
+            }),
+            None => ResetContext::Without,
+        };
+        Ok(ResetRequest {
+            params: ResetImageParams {
+                base: BaseResetImageParams {
+                    md5,
+                    circuit_size,
+                    user_address,
+                    prove_payment_src: self.prove_payment_src.unwrap_or(ProvePaymentSrc::Default),
+                    auto_submit_network_ids: self.auto_submit_network_ids,
+                    add_prove_task_restrictions: self.add_prove_task_restrictions,
+                },
+                context,
+            },
+        })
+    }
+}
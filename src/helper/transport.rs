@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use reqwest::StatusCode;
+
+use super::endpoint::TaskEndpoint;
+use super::error::Result;
+use super::upload::UploadSource;
+
+/// A single value of a multipart form field.
+#[derive(Clone, Debug)]
+pub enum MultipartValue {
+    Text(String),
+    Bytes(Bytes),
+    /// Binary content streamed from a file or reader when the request is sent.
+    Stream(UploadSource),
+}
+
+/// A named field of a multipart form. Fields may repeat, e.g. one field per element of an array.
+#[derive(Clone, Debug)]
+pub struct MultipartField {
+    pub name: String,
+    pub value: MultipartValue,
+}
+
+/// The body of a request sent through a [`Transport`].
+#[derive(Clone, Debug)]
+pub enum RequestBody {
+    Empty,
+    /// A serialized JSON document.
+    Json(Bytes),
+    Multipart(Vec<MultipartField>),
+}
+
+/// A request to one of the [`TaskEndpoint`]s, independent of the HTTP client used to send it.
+///
+/// Requests are built once by [`super::endpoint::ZkWasmServiceEndpoint`] and may be sent multiple times when retried,
+/// so transports receive them by reference.
+#[derive(Clone, Debug)]
+pub struct TransportRequest {
+    pub method: Method,
+    pub endpoint: TaskEndpoint,
+    /// The URL encoded query string, without the leading `?`. Empty when there are no query parameters.
+    pub query: String,
+    pub body: RequestBody,
+    /// The `x-eth-signature` header, if the request is signed.
+    pub signature: Option<String>,
+}
+
+impl TransportRequest {
+    /// The request path including path parameters, e.g. `/archive/task/<id>`.
+    #[must_use]
+    pub fn path(&self) -> String {
+        format!("/{}{}", self.endpoint.as_path(), self.endpoint.path_params())
+    }
+}
+
+/// The raw HTTP response returned by a [`Transport`].
+#[derive(Clone, Debug)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl TransportResponse {
+    #[must_use]
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// Sends [`TransportRequest`]s to a `ZkWasm` service and returns the raw responses.
+///
+/// The transport is only responsible for moving bytes. Building requests, retrying, decoding responses and tracing are
+/// done by [`super::endpoint::ZkWasmServiceEndpoint`], so every transport gets the same behaviour. The crate ships
+/// [`ReqwestTransport`] for talking to a real server and [`super::MockTransport`] for in-process tests.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Sends the request and returns the response, whatever its status code.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`super::ServiceError::Transport`] if the request could not be sent or the response could not be read.
+    async fn send(&self, request: &TransportRequest) -> Result<TransportResponse>;
+}
+
+/// [`Transport`] which sends requests over HTTP with a pooled [`reqwest::Client`].
+#[derive(Clone)]
+pub struct ReqwestTransport {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    #[must_use]
+    pub fn new(base_url: String, client: reqwest::Client) -> Self {
+        Self { base_url, client }
+    }
+
+    fn to_form(fields: &[MultipartField]) -> reqwest::multipart::Form {
+        fields.iter().fold(reqwest::multipart::Form::new(), |form, field| {
+            let part = match &field.value {
+                MultipartValue::Text(v) => reqwest::multipart::Part::text(v.clone()),
+                MultipartValue::Bytes(v) => reqwest::multipart::Part::stream(v.clone()),
+                MultipartValue::Stream(source) => {
+                    let body = reqwest::Body::wrap_stream(source.stream());
+                    match source.len() {
+                        Some(len) => reqwest::multipart::Part::stream_with_length(body, len),
+                        None => reqwest::multipart::Part::stream(body),
+                    }
+                }
+            };
+            form.part(field.name.clone(), part)
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+        let mut url = format!("{}{}", self.base_url, request.path());
+        if !request.query.is_empty() {
+            url.push('?');
+            url.push_str(&request.query);
+        }
+
+        let mut req = self.client.request(request.method.clone(), url);
+        if let Some(sig) = &request.signature {
+            req = req.header("x-eth-signature", sig);
+        }
+        req = match &request.body {
+            RequestBody::Empty => req,
+            RequestBody::Json(body) => req.header(reqwest::header::CONTENT_TYPE, "application/json").body(body.clone()),
+            RequestBody::Multipart(fields) => req.multipart(Self::to_form(fields)),
+        };
+
+        let resp = req.send().await?;
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let body = resp.text().await?;
+        Ok(TransportResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
@@ -0,0 +1,238 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use bytes::Bytes;
+use futures::Stream;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+
+use super::error::Result;
+use super::error::ServiceError;
+
+/// Size of the chunks read from an [`UploadSource`] and sent as one piece of the request body.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+type Reader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Progress of a streamed upload, reported after each chunk is handed to the HTTP client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// Bytes of the source sent so far.
+    pub sent: u64,
+    /// Size of the source, if known.
+    pub total: Option<u64>,
+}
+
+#[derive(Clone)]
+enum Source {
+    /// Reopened for every attempt, so requests from a file can be retried.
+    File(PathBuf),
+    /// Taken by the first attempt.
+    Reader(Arc<Mutex<Option<Reader>>>),
+}
+
+/// Binary content which is streamed into a multipart request rather than held in memory, e.g. an image added with
+/// [`super::ImageSetupRequestBuilder::image_source`].
+///
+/// The md5 of the content is part of the signed request and the signature is sent before the body, so it has to be
+/// known before the upload starts: [`Self::from_path`] computes it in a streamed pass over the file, while
+/// [`Self::from_reader`] takes it from the caller. Either way the content is hashed again as it is uploaded and the
+/// upload fails if the md5 does not match, e.g. because the file changed in between.
+///
+/// ```no_run
+/// # use zkp_service_helper::helper::ImageSetupRequest;
+/// # use zkp_service_helper::helper::UploadSource;
+/// # async fn example(user_address: String) -> zkp_service_helper::helper::Result<()> {
+/// let image = UploadSource::from_path("image.wasm")
+///     .await?
+///     .on_progress(|p| println!("{}/{} bytes", p.sent, p.total.unwrap_or_default()));
+/// let request = ImageSetupRequest::builder()
+///     .name("my image")
+///     .image_source(image)
+///     .user_address(user_address)
+///     .circuit_size(22)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct UploadSource {
+    source: Source,
+    md5: String,
+    len: Option<u64>,
+    progress: Option<Arc<dyn Fn(UploadProgress) + Send + Sync>>,
+}
+
+impl fmt::Debug for UploadSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match &self.source {
+            Source::File(path) => path.display().to_string(),
+            Source::Reader(_) => "<reader>".to_string(),
+        };
+        f.debug_struct("UploadSource")
+            .field("source", &source)
+            .field("md5", &self.md5)
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl UploadSource {
+    /// Streams the file at `path`, computing its md5 and size without reading it into memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] if the file cannot be read.
+    pub async fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = tokio::fs::File::open(&path).await.map_err(|e| read_error(&path, &e))?;
+        let mut md5 = md5::Context::new();
+        let mut len = 0;
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            let n = file.read(&mut buf).await.map_err(|e| read_error(&path, &e))?;
+            if n == 0 {
+                break;
+            }
+            md5.consume(&buf[..n]);
+            len += n as u64;
+        }
+        Ok(Self {
+            source: Source::File(path),
+            md5: format!("{:X}", md5.finalize()),
+            len: Some(len),
+            progress: None,
+        })
+    }
+
+    /// Streams `reader`, whose content has the md5 `md5` and, if known, is `len` bytes long.
+    ///
+    /// A reader can only be read once, so a request streaming it is not retried. The md5 is checked as the content is
+    /// uploaded.
+    pub fn from_reader(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        md5: impl Into<String>,
+        len: Option<u64>,
+    ) -> Self {
+        Self {
+            source: Source::Reader(Arc::new(Mutex::new(Some(Box::new(reader))))),
+            md5: md5.into(),
+            len,
+            progress: None,
+        }
+    }
+
+    /// Calls `progress` after each chunk of the upload, e.g. to update a progress bar or to forward the progress to a
+    /// channel.
+    #[must_use]
+    pub fn on_progress(mut self, progress: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// The md5 of the content, as upper case hex.
+    #[must_use]
+    pub fn md5(&self) -> &str {
+        &self.md5
+    }
+
+    /// The size of the content in bytes, if known.
+    #[must_use]
+    pub fn len(&self) -> Option<u64> {
+        self.len
+    }
+
+    /// Whether the content is known to be empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == Some(0)
+    }
+
+    /// The content as a stream of chunks, for one attempt at sending a request.
+    ///
+    /// The stream fails if the content cannot be read, if its md5 does not match [`Self::md5`] or if the source is a
+    /// reader which was already streamed.
+    pub fn stream(&self) -> impl Stream<Item = io::Result<Bytes>> + Send + 'static {
+        let upload = Upload {
+            source: self.source.clone(),
+            reader: None,
+            expected_md5: self.md5.clone(),
+            md5: Some(md5::Context::new()),
+            progress: self.progress.clone(),
+            sent: 0,
+            total: self.len,
+        };
+        futures::stream::try_unfold(upload, |mut upload| async move {
+            let chunk = upload.next_chunk().await?;
+            Ok(chunk.map(|chunk| (chunk, upload)))
+        })
+    }
+}
+
+fn read_error(path: &Path, e: &io::Error) -> ServiceError {
+    ServiceError::request(format!("cannot read {}: {e}", path.display()))
+}
+
+/// Opens `source` for one attempt at sending a request.
+async fn open(source: &Source) -> io::Result<Reader> {
+    match source {
+        Source::File(path) => Ok(Box::new(tokio::fs::File::open(path).await?)),
+        Source::Reader(reader) => reader.lock().unwrap_or_else(PoisonError::into_inner).take().ok_or_else(|| {
+            io::Error::other("the upload reader was already streamed, requests streaming a reader cannot be retried")
+        }),
+    }
+}
+
+struct Upload {
+    source: Source,
+    reader: Option<Reader>,
+    expected_md5: String,
+    /// `None` once the content has been read to the end.
+    md5: Option<md5::Context>,
+    progress: Option<Arc<dyn Fn(UploadProgress) + Send + Sync>>,
+    sent: u64,
+    total: Option<u64>,
+}
+
+impl Upload {
+    async fn next_chunk(&mut self) -> io::Result<Option<Bytes>> {
+        if self.md5.is_none() {
+            return Ok(None);
+        }
+        if self.reader.is_none() {
+            self.reader = Some(open(&self.source).await?);
+        }
+        let Some(reader) = &mut self.reader else {
+            return Ok(None);
+        };
+        let mut buf = vec![0; CHUNK_SIZE];
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            let md5 = self.md5.take().map(|md5| format!("{:X}", md5.finalize())).unwrap_or_default();
+            if !md5.eq_ignore_ascii_case(&self.expected_md5) {
+                return Err(io::Error::other(format!(
+                    "the uploaded content has md5 {md5}, expected {}",
+                    self.expected_md5
+                )));
+            }
+            return Ok(None);
+        }
+        buf.truncate(n);
+        if let Some(md5) = &mut self.md5 {
+            md5.consume(&buf);
+        }
+        self.sent += n as u64;
+        if let Some(progress) = &self.progress {
+            progress(UploadProgress {
+                sent: self.sent,
+                total: self.total,
+            });
+        }
+        Ok(Some(buf.into()))
+    }
+}
@@ -1,20 +1,15 @@
-use ethers::signers::Signer;
-use reqwest::multipart::Part;
+use std::str::FromStr;
+
+use ethers::types::Address;
+use ethers::types::Signature;
 use serde::Serialize;
-use serde_json::Value;
 
-use crate::interface::AddImageParams;
-use crate::interface::DeployParams;
-use crate::interface::ForceDryrunFailsToReprocessParams;
-use crate::interface::ForceUnprovableToReprocessParams;
-use crate::interface::LogQuery;
-use crate::interface::ModifyImageParams;
-use crate::interface::PaymentParams;
-use crate::interface::ProverNodeTimeRangeStatsParams;
-use crate::interface::ProvingParams;
-use crate::interface::ResetImageParams;
-use crate::interface::SetMaintenanceModeParams;
-use crate::interface::SubscriptionRequest;
+use super::canonical::concat_message;
+use super::error::Result;
+use super::error::ServiceError;
+use super::multipart::to_fields;
+use super::signer::RequestSigner;
+use super::transport::MultipartField;
 
 /// Defines customisation hooks for controlling how request payloads are serialized.
 ///
@@ -24,6 +19,30 @@ use crate::interface::SubscriptionRequest;
 ///
 /// This trait is used in POST request building to provide fine-grained control over how objects are turned into messages
 /// before being signed or transmitted.
+///
+/// Request types derive it with `#[derive(SerializationAttributes)]` and mark fields with `#[zkp(bytes)]` and
+/// `#[zkp(skip_signing)]`, see [`zkp_service_helper_derive`], rather than listing field names by hand.
+///
+/// ```
+/// # use serde::Serialize;
+/// # use zkp_service_helper::helper::SerializationAttributes;
+/// #[derive(Serialize, SerializationAttributes)]
+/// pub struct BaseAddImageParams {
+///     pub name: String,
+///     #[zkp(bytes, skip_signing)]
+///     pub image: Vec<u8>,
+/// }
+///
+/// #[derive(Serialize, SerializationAttributes)]
+/// #[zkp(json_body, message = "json")]
+/// pub struct LogQuery {
+///     pub id: String,
+///     pub user_address: String,
+/// }
+///
+/// assert_eq!(BaseAddImageParams::fields_which_are_bytes(), ["image"]);
+/// assert!(LogQuery::requires_json_body());
+/// ```
 pub trait SerializationAttributes {
     #[must_use]
     fn fields_which_are_bytes() -> Vec<String> {
@@ -35,197 +54,139 @@ pub trait SerializationAttributes {
         vec![]
     }
 
-    fn create_message<T: Serialize>(obj: &T) -> anyhow::Result<String> {
-        Ok(match serde_json::to_value(obj)? {
-            Value::Null => String::new(),
-            Value::Bool(v) => v.to_string(),
-            Value::Number(v) => v.to_string(),
-            Value::String(v) => v,
-            Value::Array(vs) => {
-                let mut message = String::new();
-                for v in vs {
-                    message.push_str(&Self::create_message(&v)?);
-                }
-                message
-            }
-            Value::Object(map) => {
-                let mut message = String::new();
-                for (k, v) in map {
-                    if !Self::fields_to_ignore().contains(&k) {
-                        message.push_str(&Self::create_message(&v)?);
-                    }
-                }
-                message
-            }
-        })
+    /// Builds the canonical message which is signed for `obj`, see [`super::CANONICAL_MESSAGE_VERSION`].
+    fn create_message<T: Serialize>(obj: &T) -> Result<String> {
+        concat_message(obj, &Self::fields_to_ignore())
     }
 
+    #[must_use]
     fn requires_json_body() -> bool {
         false
     }
+
+    /// Moves the values of the fields in [`Self::fields_which_are_bytes`] out of `self`, so they are sent without being
+    /// copied. Fields which are not taken are collected from the serialized value instead.
+    ///
+    /// The derive implements this for `Vec<u8>` and `Option<Vec<u8>>` fields marked with `#[zkp(bytes)]`.
+    fn take_byte_fields(&mut self) -> Vec<MultipartField> {
+        vec![]
+    }
 }
 
-/// Serializes an object into its canonical message form and signs it with the given private key.
+/// Serializes an object into its canonical message form and signs it with the given signer.
 ///
 /// This function uses the [`SerializationAttributes`] implementation of the type `T` to determine how the object is
 /// transformed into a string message (via [`SerializationAttributes::create_message`]). It then signs the resulting
-/// message with the provided Ethereum private key using [`ethers::signers::LocalWallet`].
+/// message with `signer` (via [`RequestSigner::sign_message`]).
 ///
 /// # Type Parameters
 ///
 /// - `T`: The type of the object to be signed. Must implement [`Serialize`] and [`SerializationAttributes`].
+/// - `S`: The signer, e.g. a [`super::WalletSigner`] or a hex encoded private key.
 ///
 /// # Arguments
 ///
 /// - `obj`: The object to serialize and sign.
-/// - `private_key`: The private key in hex string format, used to sign the message.
+/// - `signer`: The signer of the message.
 ///
 /// # Returns
 ///
-/// Returns [`anyhow::Result`] containing the signature as a hex-encoded string on success,
-/// or an error if serialization, key parsing, or signing fails.
+/// Returns [`Result`] containing the signature as a hex-encoded string on success,
+/// or a [`ServiceError`] if serialization or signing fails.
 ///
 /// # Errors
 ///
 /// This function will return an error if:
 ///
-/// - Serialization of `obj` into a canonical message fails.
-/// - The `private_key` string cannot be parsed into an [`ethers::signers::LocalWallet`].
-/// - The signing operation fails.
-pub async fn sign_object<T: Serialize + SerializationAttributes>(
-    obj: &T,
-    private_key: String,
-) -> anyhow::Result<String> {
+/// - Serialization of `obj` into a canonical message fails ([`ServiceError::Request`]).
+/// - The signer fails to sign the message, e.g. a private key cannot be parsed ([`ServiceError::Signing`]).
+pub async fn sign_object<T, S>(obj: &T, signer: &S) -> Result<String>
+where
+    T: Serialize + SerializationAttributes,
+    S: RequestSigner + ?Sized,
+{
     let message = T::create_message(obj)?;
-    let wallet = private_key.parse::<ethers::signers::LocalWallet>()?;
-    Ok(wallet.sign_message(message).await.map(|s| s.to_string())?)
+    signer.sign_message(message.as_bytes()).await.map(|s| s.to_string())
 }
 
-/// Utility for converting Serializable objects into [`reqwest::multipart::Form`]s.
+/// Returns the canonical message of `obj`, i.e. the exact string which is signed for a request with `obj` as payload.
 ///
-/// `IntoMultipartForm` provides methods for converting `Serialize` + [`SerializationAttributes`] objects into multipart
-/// form data, which can be used in HTTP POST/PUT requests. It handles primitive values, arrays, and byte fields while
-/// rejecting unsupported nested JSON structures.
-pub struct IntoMultipartForm;
-
-impl IntoMultipartForm {
-    fn into_part(obj: serde_json::Value) -> anyhow::Result<Part> {
-        Ok(match obj {
-            Value::Bool(v) => Part::text(v.to_string()),
-            Value::Number(v) => Part::text(v.to_string()),
-            Value::String(v) => Part::text(v),
-            _ => return Err(anyhow::anyhow!("Must be primitive object type")),
-        })
-    }
-
-    fn create_parts<T: SerializationAttributes>(name: &str, obj: serde_json::Value) -> anyhow::Result<Vec<Part>> {
-        Ok(match obj {
-            Value::Null => vec![],
-            Value::Bool(_) | Value::Number(_) | Value::String(_) => vec![Self::into_part(obj)?],
-            Value::Object(_) => return Err(anyhow::anyhow!("Nested json objects not supported")),
-            Value::Array(vs) => {
-                if T::fields_which_are_bytes().contains(&name.to_string()) {
-                    let mut bytes = Vec::new();
-                    for v in vs {
-                        if let serde_json::Value::Number(n) = v {
-                            bytes.push(
-                                n.as_u64()
-                                    .and_then(|v| u8::try_from(v).ok())
-                                    .ok_or_else(|| anyhow::anyhow!("Byte must be u8 type"))?,
-                            );
-                        } else {
-                            return Err(anyhow::anyhow!("Bytes field can only have Number values"));
-                        }
-                    }
-                    vec![Part::bytes(bytes)]
-                } else {
-                    vs.into_iter().map(Self::into_part).collect::<anyhow::Result<Vec<_>>>()?
-                }
-            }
-        })
-    }
-
-    /// Converts a serializable object into a [`reqwest::multipart::Form`].
-    ///
-    /// The object must serialize into a JSON object (top-level map). Each field is converted into one or more [`Part`]s
-    /// using [`Self::create_parts`].
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if:
-    ///
-    /// - Serialization fails.
-    /// - The top-level value is not an object.
-    /// - Any field fails conversion in [`Self::create_parts`].
-    pub fn into_multipart_form<T: Serialize + SerializationAttributes>(
-        params: T,
-    ) -> anyhow::Result<reqwest::multipart::Form> {
-        if let Value::Object(map) = serde_json::to_value(params)? {
-            let mut form = reqwest::multipart::Form::new();
-            for (k, v) in map {
-                let parts = Self::create_parts::<T>(&k, v)?;
-                for part in parts {
-                    form = form.part(k.clone(), part);
-                }
-            }
-            Ok(form)
-        } else {
-            Err(anyhow::anyhow!("Top level of json must be Object type"))
-        }
-    }
+/// Useful when the service rejects a signature: the message shows which fields were included and in which order.
+///
+/// # Errors
+///
+/// Returns [`ServiceError::Request`] if `obj` cannot be serialized.
+pub fn canonical_message<T: Serialize + SerializationAttributes>(obj: &T) -> Result<String> {
+    T::create_message(obj)
 }
 
-impl SerializationAttributes for ProvingParams {
-    fn fields_to_ignore() -> Vec<String> {
-        vec!["input_context".to_string()]
-    }
+/// Returns the JSON document of `obj`, which is signed instead of the canonical message by requests with
+/// `#[zkp(message = "json")]`.
+///
+/// # Errors
+///
+/// Returns [`ServiceError::Request`] if `obj` cannot be serialized.
+pub fn json_message<T: Serialize>(obj: &T) -> Result<String> {
+    serde_json::to_string(obj).map_err(ServiceError::request)
 }
 
-impl SerializationAttributes for DeployParams {}
-
-impl SerializationAttributes for ResetImageParams {}
-
-impl SerializationAttributes for ModifyImageParams {
-    fn create_message<T: Serialize>(obj: &T) -> anyhow::Result<String> {
-        serde_json::to_string(obj).map_err(Into::into)
-    }
+/// Recovers the address which signed the canonical message of `obj`, the way the service checks the `x-eth-signature`
+/// header of a request.
+///
+/// Works offline on any captured request: a signature is valid for the request if the returned address is its
+/// `user_address`.
+///
+/// # Errors
+///
+/// Returns [`ServiceError::Request`] if `obj` cannot be serialized and [`ServiceError::Signing`] if the signature is
+/// malformed.
+pub fn verify_signed_request<T: Serialize + SerializationAttributes>(obj: &T, signature: &str) -> Result<Address> {
+    let message = T::create_message(obj)?;
+    let signature = Signature::from_str(signature).map_err(ServiceError::signing)?;
+    signature.recover(message).map_err(ServiceError::signing)
 }
 
-impl SerializationAttributes for SetMaintenanceModeParams {}
-
-impl SerializationAttributes for ForceUnprovableToReprocessParams {}
-
-impl SerializationAttributes for ForceDryrunFailsToReprocessParams {}
-
-impl SerializationAttributes for SubscriptionRequest {}
-
-impl SerializationAttributes for PaymentParams {}
-
-impl SerializationAttributes for LogQuery {
-    fn create_message<T: Serialize>(obj: &T) -> anyhow::Result<String> {
-        serde_json::to_string(obj).map_err(Into::into)
-    }
-
-    fn requires_json_body() -> bool {
-        true
+/// Signs `obj` with [`sign_object`] and checks that the signature recovers to `user_address` before it is sent, so a
+/// signer for another account fails locally instead of being rejected by the service.
+pub(crate) async fn sign_request<T, S>(obj: &T, user_address: &str, signer: &S) -> Result<String>
+where
+    T: Serialize + SerializationAttributes,
+    S: RequestSigner + ?Sized,
+{
+    let signature = sign_object(obj, signer).await?;
+    let recovered = verify_signed_request(obj, &signature)?;
+    if format!("{recovered:#x}").eq_ignore_ascii_case(user_address) {
+        Ok(signature)
+    } else {
+        Err(ServiceError::signing(format!(
+            "signature recovers to {recovered:#x}, not to user_address {user_address}"
+        )))
     }
 }
 
-impl SerializationAttributes for ProverNodeTimeRangeStatsParams {
-    fn create_message<T: Serialize>(obj: &T) -> anyhow::Result<String> {
-        serde_json::to_string(obj).map_err(Into::into)
-    }
-
-    fn requires_json_body() -> bool {
-        true
-    }
-}
+/// Utility for converting Serializable objects into multipart form fields.
+///
+/// `IntoMultipartForm` provides methods for converting `Serialize` + [`SerializationAttributes`] objects into multipart
+/// form data, which can be used in HTTP POST/PUT requests. It handles primitive values, arrays and byte fields, and
+/// sends nested objects as JSON encoded text fields.
+pub struct IntoMultipartForm;
 
-impl SerializationAttributes for AddImageParams {
-    fn fields_which_are_bytes() -> Vec<String> {
-        vec!["image".to_string()]
-    }
-    fn fields_to_ignore() -> Vec<String> {
-        vec!["image".to_string(), "initial_context".to_string()]
+impl IntoMultipartForm {
+    /// Converts a serializable object into the fields of a multipart form, in declaration order.
+    ///
+    /// The object must serialize into a struct or map. Byte fields are moved out of `params` with
+    /// [`SerializationAttributes::take_byte_fields`] and sent as a single binary field each, other fields are
+    /// serialized directly into text fields, without going through a [`serde_json::Value`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] if:
+    ///
+    /// - Serialization fails.
+    /// - The top-level value is not a struct or map.
+    /// - A byte field which was not taken out of `params` does not serialize into a sequence of `u8`.
+    pub fn into_multipart_fields<T: Serialize + SerializationAttributes>(mut params: T) -> Result<Vec<MultipartField>> {
+        let taken = params.take_byte_fields();
+        to_fields(&params, &T::fields_which_are_bytes(), taken)
     }
 }
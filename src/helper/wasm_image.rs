@@ -0,0 +1,275 @@
+use std::path::Path;
+
+use super::error::Result;
+use super::error::ServiceError;
+
+/// Function the zkWasm runtime calls to run an image.
+pub const ZKWASM_ENTRYPOINT: &str = "zkmain";
+
+/// Default for [`WasmValidator::max_size`]. A client side safety net against uploading the wrong file, not a limit
+/// published by the service.
+pub const DEFAULT_MAX_IMAGE_SIZE: usize = 16 * 1024 * 1024;
+
+const WASM_MAGIC: [u8; 4] = *b"\0asm";
+const WASM_VERSION: u32 = 1;
+const EXPORT_SECTION: u8 = 7;
+/// Highest section id of the wasm core specification, the data count section.
+const MAX_SECTION_ID: u8 = 12;
+const FUNCTION_EXPORT: u8 = 0;
+
+fn invalid(message: impl std::fmt::Display) -> ServiceError {
+    ServiceError::request(format!("invalid wasm image: {message}"))
+}
+
+/// A wasm binary which passed the checks of a [`WasmValidator`], with its md5 in the upper case hex form the service
+/// uses for image md5s, e.g. in [`super::ZkWasmServiceHelper::query_image`].
+///
+/// ```no_run
+/// # use zkp_service_helper::helper::ImageSetupRequest;
+/// # use zkp_service_helper::helper::WasmImage;
+/// # async fn example(user_address: String) -> zkp_service_helper::helper::Result<()> {
+/// let image = WasmImage::from_path("image.wasm").await?;
+/// let request = ImageSetupRequest::builder()
+///     .name("my image")
+///     .wasm_image(image)
+///     .user_address(user_address)
+///     .circuit_size(22)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WasmImage {
+    bytes: Vec<u8>,
+    md5: String,
+}
+
+impl WasmImage {
+    /// Validates `bytes` with the default [`WasmValidator`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] if the image is not a valid zkWasm image.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        WasmValidator::default().load(bytes)
+    }
+
+    /// Reads and validates the file at `path` with the default [`WasmValidator`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] if the file cannot be read or is not a valid zkWasm image.
+    pub async fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        WasmValidator::default().load_path(path).await
+    }
+
+    #[must_use]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[must_use]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// The md5 of the image, as upper case hex.
+    #[must_use]
+    pub fn md5(&self) -> &str {
+        &self.md5
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+/// Checks wasm binaries before they are added as images, so that a wrong or broken file fails locally instead of
+/// costing a setup fee.
+///
+/// A valid image is at most [`Self::max_size`] bytes long, starts with the wasm magic and version 1 header, consists
+/// of well formed sections and exports a function named [`Self::entrypoint`]. The code itself is not validated.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct WasmValidator {
+    max_size: usize,
+    entrypoint: String,
+}
+
+impl Default for WasmValidator {
+    fn default() -> Self {
+        Self {
+            max_size: DEFAULT_MAX_IMAGE_SIZE,
+            entrypoint: ZKWASM_ENTRYPOINT.to_string(),
+        }
+    }
+}
+
+impl WasmValidator {
+    /// Largest accepted image in bytes, [`DEFAULT_MAX_IMAGE_SIZE`] by default.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Function the image must export, [`ZKWASM_ENTRYPOINT`] by default.
+    pub fn entrypoint(mut self, entrypoint: impl Into<String>) -> Self {
+        self.entrypoint = entrypoint.into();
+        self
+    }
+
+    /// Checks `bytes` without computing its md5.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] describing the first check which failed.
+    pub fn validate(&self, bytes: &[u8]) -> Result<()> {
+        if bytes.len() > self.max_size {
+            return Err(invalid(format!(
+                "{} bytes exceeds the limit of {} bytes",
+                bytes.len(),
+                self.max_size
+            )));
+        }
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4).ok() != Some(WASM_MAGIC.as_slice()) {
+            return Err(invalid("missing the `\\0asm` magic number, not a wasm module"));
+        }
+        let version = reader.u32_le()?;
+        if version != WASM_VERSION {
+            return Err(invalid(format!("unsupported wasm version {version}, expected {WASM_VERSION}")));
+        }
+
+        let mut exports = Vec::new();
+        while !reader.is_at_end() {
+            let id = reader.byte()?;
+            let size = reader.u32_leb()? as usize;
+            let payload = reader.take(size)?;
+            match id {
+                EXPORT_SECTION => exports.extend(parse_exports(payload)?),
+                id if id > MAX_SECTION_ID => return Err(invalid(format!("unknown section id {id}"))),
+                _ => {}
+            }
+        }
+
+        if exports
+            .iter()
+            .any(|(name, kind)| *name == self.entrypoint && *kind == FUNCTION_EXPORT)
+        {
+            Ok(())
+        } else {
+            let names: Vec<_> = exports.iter().map(|(name, _)| name.as_str()).collect();
+            Err(invalid(format!(
+                "does not export the function `{}`, exports: [{}]",
+                self.entrypoint,
+                names.join(", ")
+            )))
+        }
+    }
+
+    /// Validates `bytes` and computes its md5.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] if the image is not valid, see [`Self::validate`].
+    pub fn load(&self, bytes: Vec<u8>) -> Result<WasmImage> {
+        self.validate(&bytes)?;
+        let md5 = format!("{:X}", md5::compute(&bytes));
+        Ok(WasmImage { bytes, md5 })
+    }
+
+    /// Reads the file at `path`, validates it and computes its md5. Files larger than [`Self::max_size`] are rejected
+    /// before they are read.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceError::Request`] if the file cannot be read or is not valid, see [`Self::validate`].
+    pub async fn load_path(&self, path: impl AsRef<Path>) -> Result<WasmImage> {
+        let path = path.as_ref();
+        let read_error = |e: std::io::Error| ServiceError::request(format!("cannot read {}: {e}", path.display()));
+        let len = tokio::fs::metadata(path).await.map_err(read_error)?.len();
+        if usize::try_from(len).map_or(true, |len| len > self.max_size) {
+            return Err(invalid(format!(
+                "{} is {len} bytes, exceeding the limit of {} bytes",
+                path.display(),
+                self.max_size
+            )));
+        }
+        self.load(tokio::fs::read(path).await.map_err(read_error)?)
+    }
+}
+
+/// Returns the name and kind of every export in the payload of an export section.
+fn parse_exports(payload: &[u8]) -> Result<Vec<(String, u8)>> {
+    let mut reader = Reader {
+        bytes: payload,
+        pos: 0,
+    };
+    let count = reader.u32_leb()?;
+    let mut exports = Vec::new();
+    for _ in 0..count {
+        let len = reader.u32_leb()? as usize;
+        let name = std::str::from_utf8(reader.take(len)?).map_err(|_| invalid("export name is not valid UTF-8"))?;
+        let kind = reader.byte()?;
+        reader.u32_leb()?;
+        exports.push((name.to_string(), kind));
+    }
+    if !reader.is_at_end() {
+        return Err(invalid("export section is longer than its exports"));
+    }
+    Ok(exports)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_at_end(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid(format!("truncated at byte {}", self.bytes.len())))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32_le(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads an unsigned LEB128 integer of at most 32 bits.
+    fn u32_leb(&mut self) -> Result<u32> {
+        let mut value = 0u32;
+        for shift in (0..35).step_by(7) {
+            let byte = self.byte()?;
+            let bits = u32::from(byte & 0x7f);
+            if shift == 28 && bits > 0x0f {
+                return Err(invalid("integer does not fit into 32 bits"));
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("integer does not fit into 32 bits"))
+    }
+}
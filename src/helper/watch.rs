@@ -0,0 +1,236 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use super::error::Result;
+use super::helper::ZkWasmServiceHelper;
+use crate::interface::Task;
+use crate::interface::TaskStatus;
+
+/// Whether a task in `status` has finished, successfully or not. `DryRunSuccess` is not final, the task is proven
+/// afterwards.
+pub(crate) fn is_terminal(status: &TaskStatus) -> bool {
+    matches!(
+        status,
+        TaskStatus::Done | TaskStatus::Fail | TaskStatus::Unprovable | TaskStatus::DryRunFailed | TaskStatus::Stale
+    )
+}
+
+/// The name of `status` as sent by the service, for messages.
+pub(crate) fn status_name(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "Pending",
+        TaskStatus::Processing => "Processing",
+        TaskStatus::DryRunSuccess => "DryRunSuccess",
+        TaskStatus::DryRunFailed => "DryRunFailed",
+        TaskStatus::Done => "Done",
+        TaskStatus::Fail => "Fail",
+        TaskStatus::Unprovable => "Unprovable",
+        TaskStatus::Stale => "Stale",
+    }
+}
+
+/// A change of the status of a watched task, passed to [`WatchOptions::on_transition`].
+pub struct StatusTransition<'a> {
+    /// The status before the change, `None` when the task is seen for the first time.
+    pub previous: Option<TaskStatus>,
+    /// The task with its new status.
+    pub task: &'a Task,
+}
+
+type TransitionCallback = Arc<dyn Fn(&StatusTransition<'_>) + Send + Sync>;
+
+/// Controls how [`TaskWatcher`] polls a task.
+///
+/// The task is polled right away and then after [`Self::initial_interval`]. While its status stays the same the interval doubles up to
+/// [`Self::max_interval`], and it starts over at the initial interval whenever the status changes. Waiting stops with
+/// [`TaskOutcome::TimedOut`] once [`Self::timeout`] has passed.
+#[derive(Clone)]
+#[must_use]
+pub struct WatchOptions {
+    timeout: Duration,
+    initial_interval: Duration,
+    max_interval: Duration,
+    on_transition: Option<TransitionCallback>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30 * 60),
+            initial_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(30),
+            on_transition: None,
+        }
+    }
+}
+
+impl fmt::Debug for WatchOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchOptions")
+            .field("timeout", &self.timeout)
+            .field("initial_interval", &self.initial_interval)
+            .field("max_interval", &self.max_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WatchOptions {
+    /// How long to wait for the task to finish, 30 minutes by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Delay before the first poll and after every status change, 1 second by default.
+    pub fn initial_interval(mut self, interval: Duration) -> Self {
+        self.initial_interval = interval;
+        self
+    }
+
+    /// Longest delay between two polls, 30 seconds by default.
+    pub fn max_interval(mut self, interval: Duration) -> Self {
+        self.max_interval = interval;
+        self
+    }
+
+    /// Calls `callback` whenever the status of the task changes, including when it is seen for the first time.
+    pub fn on_transition(mut self, callback: impl Fn(&StatusTransition<'_>) + Send + Sync + 'static) -> Self {
+        self.on_transition = Some(Arc::new(callback));
+        self
+    }
+
+    pub(crate) fn first_interval(&self) -> Duration {
+        self.initial_interval.min(self.max_interval)
+    }
+
+    pub(crate) fn next_interval(&self, interval: Duration) -> Duration {
+        interval.saturating_mul(2).min(self.max_interval)
+    }
+
+    pub(crate) fn notify(&self, previous: Option<TaskStatus>, task: &Task) {
+        if let Some(callback) = &self.on_transition {
+            callback(&StatusTransition { previous, task });
+        }
+    }
+}
+
+/// A task which failed, with the messages the service gave for it.
+pub struct TaskFailure {
+    /// `Fail`, `Unprovable`, `DryRunFailed` or `Stale`.
+    pub status: TaskStatus,
+    pub status_message: Option<String>,
+    pub internal_message: Option<String>,
+    pub task: Box<Task>,
+}
+
+/// How waiting for a task ended.
+pub enum TaskOutcome {
+    Done(Box<Task>),
+    Failed(TaskFailure),
+    /// The task did not finish within [`WatchOptions::timeout`]. `last_status` is `None` if the task was never found.
+    TimedOut {
+        last_status: Option<TaskStatus>,
+    },
+}
+
+impl TaskOutcome {
+    pub(crate) fn finished(task: Task) -> Self {
+        match task.status {
+            TaskStatus::Done => Self::Done(Box::new(task)),
+            _ => Self::Failed(TaskFailure {
+                status: task.status.clone(),
+                status_message: task.status_message.clone(),
+                internal_message: task.internal_message.clone(),
+                task: Box::new(task),
+            }),
+        }
+    }
+
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        matches!(self, Self::Done(_))
+    }
+
+    /// The last state of the task, `None` if waiting timed out.
+    #[must_use]
+    pub fn task(&self) -> Option<&Task> {
+        match self {
+            Self::Done(task) => Some(task),
+            Self::Failed(failure) => Some(&failure.task),
+            Self::TimedOut { .. } => None,
+        }
+    }
+}
+
+/// Waits for tasks to reach a terminal status, see [`ZkWasmServiceHelper::wait_for_task`].
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use zkp_service_helper::helper::TaskOutcome;
+/// # use zkp_service_helper::helper::TaskWatcher;
+/// # use zkp_service_helper::helper::WatchOptions;
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # use zkp_service_helper::interface::AddTaskResult;
+/// # async fn example(helper: ZkWasmServiceHelper, res: AddTaskResult) -> zkp_service_helper::helper::Result<()> {
+/// let watcher = TaskWatcher::new(helper.clone(), WatchOptions::default().timeout(Duration::from_secs(600)));
+/// match watcher.wait(&res.id).await? {
+///     TaskOutcome::Done(task) => println!("proof: {:?}", task.proof),
+///     TaskOutcome::Failed(failure) => eprintln!("task failed: {:?}", failure.status_message),
+///     TaskOutcome::TimedOut { .. } => eprintln!("task is still running"),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TaskWatcher {
+    helper: ZkWasmServiceHelper,
+    options: WatchOptions,
+}
+
+impl TaskWatcher {
+    #[must_use]
+    pub fn new(helper: ZkWasmServiceHelper, options: WatchOptions) -> Self {
+        Self { helper, options }
+    }
+
+    /// Polls the task `id` until it reaches a terminal status or the timeout passes. A task which cannot be found yet,
+    /// e.g. right after it was added, is polled like a running one.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of a poll which failed after the retries of the helper's [`super::RetryPolicy`].
+    pub async fn wait(&self, id: &str) -> Result<TaskOutcome> {
+        // A timeout too large to represent never passes.
+        let deadline = Instant::now().checked_add(self.options.timeout);
+        let mut interval = self.options.first_interval();
+        let mut last_status = None;
+        loop {
+            if let Some(task) = self.helper.query_task_from_id(id.to_string()).await? {
+                if last_status.as_ref() != Some(&task.status) {
+                    self.options.notify(last_status.replace(task.status.clone()), &task);
+                    interval = self.options.first_interval();
+                }
+                if is_terminal(&task.status) {
+                    return Ok(TaskOutcome::finished(task));
+                }
+            }
+
+            let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            if remaining.is_some_and(|remaining| remaining.is_zero()) {
+                return Ok(TaskOutcome::TimedOut { last_status });
+            }
+            tokio::time::sleep(remaining.map_or(interval, |remaining| interval.min(remaining))).await;
+            interval = self.options.next_interval(interval);
+        }
+    }
+}
+
+impl ZkWasmServiceHelper {
+    /// Waits for the task `id` to finish, see [`TaskWatcher::wait`].
+    pub async fn wait_for_task(&self, id: String, options: WatchOptions) -> Result<TaskOutcome> {
+        TaskWatcher::new(self.clone(), options).wait(&id).await
+    }
+}
@@ -3,11 +3,13 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::helper::SerializationAttributes;
+
 #[derive(Deserialize, Serialize, Clone, PartialEq)]
 pub struct EmptyParams;
 
 #[derive(Deserialize, Serialize, Clone, PartialEq)]
-pub struct PaginationResult<T: Serialize> {
+pub struct PaginationResult<T> {
     pub data: T,
     pub total: u64,
 }
@@ -318,13 +320,13 @@ pub struct Round2Info {
     pub status: Round2Status,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct PaginationParams {
     pub total: Option<u64>,
     pub start: Option<u64>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct PaginatedQuery<T> {
     #[serde(flatten)]
     pub query: T,
@@ -332,7 +334,8 @@ pub struct PaginatedQuery<T> {
     pub pagination: PaginationParams,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+/// Query string of a request as sent to the service, build it with [`super::AutoSubmitQuery`].
+#[derive(Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct AutoSubmitProofQuery {
     pub id: Option<String>,
     pub task_id: Option<String>,
@@ -341,7 +344,8 @@ pub struct AutoSubmitProofQuery {
     pub chain_id: Option<u32>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+/// Query string of a request as sent to the service, build it with [`super::Round1Query`].
+#[derive(Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct Round1InfoQuery {
     pub id: Option<String>,
     pub round_1_id: Option<String>,
@@ -351,7 +355,8 @@ pub struct Round1InfoQuery {
     pub chain_id: Option<u32>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+/// Query string of a request as sent to the service, build it with [`super::Round2Query`].
+#[derive(Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct Round2InfoQuery {
     pub id: Option<String>,
     pub round_2_id: Option<String>,
@@ -386,10 +391,11 @@ pub enum AddProveTaskRestrictions {
     CreatorOnly,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct BaseAddImageParams {
     pub name: String,
     pub image_md5: String,
+    #[zkp(bytes, skip_signing)]
     pub image: Vec<u8>,
     pub user_address: String,
     pub description_url: String,
@@ -403,20 +409,21 @@ pub struct BaseAddImageParams {
     pub inherited_merkle_data_md5: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct WithInitialContext {
     pub initial_context_md5: String,
+    #[zkp(skip_signing)]
     pub initial_context: Vec<u8>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 #[serde(untagged)]
 pub enum InitialContext {
     With(WithInitialContext),
     Without,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct AddImageParams {
     #[serde(flatten)]
     pub base: BaseAddImageParams,
@@ -424,7 +431,7 @@ pub struct AddImageParams {
     pub context: InitialContext,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct BaseProvingParams {
     pub user_address: String,
     pub md5: String,
@@ -433,20 +440,21 @@ pub struct BaseProvingParams {
     pub proof_submit_mode: ProofSubmitMode,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct WithCustomInputContext {
     pub input_context_type: InputContextType,
+    #[zkp(skip_signing)]
     pub input_context: Vec<u8>,
     pub input_context_md5: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct WithNonCustomInputContext {
     #[serde(default)]
     pub input_context_type: Option<serde_json::Value>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 #[serde(untagged)]
 pub enum CustomContext {
     With(WithCustomInputContext),
@@ -454,7 +462,7 @@ pub enum CustomContext {
     Without,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct ProvingParams {
     #[serde(flatten)]
     pub base: BaseProvingParams,
@@ -462,14 +470,14 @@ pub struct ProvingParams {
     pub context: CustomContext,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct DeployParams {
     pub user_address: String,
     pub md5: String,
     pub chain_id: u32,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct BaseResetImageParams {
     pub md5: String,
     pub circuit_size: u32,
@@ -480,20 +488,20 @@ pub struct BaseResetImageParams {
     pub add_prove_task_restrictions: Option<AddProveTaskRestrictions>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct WithResetContext {
     pub reset_context: Vec<u8>,
     pub reset_context_md5: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 #[serde(untagged)]
 pub enum ResetContext {
     With(WithResetContext),
     Without,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct ResetImageParams {
     #[serde(flatten)]
     pub base: BaseResetImageParams,
@@ -501,7 +509,8 @@ pub struct ResetImageParams {
     pub context: ResetContext,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
+#[zkp(message = "json")]
 pub struct ModifyImageParams {
     pub md5: String,
     pub user_address: String,
@@ -540,7 +549,8 @@ pub struct VerifyData {
     pub aux_instances: Vec<Vec<u8>>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+/// Query string of a request as sent to the service, build it with [`super::TaskQuery`].
+#[derive(Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct QueryParams {
     pub user_address: Option<String>,
     pub md5: Option<String>,
@@ -573,7 +583,8 @@ pub struct VerifyBatchProofParams {
     pub target_instances: Vec<Vec<u8>>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
+#[zkp(json_body, message = "json")]
 pub struct LogQuery {
     pub id: String,
     pub user_address: String,
@@ -719,12 +730,12 @@ pub struct Image {
     pub add_prove_task_restrictions: AddProveTaskRestrictions,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct PaymentParams {
     pub txhash: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct SubscriptionRequest {
     pub subscriber_address: String,
     pub subscription_type: SubscriptionType,
@@ -801,7 +812,7 @@ pub enum AdminRequestType {
     ForceTaskToReprocess,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct SetMaintenanceModeParams {
     pub mode: MaintenanceModeType,
     pub nonce: u64,
@@ -823,7 +834,7 @@ pub struct EstimatedProofFee {
     pub msg: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct ForceUnprovableToReprocessParams {
     pub task_ids: Vec<String>,
     pub nonce: u64,
@@ -831,7 +842,7 @@ pub struct ForceUnprovableToReprocessParams {
     pub user_address: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
 pub struct ForceDryrunFailsToReprocessParams {
     pub task_ids: Vec<String>,
     pub nonce: u64,
@@ -846,7 +857,8 @@ pub struct ProverNodeTimeRange {
     pub end: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, SerializationAttributes)]
+#[zkp(json_body, message = "json")]
 pub struct ProverNodeTimeRangeStatsParams {
     pub ranges: Vec<ProverNodeTimeRange>,
 }
@@ -905,7 +917,8 @@ pub struct VolumeDetailResponse {
     pub tasks: PaginationResult<ConciseTask>,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq)]
+/// Query string of a request as sent to the service, build it with [`super::ArchiveTaskQuery`].
+#[derive(Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct ArchiveQuery {
     pub task_id: Option<String>,
     pub md5: Option<String>,
@@ -1,2 +1,8 @@
 mod interface;
 pub use interface::*;
+mod query;
+pub use query::ArchiveTaskQuery;
+pub use query::AutoSubmitQuery;
+pub use query::Round1Query;
+pub use query::Round2Query;
+pub use query::TaskQuery;
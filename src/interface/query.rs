@@ -0,0 +1,365 @@
+use super::interface::ArchiveQuery;
+use super::interface::AutoSubmitProofQuery;
+use super::interface::AutoSubmitProofStatus;
+use super::interface::PaginatedQuery;
+use super::interface::PaginationParams;
+use super::interface::QueryParams;
+use super::interface::Round1InfoQuery;
+use super::interface::Round1Status;
+use super::interface::Round2InfoQuery;
+use super::interface::Round2Status;
+use super::interface::TaskStatus;
+use super::interface::TaskType;
+
+/// Filters and page of a task query, built into [`QueryParams`].
+///
+/// Use this builder with [`crate::helper::ZkWasmServiceHelper::query_tasks_with`] and
+/// [`crate::helper::ZkWasmServiceHelper::query_concise_tasks_with`]. [`QueryParams`] is the query string sent to the
+/// service and only needs to be filled in by hand when converting from existing code.
+///
+/// ```no_run
+/// # use zkp_service_helper::helper::ZkWasmServiceHelper;
+/// # use zkp_service_helper::interface::TaskQuery;
+/// # use zkp_service_helper::interface::TaskStatus;
+/// # async fn example(helper: ZkWasmServiceHelper, user_address: String) -> zkp_service_helper::helper::Result<()> {
+/// let query = TaskQuery::new().user(user_address).status(TaskStatus::Done).page(0, 10);
+/// let tasks = helper.query_tasks_with(query).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct TaskQuery(QueryParams);
+
+impl TaskQuery {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only tasks submitted by `user_address`.
+    #[must_use]
+    pub fn user(mut self, user_address: impl Into<String>) -> Self {
+        self.0.user_address = Some(user_address.into());
+        self
+    }
+
+    /// Only tasks of the image with the given md5.
+    #[must_use]
+    pub fn image(mut self, md5: impl Into<String>) -> Self {
+        self.0.md5 = Some(md5.into());
+        self
+    }
+
+    /// Only the task with the given id.
+    #[must_use]
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.0.id = Some(id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn kind(mut self, tasktype: TaskType) -> Self {
+        self.0.tasktype = Some(tasktype);
+        self
+    }
+
+    #[must_use]
+    pub fn status(mut self, taskstatus: TaskStatus) -> Self {
+        self.0.taskstatus = Some(taskstatus);
+        self
+    }
+
+    /// Skips the first `start` tasks and returns at most `total` tasks.
+    #[must_use]
+    pub fn page(mut self, start: u64, total: u64) -> Self {
+        self.0.start = Some(start);
+        self.0.total = Some(total);
+        self
+    }
+}
+
+impl From<TaskQuery> for QueryParams {
+    fn from(query: TaskQuery) -> Self {
+        query.0
+    }
+}
+
+impl From<QueryParams> for TaskQuery {
+    fn from(params: QueryParams) -> Self {
+        Self(params)
+    }
+}
+
+/// Filters and page of an auto submit proof query, built into a [`PaginatedQuery`] of [`AutoSubmitProofQuery`].
+///
+/// Use this builder with [`crate::helper::ZkWasmServiceHelper::query_auto_submit_proofs_with`] rather than the query
+/// string structs.
+#[derive(Clone, Default)]
+pub struct AutoSubmitQuery(PaginatedQuery<AutoSubmitProofQuery>);
+
+impl AutoSubmitQuery {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only the auto submit proof with the given id.
+    #[must_use]
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.0.query.id = Some(id.into());
+        self
+    }
+
+    /// Only the auto submit proofs of the task with the given id.
+    #[must_use]
+    pub fn task(mut self, task_id: impl Into<String>) -> Self {
+        self.0.query.task_id = Some(task_id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn status(mut self, status: AutoSubmitProofStatus) -> Self {
+        self.0.query.status = Some(status);
+        self
+    }
+
+    #[must_use]
+    pub fn circuit_size(mut self, circuit_size: u32) -> Self {
+        self.0.query.circuit_size = Some(circuit_size);
+        self
+    }
+
+    /// Only proofs submitted to the network with the given chain id.
+    #[must_use]
+    pub fn chain(mut self, chain_id: u32) -> Self {
+        self.0.query.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Skips the first `start` proofs and returns at most `total` proofs.
+    #[must_use]
+    pub fn page(mut self, start: u64, total: u64) -> Self {
+        self.0.pagination = PaginationParams {
+            total: Some(total),
+            start: Some(start),
+        };
+        self
+    }
+}
+
+impl From<AutoSubmitQuery> for PaginatedQuery<AutoSubmitProofQuery> {
+    fn from(query: AutoSubmitQuery) -> Self {
+        query.0
+    }
+}
+
+impl From<PaginatedQuery<AutoSubmitProofQuery>> for AutoSubmitQuery {
+    fn from(params: PaginatedQuery<AutoSubmitProofQuery>) -> Self {
+        Self(params)
+    }
+}
+
+/// Filters and page of a round 1 batch query, built into a [`PaginatedQuery`] of [`Round1InfoQuery`].
+///
+/// Use this builder with [`crate::helper::ZkWasmServiceHelper::query_round1_info_with`] rather than the query string
+/// structs.
+#[derive(Clone, Default)]
+pub struct Round1Query(PaginatedQuery<Round1InfoQuery>);
+
+impl Round1Query {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only the round 1 batch with the given id.
+    #[must_use]
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.0.query.id = Some(id.into());
+        self
+    }
+
+    /// Only the batch including the auto submit proof with the given id.
+    #[must_use]
+    pub fn auto_submit_proof(mut self, id: impl Into<String>) -> Self {
+        self.0.query.round_1_id = Some(id.into());
+        self
+    }
+
+    /// Only batches including the task with the given id.
+    #[must_use]
+    pub fn task(mut self, task_id: impl Into<String>) -> Self {
+        self.0.query.task_id = Some(task_id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn status(mut self, status: Round1Status) -> Self {
+        self.0.query.status = Some(status);
+        self
+    }
+
+    #[must_use]
+    pub fn circuit_size(mut self, circuit_size: u32) -> Self {
+        self.0.query.circuit_size = Some(circuit_size);
+        self
+    }
+
+    #[must_use]
+    pub fn chain(mut self, chain_id: u32) -> Self {
+        self.0.query.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Skips the first `start` batches and returns at most `total` batches.
+    #[must_use]
+    pub fn page(mut self, start: u64, total: u64) -> Self {
+        self.0.pagination = PaginationParams {
+            total: Some(total),
+            start: Some(start),
+        };
+        self
+    }
+}
+
+impl From<Round1Query> for PaginatedQuery<Round1InfoQuery> {
+    fn from(query: Round1Query) -> Self {
+        query.0
+    }
+}
+
+impl From<PaginatedQuery<Round1InfoQuery>> for Round1Query {
+    fn from(params: PaginatedQuery<Round1InfoQuery>) -> Self {
+        Self(params)
+    }
+}
+
+/// Filters and page of a round 2 (final) batch query, built into a [`PaginatedQuery`] of [`Round2InfoQuery`].
+///
+/// Use this builder with [`crate::helper::ZkWasmServiceHelper::query_round2_info_with`] rather than the query string
+/// structs.
+#[derive(Clone, Default)]
+pub struct Round2Query(PaginatedQuery<Round2InfoQuery>);
+
+impl Round2Query {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only the round 2 batch with the given id.
+    #[must_use]
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.0.query.id = Some(id.into());
+        self
+    }
+
+    /// Only the batch including the round 1 batch with the given id.
+    #[must_use]
+    pub fn round1_batch(mut self, id: impl Into<String>) -> Self {
+        self.0.query.round_2_id = Some(id.into());
+        self
+    }
+
+    /// Only batches including the task with the given id.
+    #[must_use]
+    pub fn task(mut self, task_id: impl Into<String>) -> Self {
+        self.0.query.task_id = Some(task_id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn status(mut self, status: Round2Status) -> Self {
+        self.0.query.status = Some(status);
+        self
+    }
+
+    #[must_use]
+    pub fn chain(mut self, chain_id: u32) -> Self {
+        self.0.query.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Skips the first `start` batches and returns at most `total` batches.
+    #[must_use]
+    pub fn page(mut self, start: u64, total: u64) -> Self {
+        self.0.pagination = PaginationParams {
+            total: Some(total),
+            start: Some(start),
+        };
+        self
+    }
+}
+
+impl From<Round2Query> for PaginatedQuery<Round2InfoQuery> {
+    fn from(query: Round2Query) -> Self {
+        query.0
+    }
+}
+
+impl From<PaginatedQuery<Round2InfoQuery>> for Round2Query {
+    fn from(params: PaginatedQuery<Round2InfoQuery>) -> Self {
+        Self(params)
+    }
+}
+
+/// Filters and page of an archived task query, built into [`ArchiveQuery`].
+///
+/// Use this builder with [`crate::helper::ZkWasmServiceHelper::query_archive_with`] rather than [`ArchiveQuery`].
+#[derive(Clone, Default)]
+pub struct ArchiveTaskQuery(ArchiveQuery);
+
+impl ArchiveTaskQuery {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only the archived task with the given id.
+    #[must_use]
+    pub fn task(mut self, task_id: impl Into<String>) -> Self {
+        self.0.task_id = Some(task_id.into());
+        self
+    }
+
+    /// Only archived tasks of the image with the given md5.
+    #[must_use]
+    pub fn image(mut self, md5: impl Into<String>) -> Self {
+        self.0.md5 = Some(md5.into());
+        self
+    }
+
+    /// Only tasks submitted at or after `timestamp`, e.g. `2024-07-11T04:03:56Z`.
+    #[must_use]
+    pub fn since(mut self, timestamp: impl Into<String>) -> Self {
+        self.0.start_timestamp = Some(timestamp.into());
+        self
+    }
+
+    /// Only tasks submitted at or before `timestamp`.
+    #[must_use]
+    pub fn until(mut self, timestamp: impl Into<String>) -> Self {
+        self.0.end_timestamp = Some(timestamp.into());
+        self
+    }
+
+    /// Skips the first `start` tasks and returns at most `limit` tasks.
+    #[must_use]
+    pub fn page(mut self, start: u64, limit: u64) -> Self {
+        self.0.start = Some(start);
+        self.0.limit = Some(limit);
+        self
+    }
+}
+
+impl From<ArchiveTaskQuery> for ArchiveQuery {
+    fn from(query: ArchiveTaskQuery) -> Self {
+        query.0
+    }
+}
+
+impl From<ArchiveQuery> for ArchiveTaskQuery {
+    fn from(params: ArchiveQuery) -> Self {
+        Self(params)
+    }
+}
@@ -18,40 +18,62 @@
 //!
 //! ### Example Query Image (GET request)
 //!
-//! ```
-//! let res  = ZkWasmServiceHelper::new(endpoint).query_image(md5).await?;
+//! ```no_run
+//! # use zkp_service_helper::helper::ZkWasmServiceHelper;
+//! # async fn example(endpoint: String, md5: String) -> zkp_service_helper::helper::Result<()> {
+//! let res = ZkWasmServiceHelper::new(endpoint).query_image(md5).await?;
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! ### Example Add Image (POST request)
 //!
-//! ```
-//! let (buffer, md5) = read_wasm(image_file_path)?;
-//!
-//! let res = ZkWasmServiceHelper::new(endpoint).setup_image(
-//!     md5.clone(),
-//!     buffer,
-//!     md5.clone(),
-//!     user_address.clone(),
-//!     format!("ZKP CLI test image {md5}"),
-//!     String::new(),
-//!     22,
-//!     interface::ProvePaymentSrc::Default,
-//!     vec![chain_id],
-//!     None,
-//!     None,
-//!     interface::InitialContext::Without,
-//!     private_key.clone(),
-//! );
+//! ```no_run
+//! # use zkp_service_helper::helper::ImageSetupRequest;
+//! # use zkp_service_helper::helper::UploadSource;
+//! # use zkp_service_helper::helper::WalletSigner;
+//! # use zkp_service_helper::helper::ZkWasmServiceHelper;
+//! # async fn example(
+//! #     endpoint: String,
+//! #     image_file_path: &str,
+//! #     user_address: String,
+//! #     chain_id: u32,
+//! #     private_key: String,
+//! # ) -> zkp_service_helper::helper::Result<()> {
+//! let image = UploadSource::from_path(image_file_path)
+//!     .await?
+//!     .on_progress(|p| println!("sent {} of {:?} bytes", p.sent, p.total));
+//! let md5 = image.md5().to_string();
+//!
+//! let request = ImageSetupRequest::builder()
+//!     .name(md5.clone())
+//!     .image_source(image)
+//!     .user_address(user_address.clone())
+//!     .description_url(format!("ZKP CLI test image {md5}"))
+//!     .circuit_size(22)
+//!     .auto_submit_network_ids(vec![chain_id])
+//!     .build()?;
+//! let signer = WalletSigner::from_private_key(&private_key)?;
+//! let res = ZkWasmServiceHelper::new(endpoint).setup_image_with(request, &signer).await?;
+//! # Ok(())
+//! # }
 //! ```
 //! ## More Examples
 //!
 //! See [src/tests](https://github.com/qozymandias/zkp-service-helper/tree/main/src/tests) for more usage examples.
 
+// Lets `#[derive(SerializationAttributes)]` refer to this crate by name from within it.
+extern crate self as zkp_service_helper;
+
 /// Contains the `ZkWasmServiceHelper` struct which has functions for running API requests.
 pub mod helper;
 
 /// Struct library containing at the types required to use ZKP API.
 pub mod interface;
 
+/// An in-memory mock of the `ZkWasm` service for testing without network access.
+#[cfg(feature = "mock")]
+pub mod mock;
+
 #[cfg(test)]
 mod tests;
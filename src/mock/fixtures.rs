@@ -0,0 +1,487 @@
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use super::state::complete_task;
+use super::state::new_task;
+use super::state::timestamp;
+use super::state::verifier_contracts;
+use super::state::MockArchive;
+use super::state::MockImage;
+use super::state::MockState;
+use crate::interface::AddProveTaskRestrictions;
+use crate::interface::AppConfig;
+use crate::interface::ArchiveConfig;
+use crate::interface::ArchiveServerConfig;
+use crate::interface::ArchiveVolumeMetadata;
+use crate::interface::ArchivedFinalBatchProof;
+use crate::interface::AutoSubmitBatchMetadata;
+use crate::interface::AutoSubmitProof;
+use crate::interface::AutoSubmitProofStatus;
+use crate::interface::AutoSubmitStatus;
+use crate::interface::BaseSubscriptionDuration;
+use crate::interface::BatchProofData;
+use crate::interface::ChainInfo;
+use crate::interface::DeploymentInfo;
+use crate::interface::ERC20DepositInfo;
+use crate::interface::Image;
+use crate::interface::NodeStatistics;
+use crate::interface::ObjectId;
+use crate::interface::OnlineNodeInfo;
+use crate::interface::ProofSubmitMode;
+use crate::interface::ProvePaymentSrc;
+use crate::interface::ProverLevel;
+use crate::interface::ProverNode;
+use crate::interface::Round1Info;
+use crate::interface::Round1Status;
+use crate::interface::Round2Info;
+use crate::interface::Round2Status;
+use crate::interface::ServerVersionInfo;
+use crate::interface::StaticFileVerificationData;
+use crate::interface::Subscription;
+use crate::interface::SubscriptionDuration;
+use crate::interface::SubscriptionParams;
+use crate::interface::SubscriptionStatus;
+use crate::interface::SubscriptionType;
+use crate::interface::TaskFeeList;
+use crate::interface::TaskType;
+use crate::interface::TokenData;
+use crate::interface::TokenParams;
+use crate::interface::TransactionInfo;
+use crate::interface::User;
+use crate::interface::VersionInfo;
+use crate::interface::VolumeRange;
+
+/// A well known development key (the first account of Hardhat and Anvil). Never use it on a real network.
+const DEV_PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const DEV_ADDRESS: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+const CHAIN_ID: u32 = 10143;
+const CIRCUIT_SIZE: u32 = 22;
+/// A minimal wasm module: the magic number and version.
+const IMAGE: &[u8] = b"\0asm\x01\0\0\0";
+
+/// Identifiers of the records the mock server is seeded with.
+///
+/// The seeded user owns an image, a proven task, a task included in round 1 and round 2 batches and has enough credits
+/// to submit further tasks. The archive holds one task volume and one auto submit volume.
+#[derive(Clone, Debug)]
+pub struct MockFixtures {
+    /// Private key of the seeded user, a well known development key which must never be used on a real network.
+    pub private_key: String,
+    pub user_address: String,
+    /// Chain supported for auto submission and used by the seeded batches.
+    pub chain_id: u32,
+    pub image_md5: String,
+    /// A proven task of the seeded image.
+    pub task_id: String,
+    /// The certified prover node which proved the seeded tasks.
+    pub node_address: String,
+    pub round1_id: String,
+    pub round2_id: String,
+    /// A task whose proof was auto submitted in the round 1 and round 2 batches.
+    pub auto_submit_task_id: String,
+    /// Id of the archived final batch proof.
+    pub archive_id: String,
+    pub archived_task_id: String,
+    pub archive_volume_name: String,
+    pub archive_auto_submit_volume_name: String,
+    /// Hash of a deposit to the receiver address which can be claimed once through `/pay` or `/subscribe`.
+    pub unclaimed_deposit_txhash: String,
+}
+
+fn app_config() -> AppConfig {
+    AppConfig {
+        deployer_address: format!("0x{:040x}", 0xde9u64),
+        receiver_address: format!("0x{:040x}", 0x7ecu64),
+        task_fee_list: TaskFeeList {
+            setup_fee: "5000".to_string(),
+            prove_fee: "1000".to_string(),
+            auto_submit_prove_fee_per_network: "500".to_string(),
+        },
+        chain_info_list: vec![ChainInfo {
+            chain_id: CHAIN_ID,
+            chain_name: "Monad Testnet".to_string(),
+            block_explorer_url: "https://testnet.monadexplorer.com".to_string(),
+            deploy_fee: "0".to_string(),
+        }],
+        latest_server_checksum: vec![1, 2, 3, 4],
+        topup_token_params: token_params(),
+        topup_token_data: token_data(),
+        supported_auto_submit_network_ids: vec![CHAIN_ID],
+        server_version_info: ServerVersionInfo {
+            current_version: "0.1.0".to_string(),
+            minimum_supported_node_version: "0.1.0".to_string(),
+        },
+    }
+}
+
+fn token_params() -> TokenParams {
+    TokenParams {
+        token_address: format!("0x{:040x}", 0x70cu64),
+        network_id: CHAIN_ID,
+        topup_conversion_rate: Some(1),
+    }
+}
+
+fn token_data() -> TokenData {
+    TokenData {
+        decimals: 18,
+        symbol: "USDT".to_string(),
+    }
+}
+
+fn deposit(txhash: String, user_address: &str, amount: u128) -> ERC20DepositInfo {
+    ERC20DepositInfo {
+        user_address: user_address.to_string(),
+        receiver_address: app_config().receiver_address,
+        txhash,
+        amount: amount.to_string(),
+        token_params: token_params(),
+        token_data: token_data(),
+    }
+}
+
+fn node(address: String, prover_level: ProverLevel, now: DateTime<Utc>) -> ProverNode {
+    ProverNode {
+        statistics: NodeStatistics {
+            successful_tasks: 2,
+            failed_tasks: 0,
+            total_tasks: 2,
+            timed_out_count: 0,
+            last_timed_out: None,
+            last_timed_out_task_id: None,
+            last_failed_ts: None,
+            last_failed_task_id: None,
+            last_failed_task_log: None,
+            setup_timing_stats: None,
+            proof_timing_stats: None,
+        },
+        version_info: Some(VersionInfo {
+            version: "0.1.0".to_string(),
+        }),
+        performance_track: "Fast".to_string(),
+        prover_level: prover_level.clone(),
+        last_attempted_task: None,
+        online_activity: Some(OnlineNodeInfo {
+            address: address.clone(),
+            prover_level,
+            last_completed_dry_run_task_id: None,
+            last_active_time: timestamp(now),
+            online: true,
+        }),
+        address,
+    }
+}
+
+fn archive_volume(
+    volume_name: &str,
+    original_coll_name: &str,
+    id: String,
+    n_records: u64,
+    at: &str,
+) -> ArchiveVolumeMetadata {
+    ArchiveVolumeMetadata {
+        version: "1".to_string(),
+        volume_name: volume_name.to_string(),
+        original_coll_name: original_coll_name.to_string(),
+        range: Some(VolumeRange {
+            n_records,
+            fst: ObjectId { oid: id.clone() },
+            lst: ObjectId { oid: id.clone() },
+            fst_ts: at.to_string(),
+            lst_ts: at.to_string(),
+        }),
+        _id: ObjectId { oid: id },
+        prev_last_ts: at.to_string(),
+        image_md5s: None,
+    }
+}
+
+/// Builds the initial state of the mock server and the identifiers of the seeded records.
+#[allow(deprecated, clippy::too_many_lines)]
+pub(super) fn seed(task_duration: Duration) -> (MockState, MockFixtures) {
+    let now = Utc::now();
+    let an_hour_ago = now - chrono::Duration::hours(1);
+    let a_month_ago = now - chrono::Duration::days(30);
+    let image_md5 = format!("{:X}", md5::compute(IMAGE));
+
+    let mut state = MockState::new(
+        app_config(),
+        MockArchive {
+            server_config: ArchiveServerConfig {
+                prod_mongodb_uri: "mongodb://localhost:27017/zkwasm".to_string(),
+                archive_mongodb_uri: "mongodb://localhost:27017/archive".to_string(),
+                volume_dump_dir: "/tmp/volumes".to_string(),
+                network_list: vec![],
+                archive_config: ArchiveConfig {
+                    processing_tasks_limit: 1000,
+                    processing_time_limit_mins: 60,
+                    archive_cron_schedule: "0 0 * * *".to_string(),
+                    cleanup_cron_schedule: "0 1 * * *".to_string(),
+                    scheduled_archive_days_behind: 90,
+                },
+            },
+            task_volumes: vec![],
+            auto_submit_volumes: vec![],
+            tasks: vec![],
+            final_proofs: vec![],
+        },
+        task_duration,
+    );
+    state.admins.push(DEV_ADDRESS.to_string());
+
+    state.users.push(User {
+        user_address: DEV_ADDRESS.to_string(),
+        balance: vec![],
+        credits: "1000000".to_string(),
+        credit_deficit: "0".to_string(),
+    });
+    let txhash = format!("0x{}", "1".repeat(64));
+    state.transactions.push(TransactionInfo {
+        txhash: txhash.clone(),
+        value: vec![0x0f, 0x42, 0x40],
+        user_address: DEV_ADDRESS.to_string(),
+        receiver_address: state.app_config.receiver_address.clone(),
+    });
+    let payment = deposit(txhash, DEV_ADDRESS, 1_000_000);
+    state.deposits.push(payment.clone());
+    state.subscriptions.push(Subscription {
+        subscriber_address: DEV_ADDRESS.to_string(),
+        start_date: u64::try_from(a_month_ago.timestamp()).unwrap_or_default(),
+        end_date: u64::try_from((now + chrono::Duration::days(335)).timestamp()).unwrap_or_default(),
+        params: SubscriptionParams {
+            subscription_type: SubscriptionType::Developer,
+            duration: SubscriptionDuration {
+                base_duration: BaseSubscriptionDuration::Year,
+                multiplier: 1,
+            },
+            token_params: token_params(),
+            token_data: token_data(),
+            price_per_base_duration: "1000".to_string(),
+            credited_amount: "1000000".to_string(),
+            enabled: true,
+        },
+        status: SubscriptionStatus::Active,
+        payment_details: vec![payment],
+    });
+    let unclaimed_deposit_txhash = format!("0x{}", "2".repeat(64));
+    state
+        .unclaimed_deposits
+        .push(deposit(unclaimed_deposit_txhash.clone(), DEV_ADDRESS, 50_000));
+
+    let node_address = "0x3552f5e0bfccf79a87a304e80455b368af9b56f6".to_string();
+    state.nodes.push(node(node_address.clone(), ProverLevel::Certified, now));
+    for (idx, level) in [ProverLevel::Active, ProverLevel::Intern, ProverLevel::Inactive]
+        .into_iter()
+        .enumerate()
+    {
+        state.nodes.push(node(format!("0x{:040x}", 0x40de + idx), level, now));
+    }
+
+    state.images.push(MockImage {
+        image: Image {
+            user_address: DEV_ADDRESS.to_string(),
+            md5: image_md5.clone(),
+            deployment: vec![DeploymentInfo {
+                chain_id: CHAIN_ID,
+                address: format!("0x{:040x}", 0xc0de_u64),
+            }],
+            description_url: "Seeded mock image".to_string(),
+            avator_url: String::new(),
+            circuit_size: CIRCUIT_SIZE,
+            context: None,
+            initial_context: None,
+            status: "Verified".to_string(),
+            checksum: None,
+            prove_payment_src: ProvePaymentSrc::Default,
+            auto_submit_network_ids: vec![CHAIN_ID],
+            inherited_merkle_data_info: None,
+            add_prove_task_restrictions: AddProveTaskRestrictions::Anyone,
+        },
+        binary: IMAGE.to_vec(),
+    });
+
+    let mut setup = new_task(
+        state.next_id(),
+        DEV_ADDRESS.to_string(),
+        image_md5.clone(),
+        TaskType::Setup,
+        a_month_ago,
+    );
+    complete_task(&mut setup, Some(node_address.clone()), a_month_ago);
+    state.insert_task(setup);
+
+    let task_id = state.next_id();
+    let mut task = new_task(
+        task_id.clone(),
+        DEV_ADDRESS.to_string(),
+        image_md5.clone(),
+        TaskType::Prove,
+        an_hour_ago,
+    );
+    task.public_inputs = vec!["0x1:i64".to_string()];
+    task.proof_submit_mode = Some(ProofSubmitMode::Manual);
+    complete_task(&mut task, Some(node_address.clone()), an_hour_ago);
+
+    let (round1_id, round2_id, auto_submit_id, auto_submit_task_id) =
+        (state.next_id(), state.next_id(), state.next_id(), state.next_id());
+    let mut auto_task = new_task(
+        auto_submit_task_id.clone(),
+        DEV_ADDRESS.to_string(),
+        image_md5.clone(),
+        TaskType::Prove,
+        an_hour_ago,
+    );
+    auto_task.proof_submit_mode = Some(ProofSubmitMode::Auto);
+    auto_task.auto_submit_status = Some(AutoSubmitStatus::RegisteredProof);
+    auto_task.batch_proof_data = Some(BatchProofData {
+        round_1_batch_ids: Some(vec![AutoSubmitBatchMetadata {
+            chain_id: CHAIN_ID,
+            id: round1_id.clone(),
+        }]),
+        round_2_batch_ids: Some(vec![AutoSubmitBatchMetadata {
+            chain_id: CHAIN_ID,
+            id: round2_id.clone(),
+        }]),
+        final_proof_batch_ids: None,
+    });
+    complete_task(&mut auto_task, Some(node_address.clone()), an_hour_ago);
+
+    let static_files = StaticFileVerificationData {
+        static_file_checksum: vec![5, 6, 7, 8],
+    };
+    state.auto_submit_proofs.push(AutoSubmitProof {
+        _id: Some(ObjectId {
+            oid: auto_submit_id.clone(),
+        }),
+        task_id: auto_submit_task_id.clone(),
+        base_proof_circuit_size: CIRCUIT_SIZE,
+        proof: auto_task.proof.clone(),
+        batch_instances: auto_task.instances.clone(),
+        shadow_instances: None,
+        aux: auto_task.aux.clone(),
+        batch_started: Some(timestamp(an_hour_ago)),
+        batch_finished: Some(timestamp(an_hour_ago)),
+        internal_message: None,
+        static_files_verification_data: static_files.clone(),
+        auto_submit_network_chain_id: CHAIN_ID,
+        status: AutoSubmitProofStatus::Batched,
+    });
+    state.round1.push(Round1Info {
+        _id: Some(ObjectId {
+            oid: round1_id.clone(),
+        }),
+        round_1_ids: vec![auto_submit_id],
+        task_ids: vec![auto_submit_task_id.clone()],
+        target_instances: vec![auto_task.instances.clone()],
+        proof: vec![1; 16],
+        batch_instances: vec![2; 16],
+        shadow_instances: None,
+        aux: vec![3; 16],
+        batch_started: Some(timestamp(an_hour_ago)),
+        batch_finished: Some(timestamp(an_hour_ago)),
+        internal_message: None,
+        auto_submit_network_chain_id: CHAIN_ID,
+        verifier_contracts: verifier_contracts(CHAIN_ID, CIRCUIT_SIZE),
+        static_files_verification_data: static_files.clone(),
+        status: Round1Status::Batched,
+    });
+    state.round2.push(Round2Info {
+        _id: Some(ObjectId {
+            oid: round2_id.clone(),
+        }),
+        round_2_ids: vec![round1_id.clone()],
+        task_ids: vec![auto_submit_task_id.clone()],
+        target_instances: vec![auto_task.instances.clone()],
+        proof: vec![4; 16],
+        batch_instances: vec![5; 16],
+        shadow_instances: None,
+        aux: vec![6; 16],
+        batched_time: Some(timestamp(an_hour_ago)),
+        internal_message: None,
+        static_files_verification_data: static_files.clone(),
+        auto_submit_network_chain_id: CHAIN_ID,
+        verifier_contracts: verifier_contracts(CHAIN_ID, CIRCUIT_SIZE),
+        registered_tx_hash: Some(format!("0x{}", "3".repeat(64))),
+        status: Round2Status::ProofRegistered,
+    });
+    state.insert_task(auto_task);
+    state.insert_task(task);
+
+    let (archive_id, archived_task_id) = (state.next_id(), state.next_id());
+    let archive_volume_name = "vol_1".to_string();
+    let archive_auto_submit_volume_name = "auto_submit_vol_1".to_string();
+    let mut archived_task = new_task(
+        archived_task_id.clone(),
+        DEV_ADDRESS.to_string(),
+        image_md5.clone(),
+        TaskType::Prove,
+        a_month_ago,
+    );
+    complete_task(&mut archived_task, Some(node_address.clone()), a_month_ago);
+    let archived_at = timestamp(a_month_ago);
+    let volume_id = state.next_id();
+    state
+        .archive
+        .task_volumes
+        .push(archive_volume(&archive_volume_name, "tasks", volume_id, 1, &archived_at));
+    let volume_id = state.next_id();
+    state.archive.auto_submit_volumes.push(archive_volume(
+        &archive_auto_submit_volume_name,
+        "final_batch_proofs",
+        volume_id,
+        1,
+        &archived_at,
+    ));
+    let original_final_proof_id = state.next_id();
+    state.archive.final_proofs.push((
+        archive_auto_submit_volume_name.clone(),
+        ArchivedFinalBatchProof {
+            _id: ObjectId {
+                oid: archive_id.clone(),
+            },
+            original_final_proof_id,
+            included_md5s: vec![image_md5.clone()],
+            round_2_ids: vec![],
+            round_1_ids: vec![],
+            task_ids: vec![archived_task_id.clone()],
+            target_instances: vec![archived_task.instances.clone()],
+            proof: vec![7; 16],
+            batch_instances: vec![8; 16],
+            shadow_instances: vec![],
+            aux: vec![9; 16],
+            round_1_proof: vec![],
+            round_1_batch_instances: vec![],
+            round_1_shadow_instances: vec![],
+            round_1_aux: vec![],
+            round_1_target_instances: vec![],
+            batched_time: archived_at,
+            internal_message: None,
+            static_files_verification_data: Some(static_files),
+            auto_submit_network_chain_id: CHAIN_ID,
+            verifier_contracts: verifier_contracts(CHAIN_ID, CIRCUIT_SIZE),
+            registered_tx_hash: format!("0x{}", "4".repeat(64)),
+            status: Round2Status::ProofRegistered,
+        },
+    ));
+    state.archive.tasks.push((archive_volume_name.clone(), archived_task));
+
+    let fixtures = MockFixtures {
+        private_key: DEV_PRIVATE_KEY.to_string(),
+        user_address: DEV_ADDRESS.to_string(),
+        chain_id: CHAIN_ID,
+        image_md5,
+        task_id,
+        node_address,
+        round1_id,
+        round2_id,
+        auto_submit_task_id,
+        archive_id,
+        archived_task_id,
+        archive_volume_name,
+        archive_auto_submit_volume_name,
+        unclaimed_deposit_txhash,
+    };
+    (state, fixtures)
+}
@@ -0,0 +1,8 @@
+mod fixtures;
+pub use fixtures::MockFixtures;
+mod request;
+mod routes;
+mod server;
+pub use server::MockConfig;
+pub use server::MockServer;
+mod state;
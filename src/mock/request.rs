@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use axum::extract::multipart::Multipart;
+use axum::extract::multipart::MultipartRejection;
+use axum::http::HeaderMap;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::Json;
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::helper::endpoint::RequestResult;
+use crate::helper::verify_signed_request;
+use crate::helper::SerializationAttributes;
+use crate::helper::ServiceError;
+
+/// A request rejected by the mock server, answered with the same failure envelope as the real service.
+#[derive(Debug)]
+pub(super) struct MockError {
+    status: StatusCode,
+    message: String,
+}
+
+impl MockError {
+    pub(super) fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub(super) fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub(super) fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, message)
+    }
+
+    pub(super) fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    pub(super) fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    code: u16,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct FailureBody {
+    success: bool,
+    error: ErrorBody,
+}
+
+impl IntoResponse for MockError {
+    fn into_response(self) -> Response {
+        let body = FailureBody {
+            success: false,
+            error: ErrorBody {
+                code: self.status.as_u16(),
+                message: self.message,
+            },
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// The response of a mock route, either a success envelope around `T` or a failure envelope.
+pub(super) type Reply<T> = Result<Json<RequestResult<T>>, MockError>;
+
+#[allow(clippy::unnecessary_wraps)]
+pub(super) fn ok<T: Serialize>(result: T) -> Reply<T> {
+    Ok(Json(RequestResult {
+        success: true,
+        result,
+    }))
+}
+
+/// Decodes the URL encoded query string of a GET request.
+pub(super) fn query<T: DeserializeOwned>(query: Option<&str>) -> Result<T, MockError> {
+    serde_urlencoded::from_str(query.unwrap_or_default())
+        .map_err(|e| MockError::bad_request(format!("Invalid query: {e}")))
+}
+
+/// Decodes a JSON request body.
+pub(super) fn json<T: DeserializeOwned>(body: &Bytes) -> Result<T, MockError> {
+    serde_json::from_slice(body).map_err(|e| MockError::bad_request(format!("Invalid body: {e}")))
+}
+
+/// Checks the `x-eth-signature` header of a request the way the service does.
+///
+/// The signer is recovered from the signature over the decoded request payload with [`verify_signed_request`] and must
+/// be `user_address`.
+pub(super) fn verify_signature<T: Serialize + SerializationAttributes>(
+    params: &T,
+    user_address: &str,
+    headers: &HeaderMap,
+) -> Result<(), MockError> {
+    let signature = headers
+        .get("x-eth-signature")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| MockError::unauthorized("Missing signature"))?;
+    let signer = verify_signed_request(params, signature).map_err(|e| match e {
+        ServiceError::Request(e) => MockError::bad_request(e.to_string()),
+        e => MockError::unauthorized(format!("Invalid signature: {e}")),
+    })?;
+    if format!("{signer:#x}").eq_ignore_ascii_case(user_address) {
+        Ok(())
+    } else {
+        Err(MockError::unauthorized("Signature does not match user_address"))
+    }
+}
+
+/// The fields of a multipart request body, in the order they were sent.
+pub(super) struct Form {
+    fields: Vec<(String, Bytes)>,
+}
+
+impl Form {
+    pub(super) async fn read(multipart: std::result::Result<Multipart, MultipartRejection>) -> Result<Self, MockError> {
+        let mut multipart = multipart.map_err(|e| MockError::bad_request(e.body_text()))?;
+        let mut fields = vec![];
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| MockError::bad_request(e.body_text()))?
+        {
+            let name = field.name().unwrap_or_default().to_string();
+            let value = field.bytes().await.map_err(|e| MockError::bad_request(e.body_text()))?;
+            fields.push((name, value));
+        }
+        Ok(Self { fields })
+    }
+
+    fn values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Bytes> + 'a {
+        self.fields.iter().filter(move |(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    fn to_text(name: &str, value: &Bytes) -> Result<String, MockError> {
+        String::from_utf8(value.to_vec()).map_err(|_| MockError::bad_request(format!("Field `{name}` is not UTF-8")))
+    }
+
+    pub(super) fn opt_text(&self, name: &str) -> Result<Option<String>, MockError> {
+        self.values(name).next().map(|v| Self::to_text(name, v)).transpose()
+    }
+
+    pub(super) fn text(&self, name: &str) -> Result<String, MockError> {
+        self.opt_text(name)?
+            .ok_or_else(|| MockError::bad_request(format!("Missing field `{name}`")))
+    }
+
+    pub(super) fn bytes(&self, name: &str) -> Result<Vec<u8>, MockError> {
+        self.values(name)
+            .next()
+            .map(|v| v.to_vec())
+            .ok_or_else(|| MockError::bad_request(format!("Missing field `{name}`")))
+    }
+
+    pub(super) fn parse<T: FromStr>(&self, name: &str) -> Result<T, MockError> {
+        self.text(name)?
+            .parse()
+            .map_err(|_| MockError::bad_request(format!("Invalid field `{name}`")))
+    }
+
+    /// All values of a repeated field, e.g. the elements of an array. Empty if the field was not sent.
+    pub(super) fn list<T: FromStr>(&self, name: &str) -> Result<Vec<T>, MockError> {
+        self.values(name)
+            .map(|v| {
+                Self::to_text(name, v)?
+                    .parse()
+                    .map_err(|_| MockError::bad_request(format!("Invalid field `{name}`")))
+            })
+            .collect()
+    }
+
+    /// A field holding the name of a unit enum variant, e.g. `ProvePaymentSrc`.
+    pub(super) fn opt_variant<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, MockError> {
+        self.opt_text(name)?
+            .map(|v| {
+                serde_json::from_value(serde_json::Value::String(v))
+                    .map_err(|_| MockError::bad_request(format!("Invalid field `{name}`")))
+            })
+            .transpose()
+    }
+
+    pub(super) fn variant<T: DeserializeOwned>(&self, name: &str) -> Result<T, MockError> {
+        self.opt_variant(name)?
+            .ok_or_else(|| MockError::bad_request(format!("Missing field `{name}`")))
+    }
+}
@@ -0,0 +1,1129 @@
+use axum::extract::multipart::Multipart;
+use axum::extract::multipart::MultipartRejection;
+use axum::extract::rejection::PathRejection;
+use axum::extract::Path;
+use axum::extract::RawQuery;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::routing::get;
+use axum::routing::post;
+use axum::Router;
+use bytes::Bytes;
+use chrono::DateTime;
+use chrono::Utc;
+
+use super::request::json;
+use super::request::ok;
+use super::request::query;
+use super::request::verify_signature;
+use super::request::Form;
+use super::request::MockError;
+use super::request::Reply;
+use super::state::concise;
+use super::state::new_task;
+use super::state::paginate;
+use super::state::timestamp;
+use super::state::MockImage;
+use super::state::SharedState;
+use crate::helper::TaskEndpoint;
+use crate::interface::AddImageParams;
+use crate::interface::AddProveTaskRestrictions;
+use crate::interface::AddTaskResult;
+use crate::interface::AdminRequestType;
+use crate::interface::AppConfig;
+use crate::interface::ArchiveMetadataOverview;
+use crate::interface::ArchiveQuery;
+use crate::interface::ArchiveServerConfig;
+use crate::interface::ArchiveVolumeMetadata;
+use crate::interface::ArchivedFinalBatchProof;
+use crate::interface::ArchivedFinalProofNetworkInfo;
+use crate::interface::AutoSubmitProof;
+use crate::interface::AutoSubmitProofQuery;
+use crate::interface::BaseAddImageParams;
+use crate::interface::BaseProvingParams;
+use crate::interface::BaseResetImageParams;
+use crate::interface::ConciseTask;
+use crate::interface::CustomContext;
+use crate::interface::DeployParams;
+use crate::interface::DeploymentInfo;
+use crate::interface::ERC20DepositInfo;
+use crate::interface::EstimatedProofFee;
+use crate::interface::EstimatedProofFeeParams;
+use crate::interface::ForceDryrunFailsToReprocessParams;
+use crate::interface::ForceUnprovableToReprocessParams;
+use crate::interface::Image;
+use crate::interface::InitialContext;
+use crate::interface::LogQuery;
+use crate::interface::MaintenanceModeType;
+use crate::interface::ModifyImageParams;
+use crate::interface::NodeStatisticsQueryParams;
+use crate::interface::ObjectId;
+use crate::interface::OnlineNodesSummary;
+use crate::interface::PaginationParams;
+use crate::interface::PaginationResult;
+use crate::interface::PaymentParams;
+use crate::interface::ProofSubmitMode;
+use crate::interface::ProverLevel;
+use crate::interface::ProverNode;
+use crate::interface::ProverNodeTimeRangeStats;
+use crate::interface::ProverNodeTimeRangeStatsParams;
+use crate::interface::ProverNodesSummary;
+use crate::interface::ProvingParams;
+use crate::interface::QueryImageParams;
+use crate::interface::QueryParams;
+use crate::interface::RangeStats;
+use crate::interface::ResetContext;
+use crate::interface::ResetImageParams;
+use crate::interface::Round1Info;
+use crate::interface::Round1InfoQuery;
+use crate::interface::Round2Info;
+use crate::interface::Round2InfoQuery;
+use crate::interface::SetMaintenanceModeParams;
+use crate::interface::StatisticsInfo;
+use crate::interface::Subscription;
+use crate::interface::SubscriptionParams;
+use crate::interface::SubscriptionRequest;
+use crate::interface::SubscriptionStatus;
+use crate::interface::Task;
+use crate::interface::TaskExternalHostTable;
+use crate::interface::TaskExternalHostTableParams;
+use crate::interface::TaskStatus;
+use crate::interface::TaskType;
+use crate::interface::TransactionInfo;
+use crate::interface::TxHistoryQueryParams;
+use crate::interface::User;
+use crate::interface::UserQueryParams;
+use crate::interface::VolumeDetailQuery;
+use crate::interface::VolumeDetailResponse;
+use crate::interface::VolumeListQuery;
+use crate::interface::WithCustomInputContext;
+use crate::interface::WithInitialContext;
+use crate::interface::WithNonCustomInputContext;
+use crate::interface::WithResetContext;
+
+type MultipartBody = std::result::Result<Multipart, MultipartRejection>;
+
+fn route(endpoint: &TaskEndpoint) -> String {
+    format!("/{}", endpoint.as_path())
+}
+
+/// Builds the router serving every [`TaskEndpoint`].
+pub(super) fn router(state: SharedState) -> Router {
+    let id = || String::new();
+    Router::new()
+        .route(&route(&TaskEndpoint::Image), get(image))
+        .route(&route(&TaskEndpoint::ImageBinary), get(image_binary))
+        .route(&route(&TaskEndpoint::User), get(user))
+        .route(&route(&TaskEndpoint::UserSubscription), get(user_subscription))
+        .route(&route(&TaskEndpoint::Transactions), get(transactions))
+        .route(&route(&TaskEndpoint::Deposits), get(deposits))
+        .route(&route(&TaskEndpoint::Config), get(config))
+        .route(&route(&TaskEndpoint::Statistics), get(statistics))
+        .route(&route(&TaskEndpoint::NodeStatistics), get(node_statistics))
+        .route(&route(&TaskEndpoint::ProverNodeSummary), get(prover_node_summary))
+        .route(&route(&TaskEndpoint::OnlineNodesSummary), get(online_nodes_summary))
+        .route(&route(&TaskEndpoint::Tasks), get(tasks))
+        .route(&route(&TaskEndpoint::ConciseTasks), get(concise_tasks))
+        .route(&route(&TaskEndpoint::TaskExternalHostTable), get(task_external_host_table))
+        .route(&route(&TaskEndpoint::Round1Batch), get(round1_batch))
+        .route(&route(&TaskEndpoint::Round2Batch), get(round2_batch))
+        .route(&route(&TaskEndpoint::FinalBatch), get(final_batch))
+        .route(&route(&TaskEndpoint::Logs), get(logs))
+        .route(&route(&TaskEndpoint::EstimatedProofFee), get(estimated_proof_fee))
+        .route(
+            &route(&TaskEndpoint::ProverNodeTimerangeStats),
+            post(prover_node_timerange_stats),
+        )
+        .route(&route(&TaskEndpoint::ArchiveSummary), get(archive_summary))
+        .route(&route(&TaskEndpoint::ArchiveTaskVolumeList), get(archive_task_volume_list))
+        .route(
+            &route(&TaskEndpoint::ArchiveAutoSubmitTaskVolumeList),
+            get(archive_auto_submit_volume_list),
+        )
+        .route(&format!("{}/:id", route(&TaskEndpoint::ArchiveTask(id()))), get(archive_task))
+        .route(
+            &format!("{}/:id", route(&TaskEndpoint::ArchiveAutoSubmitNetworks(id()))),
+            get(archive_auto_submit_networks),
+        )
+        .route(
+            &format!("{}/:id/:chain_id", route(&TaskEndpoint::ArchiveAutoSubmitInfoByTask(id(), 0))),
+            get(archive_auto_submit_info_by_task),
+        )
+        .route(
+            &format!("{}/:id/:chain_id", route(&TaskEndpoint::ArchiveAutoSubmitInfo(id(), 0))),
+            get(archive_auto_submit_info),
+        )
+        .route(&route(&TaskEndpoint::ArchiveConfig), get(archive_config))
+        .route(
+            &format!("{}/:name", route(&TaskEndpoint::ArchiveTaskVolume(id()))),
+            get(archive_task_volume),
+        )
+        .route(
+            &format!("{}/:name", route(&TaskEndpoint::ArchiveAutoSubmitVolume(id()))),
+            get(archive_auto_submit_volume),
+        )
+        .route(&route(&TaskEndpoint::ArchiveArchiveQuery), get(archive_query))
+        .route(&route(&TaskEndpoint::Pay), post(pay))
+        .route(&route(&TaskEndpoint::Subscribe), post(subscribe))
+        .route(&route(&TaskEndpoint::Setup), post(setup))
+        .route(&route(&TaskEndpoint::Prove), post(prove))
+        .route(&route(&TaskEndpoint::Deploy), post(deploy))
+        .route(&route(&TaskEndpoint::Reset), post(reset))
+        .route(&route(&TaskEndpoint::Modify), post(modify))
+        .route(&route(&TaskEndpoint::SetMaintenanceMode), post(set_maintenance_mode))
+        .route(
+            &route(&TaskEndpoint::ForceUnprovableToReprocess),
+            post(force_unprovable_to_reprocess),
+        )
+        .route(
+            &route(&TaskEndpoint::ForceDryrunFailsToReprocess),
+            post(force_dryrun_fails_to_reprocess),
+        )
+        .fallback(|| async { MockError::not_found("No such endpoint") })
+        .with_state(state)
+}
+
+fn path<T>(path: std::result::Result<Path<T>, PathRejection>) -> Result<T, MockError> {
+    path.map(|Path(p)| p).map_err(|e| MockError::bad_request(e.body_text()))
+}
+
+fn parse_fee(fee: &str) -> u128 {
+    fee.parse().unwrap_or_default()
+}
+
+fn parse_timestamp(name: &str, value: &str) -> Result<DateTime<Utc>, MockError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.to_utc())
+        .map_err(|_| MockError::bad_request(format!("Invalid timestamp `{name}`")))
+}
+
+fn matches<T: PartialEq>(filter: Option<&T>, value: &T) -> bool {
+    filter.is_none_or(|f| f == value)
+}
+
+fn matches_address(filter: Option<&String>, value: &str) -> bool {
+    filter.is_none_or(|f| f.eq_ignore_ascii_case(value))
+}
+
+async fn image(State(state): State<SharedState>, RawQuery(q): RawQuery) -> Reply<Vec<Option<Image>>> {
+    let params: QueryImageParams = query(q.as_deref())?;
+    let state = state.lock();
+    ok(state.image(&params.md5).map(|i| Some(i.image.clone())).into_iter().collect())
+}
+
+async fn image_binary(State(state): State<SharedState>, RawQuery(q): RawQuery) -> Reply<Vec<u8>> {
+    let params: QueryImageParams = query(q.as_deref())?;
+    let state = state.lock();
+    let image = state
+        .image(&params.md5)
+        .ok_or_else(|| MockError::not_found("Image not found"))?;
+    ok(image.binary.clone())
+}
+
+async fn user(State(state): State<SharedState>, RawQuery(q): RawQuery) -> Reply<Option<User>> {
+    let params: UserQueryParams = query(q.as_deref())?;
+    ok(state.lock().user(&params.user_address).cloned())
+}
+
+async fn user_subscription(State(state): State<SharedState>, RawQuery(q): RawQuery) -> Reply<Option<Subscription>> {
+    let params: UserQueryParams = query(q.as_deref())?;
+    let state = state.lock();
+    ok(state
+        .subscriptions
+        .iter()
+        .find(|s| s.subscriber_address.eq_ignore_ascii_case(&params.user_address))
+        .cloned())
+}
+
+async fn transactions(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<TransactionInfo>>> {
+    let params: TxHistoryQueryParams = query(q.as_deref())?;
+    let state = state.lock();
+    let txs = state
+        .transactions
+        .iter()
+        .filter(|t| t.user_address.eq_ignore_ascii_case(&params.user_address))
+        .cloned();
+    ok(paginate(txs, params.start, params.total))
+}
+
+async fn deposits(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<ERC20DepositInfo>>> {
+    let params: TxHistoryQueryParams = query(q.as_deref())?;
+    let state = state.lock();
+    let deposits = state
+        .deposits
+        .iter()
+        .filter(|d| d.user_address.eq_ignore_ascii_case(&params.user_address))
+        .cloned();
+    ok(paginate(deposits, params.start, params.total))
+}
+
+async fn config(State(state): State<SharedState>) -> Reply<AppConfig> {
+    ok(state.lock().app_config.clone())
+}
+
+async fn statistics(State(state): State<SharedState>) -> Reply<StatisticsInfo> {
+    let state = state.lock();
+    let count = |n: usize| u64::try_from(n).unwrap_or(u64::MAX);
+    ok(StatisticsInfo {
+        total_images: count(state.images.len()),
+        total_proofs: count(
+            state
+                .tasks
+                .iter()
+                .filter(|t| matches!((&t.task.task_type, &t.task.status), (TaskType::Prove, TaskStatus::Done)))
+                .count(),
+        ),
+        total_tasks: count(state.tasks.len()),
+        total_deployed: count(state.images.iter().map(|i| i.image.deployment.len()).sum()),
+    })
+}
+
+async fn node_statistics(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<ProverNode>>> {
+    let params: NodeStatisticsQueryParams = query(q.as_deref())?;
+    let state = state.lock();
+    let nodes = state
+        .nodes
+        .iter()
+        .filter(|n| matches_address(params.address.as_ref(), &n.address))
+        .cloned();
+    ok(paginate(nodes, params.start, params.total))
+}
+
+async fn prover_node_summary(State(state): State<SharedState>) -> Reply<ProverNodesSummary> {
+    let state = state.lock();
+    let count = |level: ProverLevel| {
+        u64::try_from(state.nodes.iter().filter(|n| n.prover_level == level).count()).unwrap_or(u64::MAX)
+    };
+    ok(ProverNodesSummary {
+        certified_prover_count: count(ProverLevel::Certified),
+        active_prover_count: count(ProverLevel::Active),
+        intern_prover_count: count(ProverLevel::Intern),
+        inactive_prover_count: count(ProverLevel::Inactive),
+    })
+}
+
+async fn online_nodes_summary(State(state): State<SharedState>) -> Reply<OnlineNodesSummary> {
+    let state = state.lock();
+    let online = |level: ProverLevel| {
+        state
+            .nodes
+            .iter()
+            .filter_map(|n| n.online_activity.clone())
+            .filter(|n| n.online && n.prover_level == level)
+            .collect()
+    };
+    ok(OnlineNodesSummary {
+        certified: online(ProverLevel::Certified),
+        active: online(ProverLevel::Active),
+        intern: online(ProverLevel::Intern),
+        inactive: online(ProverLevel::Inactive),
+    })
+}
+
+/// Tasks matching the query, newest first.
+fn find_tasks(state: &SharedState, q: Option<&str>) -> Result<PaginationResult<Vec<Task>>, MockError> {
+    let params: QueryParams = query(q)?;
+    let state = state.lock();
+    let tasks = state
+        .tasks
+        .iter()
+        .rev()
+        .map(|t| &t.task)
+        .filter(|t| {
+            matches_address(params.user_address.as_ref(), &t.user_address)
+                && params.md5.as_ref().is_none_or(|m| m.eq_ignore_ascii_case(&t.md5))
+                && matches(params.id.as_ref(), &t._id.oid)
+                && matches(params.tasktype.as_ref(), &t.task_type)
+                && matches(params.taskstatus.as_ref(), &t.status)
+        })
+        .cloned();
+    Ok(paginate(tasks, params.start, params.total))
+}
+
+async fn tasks(State(state): State<SharedState>, RawQuery(q): RawQuery) -> Reply<PaginationResult<Vec<Task>>> {
+    ok(find_tasks(&state, q.as_deref())?)
+}
+
+async fn concise_tasks(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<ConciseTask>>> {
+    let tasks = find_tasks(&state, q.as_deref())?;
+    ok(PaginationResult {
+        data: tasks.data.iter().map(concise).collect(),
+        total: tasks.total,
+    })
+}
+
+async fn task_external_host_table(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<TaskExternalHostTable> {
+    let params: TaskExternalHostTableParams = query(q.as_deref())?;
+    let state = state.lock();
+    let task = state.task(&params.id).ok_or_else(|| MockError::not_found("Task not found"))?;
+    ok(TaskExternalHostTable {
+        external_host_table: md5::compute(format!("{}-external-host-table", task._id.oid)).0.to_vec(),
+        compression: None,
+    })
+}
+
+async fn round1_batch(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<AutoSubmitProof>>> {
+    let params: AutoSubmitProofQuery = query(q.as_deref())?;
+    let page: PaginationParams = query(q.as_deref())?;
+    let state = state.lock();
+    let proofs = state
+        .auto_submit_proofs
+        .iter()
+        .filter(|p| {
+            params.id.as_ref().is_none_or(|id| p._id.as_ref().is_some_and(|o| &o.oid == id))
+                && matches(params.task_id.as_ref(), &p.task_id)
+                && matches(params.status.as_ref(), &p.status)
+                && matches(params.circuit_size.as_ref(), &p.base_proof_circuit_size)
+                && matches(params.chain_id.as_ref(), &p.auto_submit_network_chain_id)
+        })
+        .cloned();
+    ok(paginate(proofs, page.start, page.total))
+}
+
+async fn round2_batch(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<Round1Info>>> {
+    let params: Round1InfoQuery = query(q.as_deref())?;
+    let page: PaginationParams = query(q.as_deref())?;
+    let state = state.lock();
+    let batches = state
+        .round1
+        .iter()
+        .filter(|b| {
+            params.id.as_ref().is_none_or(|id| b._id.as_ref().is_some_and(|o| &o.oid == id))
+                && params.round_1_id.as_ref().is_none_or(|id| b.round_1_ids.contains(id))
+                && params.task_id.as_ref().is_none_or(|id| b.task_ids.contains(id))
+                && matches(params.status.as_ref(), &b.status)
+                && matches(params.circuit_size.as_ref(), &b.verifier_contracts.circuit_size)
+                && matches(params.chain_id.as_ref(), &b.auto_submit_network_chain_id)
+        })
+        .cloned();
+    ok(paginate(batches, page.start, page.total))
+}
+
+async fn final_batch(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<Round2Info>>> {
+    let params: Round2InfoQuery = query(q.as_deref())?;
+    let page: PaginationParams = query(q.as_deref())?;
+    let state = state.lock();
+    let batches = state
+        .round2
+        .iter()
+        .filter(|b| {
+            params.id.as_ref().is_none_or(|id| b._id.as_ref().is_some_and(|o| &o.oid == id))
+                && params.round_2_id.as_ref().is_none_or(|id| b.round_2_ids.contains(id))
+                && params.task_id.as_ref().is_none_or(|id| b.task_ids.contains(id))
+                && matches(params.status.as_ref(), &b.status)
+                && matches(params.chain_id.as_ref(), &b.auto_submit_network_chain_id)
+        })
+        .cloned();
+    ok(paginate(batches, page.start, page.total))
+}
+
+async fn logs(State(state): State<SharedState>, headers: HeaderMap, RawQuery(q): RawQuery) -> Reply<String> {
+    let params: LogQuery = query(q.as_deref())?;
+    verify_signature(&params, &params.user_address, &headers)?;
+    let state = state.lock();
+    let task = state.task(&params.id).ok_or_else(|| MockError::not_found("Task not found"))?;
+    if !task.user_address.eq_ignore_ascii_case(&params.user_address) {
+        return Err(MockError::forbidden("Only the task owner can view its logs"));
+    }
+    ok(task.debug_logs.clone().unwrap_or_default())
+}
+
+/// The credits charged for a prove task of `image`.
+fn prove_fee(config: &AppConfig, image: &MockImage, mode: &ProofSubmitMode) -> u128 {
+    let fees = &config.task_fee_list;
+    let auto_submit = match mode {
+        ProofSubmitMode::Manual => 0,
+        ProofSubmitMode::Auto => {
+            parse_fee(&fees.auto_submit_prove_fee_per_network)
+                * u128::try_from(image.image.auto_submit_network_ids.len()).unwrap_or(u128::MAX)
+        }
+    };
+    parse_fee(&fees.prove_fee).saturating_add(auto_submit)
+}
+
+async fn estimated_proof_fee(State(state): State<SharedState>, RawQuery(q): RawQuery) -> Reply<EstimatedProofFee> {
+    let params: EstimatedProofFeeParams = query(q.as_deref())?;
+    let state = state.lock();
+    let image = state
+        .image(&params.md5)
+        .ok_or_else(|| MockError::not_found("Image not found"))?;
+    let fee = ethers::types::U256::from(prove_fee(&state.app_config, image, &params.proof_submit_mode));
+    ok(EstimatedProofFee {
+        min: Some(fee),
+        max: Some(fee),
+        msg: String::new(),
+    })
+}
+
+async fn prover_node_timerange_stats(
+    State(state): State<SharedState>,
+    body: Bytes,
+) -> Reply<Vec<ProverNodeTimeRangeStats>> {
+    let params: ProverNodeTimeRangeStatsParams = json(&body)?;
+    let state = state.lock();
+    let mut out = vec![];
+    for range in params.ranges {
+        let (start, end) = (parse_timestamp("start", &range.start)?, parse_timestamp("end", &range.end)?);
+        let mut finished: Vec<(DateTime<Utc>, &Task)> = state
+            .tasks
+            .iter()
+            .map(|t| &t.task)
+            .filter(|t| t.node_address.as_ref().is_some_and(|n| n.eq_ignore_ascii_case(&range.address)))
+            .filter_map(|t| {
+                let at = DateTime::parse_from_rfc3339(t.process_finished.as_ref()?).ok()?.to_utc();
+                (start <= at && at <= end).then_some((at, t))
+            })
+            .collect();
+        finished.sort_by_key(|(at, _)| *at);
+        let count = |status: TaskStatus| {
+            u64::try_from(finished.iter().filter(|(_, t)| t.status == status).count()).unwrap_or(u64::MAX)
+        };
+        out.push(ProverNodeTimeRangeStats {
+            fst_id: finished.first().map(|(_, t)| t._id.oid.clone()),
+            fst_ts: finished.first().map(|(at, _)| timestamp(*at)),
+            lst_id: finished.last().map(|(_, t)| t._id.oid.clone()),
+            lst_ts: finished.last().map(|(at, _)| timestamp(*at)),
+            stats: RangeStats {
+                successful: count(TaskStatus::Done),
+                failed: count(TaskStatus::Fail),
+                timed_out: 0,
+            },
+        });
+    }
+    ok(out)
+}
+
+async fn archive_summary(State(state): State<SharedState>) -> Reply<ArchiveMetadataOverview> {
+    let state = state.lock();
+    ok(ArchiveMetadataOverview {
+        first: state.archive.task_volumes.first().cloned(),
+        last: state.archive.task_volumes.last().cloned(),
+    })
+}
+
+async fn archive_task_volume_list(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<ArchiveVolumeMetadata>>> {
+    let params: VolumeListQuery = query(q.as_deref())?;
+    let state = state.lock();
+    ok(paginate(state.archive.task_volumes.iter().cloned(), params.start, params.limit))
+}
+
+async fn archive_auto_submit_volume_list(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<ArchiveVolumeMetadata>>> {
+    let params: VolumeListQuery = query(q.as_deref())?;
+    let state = state.lock();
+    ok(paginate(
+        state.archive.auto_submit_volumes.iter().cloned(),
+        params.start,
+        params.limit,
+    ))
+}
+
+async fn archive_task(
+    State(state): State<SharedState>,
+    id: std::result::Result<Path<String>, PathRejection>,
+) -> Reply<Task> {
+    let id = path(id)?;
+    let state = state.lock();
+    let task = state
+        .archive
+        .tasks
+        .iter()
+        .map(|(_, t)| t)
+        .find(|t| t._id.oid == id)
+        .ok_or_else(|| MockError::not_found("Archived task not found"))?;
+    ok(task.clone())
+}
+
+async fn archive_auto_submit_networks(
+    State(state): State<SharedState>,
+    id: std::result::Result<Path<String>, PathRejection>,
+) -> Reply<Vec<ArchivedFinalProofNetworkInfo>> {
+    let id = path(id)?;
+    let state = state.lock();
+    ok(state
+        .archive
+        .final_proofs
+        .iter()
+        .map(|(_, p)| p)
+        .filter(|p| p.task_ids.contains(&id))
+        .map(|p| ArchivedFinalProofNetworkInfo {
+            verifier_contracts: p.verifier_contracts.clone(),
+        })
+        .collect())
+}
+
+fn find_final_proof(
+    state: &SharedState,
+    pred: impl Fn(&ArchivedFinalBatchProof) -> bool,
+) -> Reply<ArchivedFinalBatchProof> {
+    let state = state.lock();
+    let proof = state
+        .archive
+        .final_proofs
+        .iter()
+        .map(|(_, p)| p)
+        .find(|p| pred(p))
+        .ok_or_else(|| MockError::not_found("Archived auto submit info not found"))?;
+    ok(proof.clone())
+}
+
+async fn archive_auto_submit_info_by_task(
+    State(state): State<SharedState>,
+    params: std::result::Result<Path<(String, u32)>, PathRejection>,
+) -> Reply<ArchivedFinalBatchProof> {
+    let (task_id, chain_id) = path(params)?;
+    find_final_proof(&state, |p| {
+        p.task_ids.contains(&task_id) && p.auto_submit_network_chain_id == chain_id
+    })
+}
+
+async fn archive_auto_submit_info(
+    State(state): State<SharedState>,
+    params: std::result::Result<Path<(String, u32)>, PathRejection>,
+) -> Reply<ArchivedFinalBatchProof> {
+    let (id, chain_id) = path(params)?;
+    find_final_proof(&state, |p| p._id.oid == id && p.auto_submit_network_chain_id == chain_id)
+}
+
+async fn archive_config(State(state): State<SharedState>) -> Reply<ArchiveServerConfig> {
+    ok(state.lock().archive.server_config.clone())
+}
+
+async fn archive_task_volume(
+    State(state): State<SharedState>,
+    name: std::result::Result<Path<String>, PathRejection>,
+    RawQuery(q): RawQuery,
+) -> Reply<VolumeDetailResponse> {
+    let name = path(name)?;
+    let params: VolumeDetailQuery = query(q.as_deref())?;
+    let state = state.lock();
+    let volume = state
+        .archive
+        .task_volumes
+        .iter()
+        .find(|v| v.volume_name == name)
+        .ok_or_else(|| MockError::not_found("Volume not found"))?;
+    let tasks: Vec<ConciseTask> = state
+        .archive
+        .tasks
+        .iter()
+        .filter(|(v, _)| *v == name)
+        .map(|(_, t)| concise(t))
+        .collect();
+    let total = u64::try_from(tasks.len()).unwrap_or(u64::MAX);
+    let task = tasks
+        .into_iter()
+        .nth(usize::try_from(params.tasks_start.unwrap_or_default()).unwrap_or(usize::MAX))
+        .ok_or_else(|| MockError::not_found("No tasks in volume"))?;
+    ok(VolumeDetailResponse {
+        volume: volume.clone(),
+        tasks: PaginationResult { data: task, total },
+    })
+}
+
+async fn archive_auto_submit_volume(
+    State(state): State<SharedState>,
+    name: std::result::Result<Path<String>, PathRejection>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<ArchivedFinalBatchProof>>> {
+    let name = path(name)?;
+    let params: VolumeDetailQuery = query(q.as_deref())?;
+    let state = state.lock();
+    if !state.archive.auto_submit_volumes.iter().any(|v| v.volume_name == name) {
+        return Err(MockError::not_found("Volume not found"));
+    }
+    let proofs = state
+        .archive
+        .final_proofs
+        .iter()
+        .filter(|(v, _)| *v == name)
+        .map(|(_, p)| p.clone());
+    ok(paginate(proofs, params.tasks_start, params.tasks_limit))
+}
+
+async fn archive_query(
+    State(state): State<SharedState>,
+    RawQuery(q): RawQuery,
+) -> Reply<PaginationResult<Vec<ConciseTask>>> {
+    let params: ArchiveQuery = query(q.as_deref())?;
+    let start = params
+        .start_timestamp
+        .as_ref()
+        .map(|t| parse_timestamp("start_timestamp", t))
+        .transpose()?;
+    let end = params
+        .end_timestamp
+        .as_ref()
+        .map(|t| parse_timestamp("end_timestamp", t))
+        .transpose()?;
+    let state = state.lock();
+    let tasks = state
+        .archive
+        .tasks
+        .iter()
+        .map(|(_, t)| t)
+        .filter(|t| {
+            let submitted = DateTime::parse_from_rfc3339(&t.submit_time).ok().map(|t| t.to_utc());
+            matches(params.task_id.as_ref(), &t._id.oid)
+                && params.md5.as_ref().is_none_or(|m| m.eq_ignore_ascii_case(&t.md5))
+                && start.is_none_or(|s| submitted.is_some_and(|t| s <= t))
+                && end.is_none_or(|e| submitted.is_some_and(|t| t <= e))
+        })
+        .map(concise);
+    ok(paginate(tasks, params.start, params.limit))
+}
+
+/// Claims an unclaimed on chain deposit and credits it to its sender.
+fn claim_deposit(state: &SharedState, txhash: &str) -> Result<ERC20DepositInfo, MockError> {
+    let mut state = state.lock();
+    if state.deposits.iter().any(|d| d.txhash.eq_ignore_ascii_case(txhash)) {
+        return Err(MockError::bad_request("Transaction has already been claimed"));
+    }
+    let idx = state
+        .unclaimed_deposits
+        .iter()
+        .position(|d| d.txhash.eq_ignore_ascii_case(txhash))
+        .ok_or_else(|| MockError::not_found("Transaction not found"))?;
+    let deposit = state.unclaimed_deposits.remove(idx);
+    state.credit(&deposit.user_address, parse_fee(&deposit.amount));
+    state.deposits.push(deposit.clone());
+    Ok(deposit)
+}
+
+async fn pay(State(state): State<SharedState>, multipart: MultipartBody) -> Reply<ERC20DepositInfo> {
+    let form = Form::read(multipart).await?;
+    let params = PaymentParams {
+        txhash: form.text("txhash")?,
+    };
+    ok(claim_deposit(&state, &params.txhash)?)
+}
+
+async fn subscribe(State(state): State<SharedState>, multipart: MultipartBody) -> Reply<ERC20DepositInfo> {
+    let form = Form::read(multipart).await?;
+    let params = SubscriptionRequest {
+        subscriber_address: form.text("subscriber_address")?,
+        subscription_type: form.variant("subscription_type")?,
+        duration: serde_json::from_str(&form.text("duration")?)
+            .map_err(|_| MockError::bad_request("Invalid field `duration`"))?,
+        payment_hash: form.text("payment_hash")?,
+    };
+    let deposit = claim_deposit(&state, &params.payment_hash)?;
+
+    let now = Utc::now();
+    let months = match params.duration.base_duration {
+        crate::interface::BaseSubscriptionDuration::Month => 1,
+        crate::interface::BaseSubscriptionDuration::Year => 12,
+    } * u32::try_from(params.duration.multiplier).unwrap_or(u32::MAX);
+    let end = now
+        .checked_add_months(chrono::Months::new(months))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+    let mut state = state.lock();
+    let subscription = Subscription {
+        subscriber_address: params.subscriber_address.to_lowercase(),
+        start_date: u64::try_from(now.timestamp()).unwrap_or_default(),
+        end_date: u64::try_from(end.timestamp()).unwrap_or_default(),
+        params: SubscriptionParams {
+            subscription_type: params.subscription_type,
+            duration: params.duration,
+            token_params: deposit.token_params.clone(),
+            token_data: deposit.token_data.clone(),
+            price_per_base_duration: deposit.amount.clone(),
+            credited_amount: deposit.amount.clone(),
+            enabled: true,
+        },
+        status: SubscriptionStatus::Active,
+        payment_details: vec![deposit.clone()],
+    };
+    state
+        .subscriptions
+        .retain(|s| !s.subscriber_address.eq_ignore_ascii_case(&subscription.subscriber_address));
+    state.subscriptions.push(subscription);
+    ok(deposit)
+}
+
+fn check_networks(config: &AppConfig, ids: &[u32]) -> Result<(), MockError> {
+    match ids.iter().find(|id| !config.supported_auto_submit_network_ids.contains(id)) {
+        Some(id) => Err(MockError::bad_request(format!("Unsupported auto submit network {id}"))),
+        None => Ok(()),
+    }
+}
+
+async fn setup(State(state): State<SharedState>, headers: HeaderMap, multipart: MultipartBody) -> Reply<AddTaskResult> {
+    let form = Form::read(multipart).await?;
+    let context = match form.opt_text("initial_context_md5")? {
+        Some(initial_context_md5) => InitialContext::With(WithInitialContext {
+            initial_context_md5,
+            initial_context: form.list("initial_context")?,
+        }),
+        None => InitialContext::Without,
+    };
+    let params = AddImageParams {
+        base: BaseAddImageParams {
+            name: form.text("name")?,
+            image_md5: form.text("image_md5")?,
+            image: form.bytes("image")?,
+            user_address: form.text("user_address")?,
+            description_url: form.text("description_url")?,
+            avator_url: form.text("avator_url")?,
+            circuit_size: form.parse("circuit_size")?,
+            prove_payment_src: form.variant("prove_payment_src")?,
+            auto_submit_network_ids: form.list("auto_submit_network_ids")?,
+            add_prove_task_restrictions: form.opt_variant("add_prove_task_restrictions")?,
+            inherited_merkle_data_md5: form.opt_text("inherited_merkle_data_md5")?,
+        },
+        context,
+    };
+    verify_signature(&params, &params.base.user_address, &headers)?;
+
+    let base = params.base;
+    let md5 = format!("{:X}", md5::compute(&base.image));
+    if !md5.eq_ignore_ascii_case(&base.image_md5) {
+        return Err(MockError::bad_request("Image md5 does not match the image"));
+    }
+    let mut state = state.lock();
+    state.check_not_in_maintenance()?;
+    if state.image(&md5).is_some() {
+        return Err(MockError::bad_request("Image already exists"));
+    }
+    check_networks(&state.app_config, &base.auto_submit_network_ids)?;
+    let fee = parse_fee(&state.app_config.task_fee_list.setup_fee);
+    state.charge(&base.user_address, fee)?;
+
+    let initial_context = match params.context {
+        InitialContext::With(c) => Some(c.initial_context),
+        InitialContext::Without => None,
+    };
+    let now = Utc::now();
+    let id = state.next_id();
+    let mut task = new_task(
+        id.clone(),
+        base.user_address.to_lowercase(),
+        base.image_md5.clone(),
+        TaskType::Setup,
+        now,
+    );
+    task.input_context = initial_context.clone().unwrap_or_default();
+    state.queue_task(task, now);
+    state.images.push(MockImage {
+        image: Image {
+            user_address: base.user_address.to_lowercase(),
+            md5: base.image_md5.clone(),
+            deployment: vec![],
+            description_url: base.description_url,
+            avator_url: base.avator_url,
+            circuit_size: base.circuit_size,
+            context: initial_context.clone(),
+            initial_context,
+            status: "Received".to_string(),
+            checksum: None,
+            prove_payment_src: base.prove_payment_src,
+            auto_submit_network_ids: base.auto_submit_network_ids,
+            inherited_merkle_data_info: base
+                .inherited_merkle_data_md5
+                .map(|md5| crate::interface::InheritedMerkleDataInfo { md5 }),
+            add_prove_task_restrictions: base.add_prove_task_restrictions.unwrap_or(AddProveTaskRestrictions::Anyone),
+        },
+        binary: base.image,
+    });
+    ok(AddTaskResult {
+        md5: base.image_md5,
+        id,
+    })
+}
+
+async fn prove(State(state): State<SharedState>, headers: HeaderMap, multipart: MultipartBody) -> Reply<AddTaskResult> {
+    let form = Form::read(multipart).await?;
+    let context = if let Some(input_context_md5) = form.opt_text("input_context_md5")? {
+        CustomContext::With(WithCustomInputContext {
+            input_context_type: form.variant("input_context_type")?,
+            input_context: form.list("input_context")?,
+            input_context_md5,
+        })
+    } else if let Some(input_context_type) = form.opt_text("input_context_type")? {
+        CustomContext::WithNonCustom(WithNonCustomInputContext {
+            input_context_type: Some(serde_json::Value::String(input_context_type)),
+        })
+    } else {
+        CustomContext::Without
+    };
+    let params = ProvingParams {
+        base: BaseProvingParams {
+            user_address: form.text("user_address")?,
+            md5: form.text("md5")?,
+            public_inputs: form.list("public_inputs")?,
+            private_inputs: form.list("private_inputs")?,
+            proof_submit_mode: form.variant("proof_submit_mode")?,
+        },
+        context,
+    };
+    verify_signature(&params, &params.base.user_address, &headers)?;
+
+    let base = params.base;
+    let mut state = state.lock();
+    state.check_not_in_maintenance()?;
+    let image = state.image(&base.md5).ok_or_else(|| MockError::not_found("Image not found"))?;
+    if let AddProveTaskRestrictions::CreatorOnly = image.image.add_prove_task_restrictions {
+        if !image.image.user_address.eq_ignore_ascii_case(&base.user_address) {
+            return Err(MockError::forbidden("Only the image creator can add prove tasks"));
+        }
+    }
+    let fee = prove_fee(&state.app_config, image, &base.proof_submit_mode);
+    state.charge(&base.user_address, fee)?;
+
+    let now = Utc::now();
+    let id = state.next_id();
+    let mut task = new_task(
+        id.clone(),
+        base.user_address.to_lowercase(),
+        base.md5.clone(),
+        TaskType::Prove,
+        now,
+    );
+    task.public_inputs = base.public_inputs;
+    task.private_inputs = base.private_inputs;
+    task.proof_submit_mode = Some(base.proof_submit_mode);
+    if let CustomContext::With(c) = params.context {
+        task.input_context = c.input_context;
+        task.input_context_type = Some(c.input_context_type);
+    }
+    state.queue_task(task, now);
+    ok(AddTaskResult { md5: base.md5, id })
+}
+
+async fn deploy(State(state): State<SharedState>, headers: HeaderMap, multipart: MultipartBody) -> Reply<()> {
+    let form = Form::read(multipart).await?;
+    let params = DeployParams {
+        user_address: form.text("user_address")?,
+        md5: form.text("md5")?,
+        chain_id: form.parse("chain_id")?,
+    };
+    verify_signature(&params, &params.user_address, &headers)?;
+
+    let mut state = state.lock();
+    if !state.app_config.chain_info_list.iter().any(|c| c.chain_id == params.chain_id) {
+        return Err(MockError::bad_request(format!("Unsupported chain {}", params.chain_id)));
+    }
+    let image = state.image_mut(&params.md5)?;
+    if !image.image.deployment.iter().any(|d| d.chain_id == params.chain_id) {
+        image.image.deployment.push(DeploymentInfo {
+            chain_id: params.chain_id,
+            address: format!("0x{:0>40}", &image.image.md5.to_lowercase()[..20]),
+        });
+    }
+    ok(())
+}
+
+async fn reset(State(state): State<SharedState>, headers: HeaderMap, multipart: MultipartBody) -> Reply<AddTaskResult> {
+    let form = Form::read(multipart).await?;
+    let context = match form.opt_text("reset_context_md5")? {
+        Some(reset_context_md5) => ResetContext::With(WithResetContext {
+            reset_context: form.list("reset_context")?,
+            reset_context_md5,
+        }),
+        None => ResetContext::Without,
+    };
+    let params = ResetImageParams {
+        base: BaseResetImageParams {
+            md5: form.text("md5")?,
+            circuit_size: form.parse("circuit_size")?,
+            user_address: form.text("user_address")?,
+            prove_payment_src: form.variant("prove_payment_src")?,
+            auto_submit_network_ids: form.list("auto_submit_network_ids")?,
+            add_prove_task_restrictions: form.opt_variant("add_prove_task_restrictions")?,
+        },
+        context,
+    };
+    verify_signature(&params, &params.base.user_address, &headers)?;
+
+    let base = params.base;
+    let mut state = state.lock();
+    state.check_not_in_maintenance()?;
+    check_networks(&state.app_config, &base.auto_submit_network_ids)?;
+    let owner = state
+        .image(&base.md5)
+        .map(|i| i.image.user_address.clone())
+        .ok_or_else(|| MockError::not_found("Image not found"))?;
+    if !owner.eq_ignore_ascii_case(&base.user_address) {
+        return Err(MockError::forbidden("Only the image creator can reset the image"));
+    }
+    let fee = parse_fee(&state.app_config.task_fee_list.setup_fee);
+    state.charge(&base.user_address, fee)?;
+
+    let reset_context = match params.context {
+        ResetContext::With(c) => Some(c.reset_context),
+        ResetContext::Without => None,
+    };
+    let image = &mut state.image_mut(&base.md5)?.image;
+    image.circuit_size = base.circuit_size;
+    image.prove_payment_src = base.prove_payment_src;
+    image.auto_submit_network_ids = base.auto_submit_network_ids;
+    if let Some(restrictions) = base.add_prove_task_restrictions {
+        image.add_prove_task_restrictions = restrictions;
+    }
+    if let Some(context) = &reset_context {
+        image.context = Some(context.clone());
+    }
+    image.status = "Received".to_string();
+
+    let now = Utc::now();
+    let id = state.next_id();
+    let mut task = new_task(
+        id.clone(),
+        base.user_address.to_lowercase(),
+        base.md5.clone(),
+        TaskType::Reset,
+        now,
+    );
+    task.input_context = reset_context.unwrap_or_default();
+    state.queue_task(task, now);
+    ok(AddTaskResult { md5: base.md5, id })
+}
+
+async fn modify(State(state): State<SharedState>, headers: HeaderMap, multipart: MultipartBody) -> Reply<String> {
+    let form = Form::read(multipart).await?;
+    let params = ModifyImageParams {
+        md5: form.text("md5")?,
+        user_address: form.text("user_address")?,
+        description_url: form.text("description_url")?,
+        avator_url: form.text("avator_url")?,
+    };
+    verify_signature(&params, &params.user_address, &headers)?;
+
+    let mut state = state.lock();
+    let image = &mut state.image_mut(&params.md5)?.image;
+    if !image.user_address.eq_ignore_ascii_case(&params.user_address) {
+        return Err(MockError::forbidden("Only the image creator can modify the image"));
+    }
+    image.description_url = params.description_url;
+    image.avator_url = params.avator_url;
+    ok("Image modified".to_string())
+}
+
+fn check_admin(
+    state: &super::state::MockState,
+    user_address: &str,
+    request_type: &AdminRequestType,
+    expected: &AdminRequestType,
+) -> Result<(), MockError> {
+    if request_type != expected {
+        return Err(MockError::bad_request("Invalid request type"));
+    }
+    if state.is_admin(user_address) {
+        Ok(())
+    } else {
+        Err(MockError::forbidden("Admin privileges required"))
+    }
+}
+
+async fn set_maintenance_mode(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    multipart: MultipartBody,
+) -> Reply<String> {
+    let form = Form::read(multipart).await?;
+    let params = SetMaintenanceModeParams {
+        mode: form.variant("mode")?,
+        nonce: form.parse("nonce")?,
+        request_type: form.variant("request_type")?,
+        user_address: form.text("user_address")?,
+    };
+    verify_signature(&params, &params.user_address, &headers)?;
+
+    let mut state = state.lock();
+    check_admin(
+        &state,
+        &params.user_address,
+        &params.request_type,
+        &AdminRequestType::MaintenanceMode,
+    )?;
+    state.maintenance_mode = matches!(params.mode, MaintenanceModeType::Enabled);
+    ok("Maintenance mode updated".to_string())
+}
+
+/// Requeues the tasks in `task_ids` which are in `status`, returning the ids of the requeued tasks.
+fn reprocess(state: &SharedState, task_ids: &[String], status: &TaskStatus) -> Vec<ObjectId> {
+    let now = Utc::now();
+    let mut state = state.lock();
+    state
+        .tasks
+        .iter_mut()
+        .filter(|t| task_ids.contains(&t.task._id.oid) && t.task.status == *status)
+        .map(|t| {
+            t.requeue(now);
+            t.task._id.clone()
+        })
+        .collect()
+}
+
+async fn force_unprovable_to_reprocess(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    multipart: MultipartBody,
+) -> Reply<Vec<ObjectId>> {
+    let form = Form::read(multipart).await?;
+    let params = ForceUnprovableToReprocessParams {
+        task_ids: form.list("task_ids")?,
+        nonce: form.parse("nonce")?,
+        request_type: form.variant("request_type")?,
+        user_address: form.text("user_address")?,
+    };
+    verify_signature(&params, &params.user_address, &headers)?;
+    check_admin(
+        &state.lock(),
+        &params.user_address,
+        &params.request_type,
+        &AdminRequestType::ForceTaskToReprocess,
+    )?;
+    ok(reprocess(&state, &params.task_ids, &TaskStatus::Unprovable))
+}
+
+async fn force_dryrun_fails_to_reprocess(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    multipart: MultipartBody,
+) -> Reply<Vec<ObjectId>> {
+    let form = Form::read(multipart).await?;
+    let params = ForceDryrunFailsToReprocessParams {
+        task_ids: form.list("task_ids")?,
+        nonce: form.parse("nonce")?,
+        request_type: form.variant("request_type")?,
+        user_address: form.text("user_address")?,
+    };
+    verify_signature(&params, &params.user_address, &headers)?;
+    check_admin(
+        &state.lock(),
+        &params.user_address,
+        &params.request_type,
+        &AdminRequestType::ForceTaskToReprocess,
+    )?;
+    ok(reprocess(&state, &params.task_ids, &TaskStatus::DryRunFailed))
+}
@@ -0,0 +1,122 @@
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use super::fixtures::seed;
+use super::fixtures::MockFixtures;
+use super::routes::router;
+use super::state::SharedState;
+use crate::helper::ZkWasmServiceHelper;
+
+/// Configuration of a [`MockServer`].
+#[derive(Clone, Copy, Debug)]
+pub struct MockConfig {
+    task_duration: Duration,
+}
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        Self {
+            task_duration: Duration::from_secs(2),
+        }
+    }
+}
+
+impl MockConfig {
+    /// Sets how long a submitted task takes to be proven. The task is `Processing` after half of this duration and
+    /// `Done` once it has passed.
+    #[must_use]
+    pub fn task_duration(mut self, task_duration: Duration) -> Self {
+        self.task_duration = task_duration;
+        self
+    }
+}
+
+/// An in-memory implementation of the `ZkWasm` service listening on a local port.
+///
+/// The server is seeded with the records described by [`MockFixtures`], checks request signatures, charges credits and
+/// moves submitted tasks from `Pending` to `Done`. It shuts down when dropped.
+pub struct MockServer {
+    url: String,
+    fixtures: MockFixtures,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// Starts the server on the current tokio runtime.
+    pub async fn start(config: MockConfig) -> io::Result<Self> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let (server, serve) = Self::serve(listener, &config)?;
+        tokio::spawn(serve);
+        Ok(server)
+    }
+
+    /// Starts the server on its own thread and runtime, so that it outlives the runtime of the caller, e.g. when shared
+    /// between `#[tokio::test]`s.
+    pub fn start_in_background(config: MockConfig) -> io::Result<Self> {
+        let listener = std::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))?;
+        listener.set_nonblocking(true)?;
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let (server, serve) = {
+            let _guard = runtime.enter();
+            Self::serve(TcpListener::from_std(listener)?, &config)?
+        };
+        std::thread::Builder::new()
+            .name("zkwasm-mock-server".to_string())
+            .spawn(move || runtime.block_on(serve))?;
+        Ok(server)
+    }
+
+    fn serve(
+        listener: TcpListener,
+        config: &MockConfig,
+    ) -> io::Result<(Self, impl std::future::Future<Output = ()> + Send + 'static)> {
+        let url = format!("http://{}", listener.local_addr()?);
+        let (state, fixtures) = seed(config.task_duration);
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        let app = router(SharedState::new(state));
+        let serve = async move {
+            let shutdown = async move {
+                let _ = stopped.await;
+            };
+            if let Err(e) = axum::serve(listener, app).with_graceful_shutdown(shutdown).await {
+                tracing::error!(error = %e, "mock server failed");
+            }
+        };
+        let server = Self {
+            url,
+            fixtures,
+            shutdown: Some(shutdown),
+        };
+        Ok((server, serve))
+    }
+
+    /// The base URL of the server, e.g. `http://127.0.0.1:38211`.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The identifiers of the records the server was seeded with.
+    #[must_use]
+    pub fn fixtures(&self) -> &MockFixtures {
+        &self.fixtures
+    }
+
+    /// A helper sending its requests to this server.
+    #[must_use]
+    pub fn helper(&self) -> ZkWasmServiceHelper {
+        ZkWasmServiceHelper::new(self.url.clone())
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
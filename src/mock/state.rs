@@ -0,0 +1,356 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use serde::Serialize;
+
+use super::request::MockError;
+use crate::interface::AppConfig;
+use crate::interface::ArchiveServerConfig;
+use crate::interface::ArchiveVolumeMetadata;
+use crate::interface::ArchivedFinalBatchProof;
+use crate::interface::AutoSubmitProof;
+use crate::interface::ConciseTask;
+use crate::interface::ERC20DepositInfo;
+use crate::interface::Image;
+use crate::interface::ObjectId;
+use crate::interface::PaginationResult;
+use crate::interface::ProverNode;
+use crate::interface::Round1Info;
+use crate::interface::Round2Info;
+use crate::interface::Subscription;
+use crate::interface::Task;
+use crate::interface::TaskStatus;
+use crate::interface::TaskType;
+use crate::interface::TaskVerificationData;
+use crate::interface::TransactionInfo;
+use crate::interface::User;
+use crate::interface::VerifierContracts;
+
+/// Formats a timestamp the way the service does, e.g. `2024-07-11T04:03:56.123Z`.
+pub(super) fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub(super) fn verifier_contracts(chain_id: u32, circuit_size: u32) -> VerifierContracts {
+    VerifierContracts {
+        chain_id,
+        aggregator_verifier: format!("0x{:040x}", 0xa99u64 + u64::from(chain_id)),
+        batch_verifier: Some(format!("0x{:040x}", 0xba7u64 + u64::from(chain_id))),
+        circuit_size,
+    }
+}
+
+/// A task which has just been submitted.
+pub(super) fn new_task(
+    id: String,
+    user_address: String,
+    md5: String,
+    task_type: TaskType,
+    submitted: DateTime<Utc>,
+) -> Task {
+    Task {
+        user_address,
+        node_address: None,
+        _id: ObjectId { oid: id },
+        status: TaskStatus::Pending,
+        md5,
+        task_type,
+        public_inputs: vec![],
+        private_inputs: vec![],
+        single_proof: vec![],
+        proof: vec![],
+        batch_instances: vec![],
+        shadow_instances: vec![],
+        instances: vec![],
+        aux: vec![],
+        input_context: vec![],
+        input_context_type: None,
+        output_context: vec![],
+        submit_time: timestamp(submitted),
+        process_started: None,
+        process_finished: None,
+        task_fee: None,
+        status_message: None,
+        internal_message: None,
+        guest_statics: None,
+        task_verification_data: TaskVerificationData {
+            static_file_checksum: vec![],
+            verifier_contracts: vec![],
+        },
+        debug_logs: None,
+        proof_submit_mode: None,
+        batch_proof_data: None,
+        auto_submit_status: None,
+        compression: None,
+    }
+}
+
+/// Marks a task as proven by `node_address`.
+pub(super) fn complete_task(task: &mut Task, node_address: Option<String>, finished: DateTime<Utc>) {
+    let digest = md5::compute(&task._id.oid).0.to_vec();
+    task.status = TaskStatus::Done;
+    task.node_address = node_address;
+    task.process_started.get_or_insert_with(|| timestamp(finished));
+    task.process_finished = Some(timestamp(finished));
+    if let TaskType::Prove = task.task_type {
+        task.single_proof.clone_from(&digest);
+        task.proof.clone_from(&digest);
+        task.instances.clone_from(&digest);
+        task.aux = digest;
+    }
+    task.guest_statics = Some(1024);
+    task.debug_logs = Some(format!("mock prover: task {} completed", task._id.oid));
+}
+
+pub(super) fn concise(task: &Task) -> ConciseTask {
+    ConciseTask {
+        _id: task._id.clone(),
+        user_address: task.user_address.clone(),
+        md5: task.md5.clone(),
+        task_type: task.task_type.clone(),
+        status: task.status.clone(),
+        submit_time: task.submit_time.clone(),
+        process_started: task.process_started.clone(),
+        process_finished: task.process_finished.clone(),
+        proof_submit_mode: task.proof_submit_mode.clone(),
+        auto_submit_status: task.auto_submit_status.clone(),
+    }
+}
+
+/// Returns the page of `items` starting at `start` with at most `total` entries, and the number of items overall.
+pub(super) fn paginate<T: Serialize>(
+    items: impl IntoIterator<Item = T>,
+    start: Option<u64>,
+    total: Option<u64>,
+) -> PaginationResult<Vec<T>> {
+    let items: Vec<T> = items.into_iter().collect();
+    let count = u64::try_from(items.len()).unwrap_or(u64::MAX);
+    let start = start.map_or(0, |s| usize::try_from(s).unwrap_or(usize::MAX));
+    let total = total.map_or(usize::MAX, |t| usize::try_from(t).unwrap_or(usize::MAX));
+    PaginationResult {
+        data: items.into_iter().skip(start).take(total).collect(),
+        total: count,
+    }
+}
+
+pub(super) struct MockImage {
+    pub(super) image: Image,
+    pub(super) binary: Vec<u8>,
+}
+
+/// A task and the time it was (re)queued, which drives its progress from `Pending` to `Done`.
+pub(super) struct MockTask {
+    pub(super) task: Task,
+    queued: DateTime<Utc>,
+}
+
+impl MockTask {
+    pub(super) fn requeue(&mut self, now: DateTime<Utc>) {
+        self.task.status = TaskStatus::Pending;
+        self.task.node_address = None;
+        self.task.process_started = None;
+        self.task.process_finished = None;
+        self.queued = now;
+    }
+}
+
+pub(super) struct MockArchive {
+    pub(super) server_config: ArchiveServerConfig,
+    pub(super) task_volumes: Vec<ArchiveVolumeMetadata>,
+    pub(super) auto_submit_volumes: Vec<ArchiveVolumeMetadata>,
+    /// Archived tasks with the name of the volume holding them.
+    pub(super) tasks: Vec<(String, Task)>,
+    /// Archived final batch proofs with the name of the volume holding them.
+    pub(super) final_proofs: Vec<(String, ArchivedFinalBatchProof)>,
+}
+
+/// The records served by the mock, equivalent to the service's database.
+pub(super) struct MockState {
+    pub(super) app_config: AppConfig,
+    pub(super) images: Vec<MockImage>,
+    pub(super) tasks: Vec<MockTask>,
+    pub(super) users: Vec<User>,
+    pub(super) subscriptions: Vec<Subscription>,
+    pub(super) transactions: Vec<TransactionInfo>,
+    pub(super) deposits: Vec<ERC20DepositInfo>,
+    /// Deposits made on chain which have not been claimed through `/pay` or `/subscribe` yet.
+    pub(super) unclaimed_deposits: Vec<ERC20DepositInfo>,
+    pub(super) nodes: Vec<ProverNode>,
+    pub(super) auto_submit_proofs: Vec<AutoSubmitProof>,
+    pub(super) round1: Vec<Round1Info>,
+    pub(super) round2: Vec<Round2Info>,
+    pub(super) archive: MockArchive,
+    pub(super) admins: Vec<String>,
+    pub(super) maintenance_mode: bool,
+    task_duration: Duration,
+    next_id: u64,
+}
+
+impl MockState {
+    pub(super) fn new(app_config: AppConfig, archive: MockArchive, task_duration: Duration) -> Self {
+        Self {
+            app_config,
+            images: vec![],
+            tasks: vec![],
+            users: vec![],
+            subscriptions: vec![],
+            transactions: vec![],
+            deposits: vec![],
+            unclaimed_deposits: vec![],
+            nodes: vec![],
+            auto_submit_proofs: vec![],
+            round1: vec![],
+            round2: vec![],
+            archive,
+            admins: vec![],
+            maintenance_mode: false,
+            task_duration,
+            next_id: 0,
+        }
+    }
+
+    /// Returns a new unique 24 digit hex id, in the format of a `MongoDB` object id.
+    pub(super) fn next_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("69{:022x}", self.next_id)
+    }
+
+    pub(super) fn queue_task(&mut self, task: Task, now: DateTime<Utc>) {
+        self.tasks.push(MockTask { task, queued: now });
+    }
+
+    /// Adds a task which has already been proven.
+    pub(super) fn insert_task(&mut self, task: Task) {
+        let queued = DateTime::parse_from_rfc3339(&task.submit_time).map_or_else(|_| Utc::now(), |t| t.to_utc());
+        self.tasks.push(MockTask { task, queued });
+    }
+
+    pub(super) fn task(&self, id: &str) -> Option<&Task> {
+        self.tasks.iter().map(|t| &t.task).find(|t| t._id.oid == id)
+    }
+
+    pub(super) fn image(&self, md5: &str) -> Option<&MockImage> {
+        self.images.iter().find(|i| i.image.md5.eq_ignore_ascii_case(md5))
+    }
+
+    pub(super) fn image_mut(&mut self, md5: &str) -> Result<&mut MockImage, MockError> {
+        self.images
+            .iter_mut()
+            .find(|i| i.image.md5.eq_ignore_ascii_case(md5))
+            .ok_or_else(|| MockError::not_found("Image not found"))
+    }
+
+    pub(super) fn user(&self, user_address: &str) -> Option<&User> {
+        self.users.iter().find(|u| u.user_address.eq_ignore_ascii_case(user_address))
+    }
+
+    pub(super) fn is_admin(&self, user_address: &str) -> bool {
+        self.admins.iter().any(|a| a.eq_ignore_ascii_case(user_address))
+    }
+
+    pub(super) fn check_not_in_maintenance(&self) -> Result<(), MockError> {
+        if self.maintenance_mode {
+            Err(MockError::new(
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                "Server is in maintenance mode",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Deducts `fee` from the user's credits.
+    pub(super) fn charge(&mut self, user_address: &str, fee: u128) -> Result<(), MockError> {
+        let user = self
+            .users
+            .iter_mut()
+            .find(|u| u.user_address.eq_ignore_ascii_case(user_address))
+            .ok_or_else(|| MockError::forbidden("Insufficient credits"))?;
+        let credits = user.credits.parse::<u128>().unwrap_or_default();
+        let remaining = credits
+            .checked_sub(fee)
+            .ok_or_else(|| MockError::forbidden("Insufficient credits"))?;
+        user.credits = remaining.to_string();
+        Ok(())
+    }
+
+    /// Adds `amount` to the user's credits, creating the user if necessary.
+    #[allow(deprecated)]
+    pub(super) fn credit(&mut self, user_address: &str, amount: u128) {
+        let idx = if let Some(idx) = self
+            .users
+            .iter()
+            .position(|u| u.user_address.eq_ignore_ascii_case(user_address))
+        {
+            idx
+        } else {
+            self.users.push(User {
+                user_address: user_address.to_lowercase(),
+                balance: vec![],
+                credits: "0".to_string(),
+                credit_deficit: "0".to_string(),
+            });
+            self.users.len() - 1
+        };
+        let user = &mut self.users[idx];
+        let credits = user.credits.parse::<u128>().unwrap_or_default();
+        user.credits = credits.saturating_add(amount).to_string();
+    }
+
+    /// Moves queued tasks along: a task is picked up by a prover after half of the task duration and is done once the
+    /// full duration has passed.
+    pub(super) fn advance(&mut self, now: DateTime<Utc>) {
+        let duration = chrono::Duration::from_std(self.task_duration).unwrap_or(chrono::Duration::MAX);
+        let node_address = self.nodes.first().map(|n| n.address.clone());
+        let mut completed = vec![];
+        for t in &mut self.tasks {
+            if !matches!(t.task.status, TaskStatus::Pending | TaskStatus::Processing) {
+                continue;
+            }
+            let started = t.queued + duration / 2;
+            if now >= started && matches!(t.task.status, TaskStatus::Pending) {
+                t.task.status = TaskStatus::Processing;
+                t.task.node_address.clone_from(&node_address);
+                t.task.process_started = Some(timestamp(started));
+            }
+            let finished = t.queued + duration;
+            if now >= finished {
+                complete_task(&mut t.task, node_address.clone(), finished);
+                completed.push((t.task.md5.clone(), t.task.task_type.clone()));
+            }
+        }
+
+        for (md5, task_type) in completed {
+            if let (TaskType::Setup | TaskType::Reset, Ok(image)) = (task_type, self.image_mut(&md5)) {
+                image.image.status = "Verified".to_string();
+            }
+            if let Some(node) = self.nodes.first_mut() {
+                node.statistics.successful_tasks += 1;
+                node.statistics.total_tasks += 1;
+            }
+        }
+    }
+}
+
+/// [`MockState`] shared between the routes of the mock server.
+#[derive(Clone)]
+pub(super) struct SharedState(Arc<Mutex<MockState>>);
+
+impl SharedState {
+    pub(super) fn new(state: MockState) -> Self {
+        Self(Arc::new(Mutex::new(state)))
+    }
+
+    /// Locks the state, first moving queued tasks along to the current time.
+    pub(super) fn lock(&self) -> MutexGuard<'_, MockState> {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        state.advance(Utc::now());
+        state
+    }
+}
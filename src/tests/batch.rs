@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use ethers::types::U256;
+use reqwest::StatusCode;
+
+use super::util::added;
+use super::util::signer;
+use crate::helper::MockTransport;
+use crate::helper::ProveBatchError;
+use crate::helper::ProveBatchOptions;
+use crate::helper::ProveRequest;
+use crate::helper::TaskEndpoint;
+use crate::helper::ZkWasmServiceHelper;
+use crate::interface::EstimatedProofFee;
+
+const PRIVATE_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+const ADDRESS: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+const MD5: &str = "5240DD2F4E1A2B3C4D5E6F708192A3B4";
+
+fn request(input: u32) -> ProveRequest {
+    ProveRequest::builder()
+        .user_address(ADDRESS)
+        .md5(MD5)
+        .public_input(format!("{input:#x}:i64"))
+        .build()
+        .expect("Should build prove request")
+}
+
+#[tokio::test]
+async fn test_prove_batch_caps_spend() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(
+        &TaskEndpoint::EstimatedProofFee,
+        &EstimatedProofFee {
+            min: Some(U256::from(60)),
+            max: Some(U256::from(100)),
+            msg: String::new(),
+        },
+    );
+    mock.respond_ok_to(&TaskEndpoint::Prove, &added(MD5, "first"));
+    mock.respond_error_to(&TaskEndpoint::Prove, StatusCode::BAD_REQUEST, "Insufficient balance");
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let report = zkh
+        .submit_prove_batch(
+            (0..4).map(request),
+            signer(PRIVATE_KEY),
+            ProveBatchOptions::default().max_spend(U256::from(250)).max_concurrency(1),
+        )
+        .await
+        .expect("Should submit batch");
+
+    assert_eq!(report.items.len(), 4);
+    assert_eq!(report.estimated_spend, Some(U256::from(200)));
+    assert!(report.items[0].result.as_ref().is_ok_and(|added| added.id == "first"));
+    assert!(
+        matches!(&report.items[1].result, Err(ProveBatchError::Request(e)) if e.to_string().contains("Insufficient balance"))
+    );
+    for item in &report.items[2..] {
+        let Err(ProveBatchError::SpendCapExceeded { fee, remaining }) = item.result else {
+            unreachable!("Requests over the cap should not be submitted");
+        };
+        assert_eq!((fee, remaining), (U256::from(100), U256::from(50)));
+        assert!(item.outcome.is_none());
+    }
+    assert_eq!(report.added().count(), 1);
+
+    // The fee is estimated once for the image, and only the admitted requests are sent.
+    assert_eq!(mock.remaining(), 0);
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[0].endpoint == TaskEndpoint::EstimatedProofFee);
+    assert!(requests[1..]
+        .iter()
+        .all(|r| r.endpoint == TaskEndpoint::Prove && r.signature.is_some()));
+}
+
+#[tokio::test]
+async fn test_prove_batch_without_estimate() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(
+        &TaskEndpoint::EstimatedProofFee,
+        &EstimatedProofFee {
+            min: None,
+            max: None,
+            msg: "Image not found".to_string(),
+        },
+    );
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let err = zkh
+        .submit_prove_batch(
+            (0..2).map(request),
+            signer(PRIVATE_KEY),
+            ProveBatchOptions::default().max_spend(U256::from(250)),
+        )
+        .await
+        .err()
+        .expect("Should need a fee estimate");
+    assert!(err.to_string().contains("Image not found"), "{err}");
+    assert_eq!(mock.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_session_prove_batch_keeps_order() {
+    let mock = Arc::new(MockTransport::new());
+    for id in ["a", "b", "c"] {
+        mock.respond_ok_to(&TaskEndpoint::Prove, &added(MD5, id));
+    }
+    let session = ZkWasmServiceHelper::with_transport(mock.clone())
+        .with_signer(signer(PRIVATE_KEY))
+        .await
+        .expect("Should create session");
+
+    let requests = vec![
+        ProveRequest::builder().md5(MD5),
+        ProveRequest::builder().md5(MD5).public_input("not an input"),
+        ProveRequest::builder().md5(MD5),
+        ProveRequest::builder().md5(MD5),
+    ];
+    let report = session
+        .submit_prove_batch(requests, ProveBatchOptions::default())
+        .await
+        .expect("Should submit batch");
+
+    let ids: Vec<_> = report
+        .items
+        .iter()
+        .map(|item| item.result.as_ref().ok().map(|added| added.id.as_str()))
+        .collect();
+    assert_eq!(ids, [Some("a"), None, Some("b"), Some("c")]);
+    assert!(matches!(report.items[1].result, Err(ProveBatchError::Request(_))));
+    assert!(report.estimated_spend.is_none());
+    assert_eq!(mock.requests().len(), 3);
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_prove_batch_waits_for_mock_tasks() {
+    use std::time::Duration;
+
+    use crate::helper::MonitorOptions;
+    use crate::interface::ProofSubmitMode;
+
+    let server =
+        crate::mock::MockServer::start(crate::mock::MockConfig::default().task_duration(Duration::from_millis(200)))
+            .await
+            .expect("Should start mock server");
+    let fixtures = server.fixtures();
+    let helper = server.helper();
+    let fee = helper
+        .query_estimated_proof_fee(
+            fixtures.user_address.clone(),
+            fixtures.image_md5.clone(),
+            ProofSubmitMode::Manual,
+        )
+        .await
+        .expect("Should estimate fee")
+        .max
+        .expect("Should have a fee");
+
+    let session = helper
+        .with_signer(signer(&fixtures.private_key))
+        .await
+        .expect("Should create session");
+    let requests = (0..6).map(|i| {
+        ProveRequest::builder()
+            .md5(fixtures.image_md5.clone())
+            .public_input(format!("{i:#x}:i64"))
+    });
+    let report = session
+        .submit_prove_batch(
+            requests,
+            ProveBatchOptions::default().max_spend(fee * 5).wait(
+                Duration::from_secs(10),
+                MonitorOptions::default().interval(Duration::from_millis(50)),
+            ),
+        )
+        .await
+        .expect("Should submit batch");
+
+    assert_eq!(report.estimated_spend, Some(fee * 5));
+    assert!(report.wait_error.is_none());
+    assert_eq!(report.added().count(), 5);
+    for item in &report.items[..5] {
+        assert!(item.outcome.as_ref().is_some_and(crate::helper::TaskOutcome::is_done));
+    }
+    assert!(matches!(report.items[5].result, Err(ProveBatchError::SpendCapExceeded { .. })));
+}
+
+#[tokio::test]
+async fn test_prove_batch_stops_waiting_when_polls_keep_failing() {
+    use std::time::Duration;
+
+    use crate::helper::MonitorOptions;
+    use crate::helper::TaskOutcome;
+    use crate::helper::MAX_FAILED_POLLS;
+
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Prove, &added(MD5, "a"));
+    mock.respond_ok_to(&TaskEndpoint::Prove, &added(MD5, "b"));
+    // Every poll fetches both unknown tasks, and every fetch fails.
+    for _ in 0..2 * MAX_FAILED_POLLS {
+        mock.respond_error_to(&TaskEndpoint::Tasks, StatusCode::UNAUTHORIZED, "Invalid token");
+    }
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let report = zkh
+        .submit_prove_batch(
+            (0..2).map(request),
+            signer(PRIVATE_KEY),
+            ProveBatchOptions::default().wait(
+                Duration::from_secs(3600),
+                MonitorOptions::default().interval(Duration::from_millis(1)),
+            ),
+        )
+        .await
+        .expect("Should submit batch");
+
+    assert_eq!(report.added().count(), 2);
+    for item in &report.items {
+        assert!(matches!(item.outcome, Some(TaskOutcome::TimedOut { last_status: None })));
+    }
+    let wait_error = report.wait_error.expect("Should report the failed polls");
+    assert!(wait_error.to_string().contains("Invalid token"), "{wait_error}");
+    assert_eq!(mock.remaining(), 0);
+    assert_eq!(mock.requests().len(), 2 + 2 * MAX_FAILED_POLLS as usize);
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::header::HeaderValue;
+
+use super::stub::StubResponse;
+use super::stub::StubServer;
+use super::*;
+
+#[tokio::test]
+async fn test_builder_sends_configured_headers() {
+    let stub = StubServer::start(vec![StubResponse::ok(&serde_json::json!({
+        "total_images": 1,
+        "total_proofs": 2,
+        "total_tasks": 3,
+        "total_deployed": 4,
+    }))])
+    .await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-api-key", HeaderValue::from_static("secret"));
+    let zkh = ZkWasmServiceHelper::builder(stub.url.clone())
+        .connect_timeout(Duration::from_secs(5))
+        .read_timeout(Duration::from_secs(5))
+        .user_agent("zkp-test-agent/1.0")
+        .default_headers(headers)
+        .tcp_keepalive(Duration::from_secs(30))
+        .build()
+        .expect("Should build helper");
+
+    let res = zkh.query_statistics().await.expect("Should query statistics");
+    assert_eq!(res.total_tasks, 3);
+
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/statistics");
+    assert_eq!(requests[0].header("user-agent"), Some("zkp-test-agent/1.0"));
+    assert_eq!(requests[0].header("x-api-key"), Some("secret"));
+}
+
+#[tokio::test]
+async fn test_clones_share_pooled_connection() {
+    let stub = StubServer::start(vec![StubResponse::ok(&serde_json::json!({
+        "certified_prover_count": 1,
+        "active_prover_count": 1,
+        "intern_prover_count": 1,
+        "inactive_prover_count": 1,
+    }))])
+    .await;
+
+    let zkh = ZkWasmServiceHelper::builder(stub.url.clone())
+        .build()
+        .expect("Should build helper");
+    for _ in 0..3 {
+        let zkh = zkh.clone();
+        tokio::spawn(async move { zkh.query_prover_node_summary().await })
+            .await
+            .expect("Task should complete")
+            .expect("Should query summary");
+    }
+
+    assert_eq!(stub.requests().len(), 3);
+    assert_eq!(stub.connections(), 1);
+}
@@ -0,0 +1,164 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+
+use super::util::recorded_image;
+use super::util::recorded_task;
+use crate::helper::MockTransport;
+use crate::helper::Result;
+use crate::helper::TaskEndpoint;
+use crate::helper::Transport;
+use crate::helper::TransportRequest;
+use crate::helper::TransportResponse;
+use crate::helper::ZkWasmServiceHelper;
+use crate::interface::PaginationResult;
+use crate::interface::Task;
+use crate::interface::TaskStatus;
+
+/// Answers task and image queries after a short delay, with a record for every id or md5 except `missing` and an error
+/// for the ones in `failing`, and records how many requests were in flight at once.
+#[derive(Default)]
+struct SlowServer {
+    missing: Vec<String>,
+    failing: Vec<String>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+    requests: AtomicUsize,
+}
+
+#[async_trait]
+impl Transport for SlowServer {
+    async fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        let key = if request.endpoint == TaskEndpoint::Image {
+            "md5"
+        } else {
+            "id"
+        };
+        let query: Vec<(String, String)> = serde_urlencoded::from_str(&request.query).expect("Should decode query");
+        let id = query
+            .into_iter()
+            .find_map(|(k, value)| (k == key).then_some(value))
+            .expect("Should query by id or md5");
+        if self.failing.contains(&id) {
+            let body = serde_json::json!({ "success": false, "error": { "message": "Invalid id" } });
+            return Ok(TransportResponse::new(StatusCode::BAD_REQUEST, body.to_string()));
+        }
+        let found = !self.missing.contains(&id);
+        let result = match request.endpoint {
+            TaskEndpoint::Tasks => {
+                let data: Vec<Task> = found.then(|| recorded_task(&id, TaskStatus::Done)).into_iter().collect();
+                let total = data.len() as u64;
+                serde_json::to_value(PaginationResult { data, total }).expect("Should encode tasks")
+            }
+            TaskEndpoint::Image => {
+                serde_json::to_value(found.then(|| recorded_image(&id)).into_iter().collect::<Vec<_>>())
+                    .expect("Should encode images")
+            }
+            _ => unreachable!("Unexpected request to {}", request.path()),
+        };
+        let body = serde_json::json!({ "success": true, "result": result });
+        Ok(TransportResponse::new(StatusCode::OK, body.to_string()))
+    }
+}
+
+fn ids(count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("{i:024x}")).collect()
+}
+
+#[tokio::test]
+async fn test_query_tasks_from_ids_keeps_order_and_misses() {
+    let ids = ids(45);
+    let server = Arc::new(SlowServer {
+        missing: vec![ids[3].clone(), ids[17].clone()],
+        failing: vec![ids[30].clone()],
+        ..SlowServer::default()
+    });
+    let zkh = ZkWasmServiceHelper::builder(String::new())
+        .transport(server.clone())
+        .max_concurrency(4)
+        .build()
+        .expect("Should build helper");
+
+    let tasks = zkh.query_tasks_from_ids(ids.clone()).await;
+    assert_eq!(tasks.len(), ids.len());
+    for (i, (id, (queried, task))) in ids.iter().zip(&tasks).enumerate() {
+        assert_eq!(queried, id);
+        match task {
+            Ok(Some(task)) => assert_eq!(task._id.oid, *id),
+            Ok(None) => assert!(i == 3 || i == 17, "Task {i} should be found"),
+            Err(e) => assert!(i == 30 && e.to_string().contains("Invalid id"), "Task {i} should not fail: {e}"),
+        }
+    }
+    assert_eq!(tasks.iter().filter(|(_, task)| matches!(task, Ok(None))).count(), 2);
+    assert_eq!(tasks.iter().filter(|(_, task)| task.is_err()).count(), 1);
+    // The failed id does not stop the others.
+    assert_eq!(server.requests.load(Ordering::SeqCst), ids.len());
+    assert_eq!(server.max_in_flight.load(Ordering::SeqCst), ids.len().min(4));
+}
+
+#[tokio::test]
+async fn test_query_tasks_from_ids_runs_ids_concurrently() {
+    let max_concurrency = crate::helper::DEFAULT_MAX_CONCURRENCY;
+    for count in [3, max_concurrency, 100] {
+        let server = Arc::new(SlowServer::default());
+        let zkh = ZkWasmServiceHelper::with_transport(server.clone());
+        assert_eq!(zkh.max_concurrency(), max_concurrency);
+
+        let tasks = zkh.query_tasks_from_ids(ids(count)).await;
+        assert_eq!(tasks.len(), count);
+        assert!(tasks.iter().all(|(_, task)| matches!(task, Ok(Some(_)))));
+        assert_eq!(server.max_in_flight.load(Ordering::SeqCst), count.min(max_concurrency));
+    }
+
+    let zkh = ZkWasmServiceHelper::with_transport(Arc::new(SlowServer::default()));
+    assert!(zkh.query_tasks_from_ids(vec![]).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_query_images_maps_md5s() {
+    let md5s = ids(20);
+    let server = Arc::new(SlowServer {
+        missing: vec![md5s[5].clone()],
+        ..SlowServer::default()
+    });
+    let zkh = ZkWasmServiceHelper::builder(String::new())
+        .transport(server.clone())
+        .max_concurrency(3)
+        .build()
+        .expect("Should build helper");
+
+    let mut requested = md5s.clone();
+    requested.extend(md5s.iter().take(4).cloned());
+    let images = zkh.query_images(requested).await.expect("Should query images");
+    assert_eq!(images.len(), md5s.len());
+    for (i, md5) in md5s.iter().enumerate() {
+        let image = images.get(md5).expect("Should map every md5");
+        assert_eq!(image.as_ref().map(|image| image.md5.as_str()), (i != 5).then_some(md5.as_str()));
+    }
+    assert_eq!(server.requests.load(Ordering::SeqCst), md5s.len());
+    assert_eq!(server.max_in_flight.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_query_image_handles_empty_responses() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Image, &serde_json::json!([]));
+    mock.respond_ok_to(&TaskEndpoint::Image, &serde_json::json!([null]));
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let image = zkh.query_image("0".repeat(32)).await.expect("Should accept an empty response");
+    assert!(image.is_none());
+    let image = zkh.query_image("0".repeat(32)).await.expect("Should accept a null image");
+    assert!(image.is_none());
+    assert_eq!(mock.remaining(), 0);
+}
@@ -0,0 +1,100 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::helper::canonical_message;
+use crate::helper::util::sign_object;
+use crate::helper::verify_signed_request;
+use crate::helper::SerializationAttributes;
+use crate::helper::WalletSigner;
+use crate::helper::CANONICAL_MESSAGE_VERSION;
+use crate::interface::AddImageParams;
+use crate::interface::ForceDryrunFailsToReprocessParams;
+use crate::interface::ForceUnprovableToReprocessParams;
+use crate::interface::LogQuery;
+use crate::interface::ModifyImageParams;
+use crate::interface::ProvingParams;
+use crate::interface::ResetImageParams;
+use crate::interface::SetMaintenanceModeParams;
+
+/// Canonical messages and signatures of every kind of signed request, see `source` in the file for where the messages
+/// come from and `scripts/canonical_messages.mjs` to take them from the TypeScript helper.
+const VECTORS: &str = "./data/canonical_messages.json";
+
+/// The payloads of a vector's params and the fields holding their md5.
+const PAYLOAD_MD5S: [(&str, &str); 4] = [
+    ("image", "image_md5"),
+    ("initial_context", "initial_context_md5"),
+    ("input_context", "input_context_md5"),
+    ("reset_context", "reset_context_md5"),
+];
+
+#[derive(Deserialize, Serialize)]
+struct VectorFile {
+    version: u32,
+    source: String,
+    private_key: String,
+    vectors: Vec<Vector>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Vector {
+    name: String,
+    params: serde_json::Value,
+    message: String,
+    signature: String,
+}
+
+/// Returns the canonical message and signature of the vector's params, decoded as `T`.
+async fn sign<T>(vector: &Vector, signer: &WalletSigner) -> (String, String)
+where
+    T: DeserializeOwned + Serialize + SerializationAttributes,
+{
+    let params: T = serde_json::from_value(vector.params.clone())
+        .unwrap_or_else(|e| unreachable!("Params of {} should decode: {e}", vector.name));
+    let message = canonical_message(&params).expect("Should build message");
+    let signature = sign_object(&params, signer).await.expect("Should sign");
+    let recovered = verify_signed_request(&params, &signature).expect("Should recover signer");
+    assert_eq!(recovered, signer.wallet_address());
+    (message, signature)
+}
+
+/// Checks that every payload md5 of the vector is the md5 of its payload, as the service would compute it.
+fn check_payload_md5s(vector: &Vector) {
+    for (payload, md5) in PAYLOAD_MD5S {
+        let Some(payload) = vector.params.get(payload) else {
+            continue;
+        };
+        let bytes: Vec<u8> = serde_json::from_value(payload.clone()).expect("Should decode payload");
+        let expected = format!("{:X}", md5::compute(bytes));
+        assert_eq!(vector.params[md5], expected.as_str(), "{md5} of {}", vector.name);
+    }
+}
+
+async fn sign_vector(vector: &Vector, signer: &WalletSigner) -> (String, String) {
+    match vector.name.split(':').next().unwrap_or_default() {
+        "prove" => sign::<ProvingParams>(vector, signer).await,
+        "setup" => sign::<AddImageParams>(vector, signer).await,
+        "reset" => sign::<ResetImageParams>(vector, signer).await,
+        "modify" => sign::<ModifyImageParams>(vector, signer).await,
+        "logs" => sign::<LogQuery>(vector, signer).await,
+        "set_maintenance_mode" => sign::<SetMaintenanceModeParams>(vector, signer).await,
+        "force_unprovable_to_reprocess" => sign::<ForceUnprovableToReprocessParams>(vector, signer).await,
+        "force_dryrun_fails_to_reprocess" => sign::<ForceDryrunFailsToReprocessParams>(vector, signer).await,
+        name => unreachable!("No type registered for vector {name}"),
+    }
+}
+
+#[tokio::test]
+async fn test_canonical_message_vectors() {
+    let file = std::fs::read_to_string(VECTORS).expect("Should read vectors");
+    let vectors: VectorFile = serde_json::from_str(&file).expect("Should decode vectors");
+    assert_eq!(vectors.version, CANONICAL_MESSAGE_VERSION, "Vectors are for another version");
+    let signer = WalletSigner::from_private_key(&vectors.private_key).expect("Should parse private key");
+    for vector in &vectors.vectors {
+        check_payload_md5s(vector);
+        let (message, signature) = sign_vector(vector, &signer).await;
+        assert_eq!(message, vector.message, "Message of {}", vector.name);
+        assert_eq!(signature, vector.signature, "Signature of {}", vector.name);
+    }
+}
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::*;
+use crate::helper::endpoint::ZkWasmServiceEndpoint;
+use crate::helper::Cassette;
+use crate::helper::CassetteTransport;
+use crate::helper::Interaction;
+use crate::helper::MockTransport;
+use crate::helper::ReqwestTransport;
+use crate::helper::ServiceError;
+use crate::helper::TaskEndpoint;
+use crate::interface::AppConfig;
+use crate::interface::ArchivedFinalBatchProof;
+use crate::interface::AutoSubmitProof;
+use crate::interface::Image;
+use crate::interface::PaginationResult;
+use crate::interface::Round1Info;
+use crate::interface::Round2Info;
+use crate::interface::StatisticsInfo;
+use crate::interface::Task;
+use crate::interface::VolumeDetailResponse;
+
+/// Synthetic cassettes recorded from the mock server, re-record them from a real server with
+/// `cargo test test_record_server_cassette -- --ignored`.
+const SERVER_CASSETTE: &str = "./data/cassettes/server.json";
+const ARCHIVE_CASSETTE: &str = "./data/cassettes/archive.json";
+
+fn temp_cassette(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("zkp-service-helper-{}-{name}.json", std::process::id()))
+}
+
+fn statistics(total_tasks: u64) -> StatisticsInfo {
+    StatisticsInfo {
+        total_images: 1,
+        total_proofs: 2,
+        total_tasks,
+        total_deployed: 4,
+    }
+}
+
+#[tokio::test]
+async fn test_cassette_records_and_replays() {
+    let path = temp_cassette("record");
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Statistics, &statistics(3))
+        .respond_ok_to(&TaskEndpoint::Statistics, &statistics(5))
+        .respond_error_to(&TaskEndpoint::Config, reqwest::StatusCode::FORBIDDEN, "Not allowed");
+
+    let recorder = ZkWasmServiceHelper::with_transport(Arc::new(CassetteTransport::record(mock, path.clone())));
+    recorder.query_statistics().await.expect("Should query statistics");
+    recorder.query_statistics().await.expect("Should query statistics");
+    recorder.query_config().await.err().expect("Should be rejected");
+
+    let cassette = Cassette::load(&path).expect("Should load cassette");
+    assert_eq!(cassette.interactions.len(), 3);
+    assert_eq!(cassette.interactions[0].path, "/statistics");
+
+    let replayer =
+        ZkWasmServiceHelper::with_transport(Arc::new(CassetteTransport::replay(path.clone()).expect("Should replay")));
+    let first = replayer.query_statistics().await.expect("Should replay statistics");
+    let second = replayer.query_statistics().await.expect("Should replay statistics");
+    let again = replayer.query_statistics().await.expect("Should replay last statistics");
+    assert_eq!((first.total_tasks, second.total_tasks, again.total_tasks), (3, 5, 5));
+
+    let err = replayer.query_config().await.err().expect("Should replay rejection");
+    assert!(
+        matches!(&err, ServiceError::ServerRejected { status, message, .. } if *status == reqwest::StatusCode::FORBIDDEN && message == "Not allowed"),
+        "{err:?}"
+    );
+
+    let err = replayer
+        .query_task_from_id("unknown".to_string())
+        .await
+        .err()
+        .expect("Should not be recorded");
+    assert!(
+        matches!(&err, ServiceError::HttpStatus { status, .. } if *status == reqwest::StatusCode::NOT_IMPLEMENTED),
+        "{err:?}"
+    );
+    let _ = std::fs::remove_file(path);
+}
+
+fn decode<V: for<'de> Deserialize<'de> + Serialize>(interaction: &Interaction) -> crate::helper::Result<()> {
+    let response = reqwest::StatusCode::from_u16(interaction.status).expect("Should be a valid status");
+    let body = match &interaction.body {
+        crate::helper::RecordedBody::Json(v) => v.to_string(),
+        crate::helper::RecordedBody::Text(v) => v.clone(),
+    };
+    ZkWasmServiceEndpoint::decode_response::<V>(response, &body).map(|_| ())
+}
+
+/// Decodes every recorded payload into its interface type. The committed cassettes are synthetic, so this checks the
+/// decoders against the mock server's encoding and says nothing about real server payloads.
+#[test]
+fn test_recorded_mock_payloads_decode() {
+    let server = Cassette::load(SERVER_CASSETTE).expect("Should load server cassette");
+    let archive = Cassette::load(ARCHIVE_CASSETTE).expect("Should load archive cassette");
+    assert!(!server.interactions.is_empty() && !archive.interactions.is_empty());
+    for interaction in server.interactions.iter().chain(&archive.interactions) {
+        let path = interaction.path.as_str();
+        let res = match path {
+            "/config" => decode::<AppConfig>(interaction),
+            "/image" => decode::<Vec<Option<Image>>>(interaction),
+            "/tasks" => decode::<PaginationResult<Vec<Task>>>(interaction),
+            "/round1_batch_proofs" => decode::<PaginationResult<Vec<AutoSubmitProof>>>(interaction),
+            "/round2_batch_proofs" => decode::<PaginationResult<Vec<Round1Info>>>(interaction),
+            "/final_batch_proofs" => decode::<PaginationResult<Vec<Round2Info>>>(interaction),
+            _ if path.starts_with("/archive/task_volume/") => decode::<VolumeDetailResponse>(interaction),
+            _ if path.starts_with("/archive/task/") => decode::<Task>(interaction),
+            _ if path.starts_with("/archive/auto_submit_info") => decode::<ArchivedFinalBatchProof>(interaction),
+            _ => unreachable!("No type registered for recorded path {path}"),
+        };
+        res.unwrap_or_else(|e| unreachable!("Recorded {path} payload should decode: {e}"));
+    }
+}
+
+fn recorder(server_url: &str, cassette: &str) -> ZkWasmServiceHelper {
+    let server = ReqwestTransport::new(server_url.to_string(), reqwest::Client::new());
+    ZkWasmServiceHelper::with_transport(Arc::new(CassetteTransport::record(Arc::new(server), cassette)))
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_record_server_cassette() {
+    let zkh = recorder(&CONFIG.details.server_url, SERVER_CASSETTE);
+    let auto_submit_task_id = CONFIG.auto_submit.task_id_in_auto_submit_batch.clone();
+    zkh.query_config().await.expect("Should query config");
+    zkh.query_image(CONFIG.query.md5.clone()).await.expect("Should query image");
+    zkh.query_task_from_id(CONFIG.query.task_id.clone())
+        .await
+        .expect("Should query task");
+    zkh.query_auto_submit_proofs(None, Some(auto_submit_task_id.clone()), None, None, None, None, None)
+        .await
+        .expect("Should query auto submit proofs");
+    zkh.query_round1_info(None, None, Some(auto_submit_task_id.clone()), None, None, None, None, None)
+        .await
+        .expect("Should query round 1 info");
+    zkh.query_round2_info(None, None, Some(auto_submit_task_id), None, None, None, None)
+        .await
+        .expect("Should query round 2 info");
+
+    let archive = recorder(&CONFIG.archive.server_url, ARCHIVE_CASSETTE);
+    archive
+        .query_archived_task(CONFIG.archive.archived_task_id.clone())
+        .await
+        .expect("Should query archived task");
+    archive
+        .query_archived_auto_submit_info_by_archive_id(CONFIG.archive.id.clone(), CONFIG.details.chain_id)
+        .await
+        .expect("Should query archived auto submit info");
+    archive
+        .query_archive_task_volume(CONFIG.archive.archive_volume_name.clone(), None, None)
+        .await
+        .expect("Should query archive task volume");
+}
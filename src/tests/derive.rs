@@ -0,0 +1,66 @@
+use serde::Serialize;
+
+use crate::helper::canonical_message;
+use crate::helper::SerializationAttributes;
+use crate::interface::AddImageParams;
+use crate::interface::LogQuery;
+use crate::interface::ModifyImageParams;
+use crate::interface::ProvingParams;
+use crate::interface::ResetImageParams;
+
+#[derive(Serialize, SerializationAttributes)]
+struct Inner {
+    #[zkp(skip_signing)]
+    secret: String,
+    public: String,
+}
+
+#[derive(Serialize, SerializationAttributes)]
+#[zkp(json_body)]
+struct Outer {
+    #[serde(rename = "payload", skip_serializing_if = "Vec::is_empty")]
+    #[zkp(bytes, skip_signing)]
+    data: Vec<u8>,
+    #[serde(flatten)]
+    inner: Inner,
+    #[zkp(bytes)]
+    r#type: Vec<u8>,
+}
+
+#[test]
+fn test_derived_attributes_match_request_types() {
+    assert_eq!(AddImageParams::fields_which_are_bytes(), ["image"]);
+    assert_eq!(AddImageParams::fields_to_ignore(), ["image", "initial_context"]);
+    assert!(!AddImageParams::requires_json_body());
+    assert!(ProvingParams::fields_which_are_bytes().is_empty());
+    assert_eq!(ProvingParams::fields_to_ignore(), ["input_context"]);
+    assert!(ResetImageParams::fields_which_are_bytes().is_empty() && ResetImageParams::fields_to_ignore().is_empty());
+    assert!(LogQuery::requires_json_body());
+    assert!(!ModifyImageParams::requires_json_body());
+
+    let query = LogQuery {
+        id: "task".to_string(),
+        user_address: "0xabc".to_string(),
+    };
+    assert_eq!(
+        canonical_message(&query).expect("Should build message"),
+        r#"{"id":"task","user_address":"0xabc"}"#
+    );
+}
+
+#[test]
+fn test_derive_uses_serialized_and_flattened_names() {
+    assert_eq!(Outer::fields_which_are_bytes(), ["payload", "type"]);
+    assert_eq!(Outer::fields_to_ignore(), ["payload", "secret"]);
+    assert!(Outer::requires_json_body());
+
+    let outer = Outer {
+        data: vec![1],
+        inner: Inner {
+            secret: "secret".to_string(),
+            public: "public".to_string(),
+        },
+        r#type: vec![2],
+    };
+    assert_eq!(canonical_message(&outer).expect("Should build message"), "public2");
+}
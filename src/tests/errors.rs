@@ -0,0 +1,128 @@
+use super::stub::StubResponse;
+use super::stub::StubServer;
+use super::*;
+use crate::helper::endpoint::ZkWasmServiceEndpoint;
+use crate::helper::ServiceError;
+use crate::helper::WalletSigner;
+use crate::interface::StatisticsInfo;
+
+#[tokio::test]
+async fn test_transport_error() {
+    // Nothing listens on the discard port, so the connection is refused.
+    let zkh = ZkWasmServiceHelper::new("http://127.0.0.1:9".to_string());
+    let err = zkh.query_config().await.err().expect("Should fail to connect");
+    assert!(matches!(err, ServiceError::Transport(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_decode_error_reports_path_and_body() {
+    let stub = StubServer::start(vec![StubResponse::ok(&serde_json::json!({
+        "total_images": 1,
+        "total_proofs": "not a number",
+        "total_tasks": 3,
+        "total_deployed": 4,
+    }))])
+    .await;
+
+    let zkh = ZkWasmServiceHelper::new(stub.url.clone());
+    let err = zkh.query_statistics().await.err().expect("Should fail to decode");
+    let ServiceError::Decode { path, body, .. } = err else {
+        unreachable!("Expected decode error, got {err:?}");
+    };
+    assert_eq!(path, "result.total_proofs");
+    assert!(body.contains("not a number"));
+}
+
+#[tokio::test]
+async fn test_server_rejected() {
+    let stub = StubServer::start(vec![StubResponse::json(
+        200,
+        &serde_json::json!({ "success": false, "result": null }),
+    )])
+    .await;
+
+    let zkh = ZkWasmServiceHelper::new(stub.url.clone());
+    let err = zkh.query_user("0x0".to_string()).await.err().expect("Should be rejected");
+    assert!(matches!(err, ServiceError::ServerRejected { .. }), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_server_rejected_with_error_payload() {
+    let stub = StubServer::start(vec![StubResponse::json(
+        500,
+        &serde_json::json!({
+            "success": false,
+            "error": { "code": 401, "message": "Invalid signature" },
+        }),
+    )])
+    .await;
+
+    let zkh = ZkWasmServiceHelper::new(stub.url.clone());
+    let err = zkh.query_statistics().await.err().expect("Should be rejected");
+    let ServiceError::ServerRejected {
+        status,
+        code,
+        message,
+    } = err
+    else {
+        unreachable!("Expected server rejection, got {err:?}");
+    };
+    assert_eq!(status, reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(code.as_deref(), Some("401"));
+    assert_eq!(message, "Invalid signature");
+}
+
+#[tokio::test]
+async fn test_http_status_error_page() {
+    let stub = StubServer::start(vec![StubResponse::text(502, "<html>Bad Gateway</html>")]).await;
+
+    let zkh = ZkWasmServiceHelper::new(stub.url.clone());
+    let err = zkh.query_statistics().await.err().expect("Should fail");
+    let ServiceError::HttpStatus { status, body } = err else {
+        unreachable!("Expected HTTP status error, got {err:?}");
+    };
+    assert_eq!(status, reqwest::StatusCode::BAD_GATEWAY);
+    assert_eq!(body, "<html>Bad Gateway</html>");
+}
+
+#[test]
+fn test_decode_response_envelopes() {
+    let ok = reqwest::StatusCode::OK;
+
+    let err = ZkWasmServiceEndpoint::decode_response::<StatisticsInfo>(
+        ok,
+        r#"{"success": false, "error": "Image not found"}"#,
+    )
+    .err()
+    .expect("Should be rejected");
+    assert!(
+        matches!(&err, ServiceError::ServerRejected { code: None, message, .. } if message == "Image not found"),
+        "{err:?}"
+    );
+
+    let err = ZkWasmServiceEndpoint::decode_response::<StatisticsInfo>(
+        ok,
+        r#"{"success": false, "error": {"code": "E_LIMIT", "msg": "Too many tasks"}}"#,
+    )
+    .err()
+    .expect("Should be rejected");
+    assert!(
+        matches!(&err, ServiceError::ServerRejected { code: Some(c), message, .. } if c == "E_LIMIT" && message == "Too many tasks"),
+        "{err:?}"
+    );
+
+    let err = ZkWasmServiceEndpoint::decode_response::<StatisticsInfo>(ok, "not json")
+        .err()
+        .expect("Should fail to decode");
+    assert!(matches!(err, ServiceError::Decode { .. }), "{err:?}");
+
+    let res = ZkWasmServiceEndpoint::decode_response::<Option<String>>(ok, r#"{"success": true, "result": null}"#)
+        .expect("Should decode");
+    assert!(res.is_none());
+}
+
+#[test]
+fn test_signing_error() {
+    let err = WalletSigner::from_private_key("not a key").expect_err("Should fail to parse");
+    assert!(matches!(err, ServiceError::Signing(_)), "{err:?}");
+}
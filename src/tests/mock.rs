@@ -0,0 +1,243 @@
+use std::time::Duration;
+
+use ethers::signers::LocalWallet;
+use ethers::signers::Signer;
+
+use crate::helper::endpoint::ZkWasmServiceEndpoint;
+use crate::helper::util::sign_object;
+use crate::helper::ServiceError;
+use crate::helper::TaskEndpoint;
+use crate::interface::AdminRequestType;
+use crate::interface::BaseProvingParams;
+use crate::interface::CustomContext;
+use crate::interface::MaintenanceModeType;
+use crate::interface::ProofSubmitMode;
+use crate::interface::ProvingParams;
+use crate::interface::TaskStatus;
+use crate::mock::MockConfig;
+use crate::mock::MockServer;
+
+/// A development key which the mock server knows nothing about.
+const OTHER_PRIVATE_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+/// A fixed prove request of the seeded user for the seeded image, written out field by field as it goes over the wire,
+/// with its signed message and signature.
+///
+/// The vector is fixed here rather than produced by the helper, so the mock server's signature check is tested against
+/// the service's message format instead of against the helper's own message builder. It is spelled out from the
+/// message format the service expects and signed with the seeded development key, it was not captured from the live
+/// service.
+const VECTOR_USER_ADDRESS: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+const VECTOR_MD5: &str = "319936F5C0F8203A7759EF58EC667249";
+const VECTOR_PUBLIC_INPUT: &str = "0x1:i64";
+const VECTOR_PRIVATE_INPUT: &str = "0x2a:i64";
+const VECTOR_PROOF_SUBMIT_MODE: &str = "Manual";
+const VECTOR_MESSAGE: &str =
+    "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266319936F5C0F8203A7759EF58EC6672490x1:i640x2a:i64Manual";
+const VECTOR_SIGNATURE: &str = "a9bf042c04a848a5d7de2644170d5596134de5e7f22e4b50f3235e7cac069c8879a43ef5c0b0050b91f4e652e6d7a4ddc3b9d178e646737dcdc5fec53acd5a061c";
+/// Signature of the same fields in another order and encoding, which the service rejects.
+const VECTOR_MISORDERED_SIGNATURE: &str = "a4a722bc3fc18c03db3c2323eaf7419df149fd51d3e219c4f3f7ef719cf4eacc05e5526cd8cf823a433bf59401997132880c3fb073752c2398dccaa0a18cfc361b";
+
+fn other_address() -> String {
+    let wallet: LocalWallet = OTHER_PRIVATE_KEY.parse().expect("Private key should parse");
+    format!("{:#x}", wallet.address())
+}
+
+async fn start(task_duration: Duration) -> MockServer {
+    MockServer::start(MockConfig::default().task_duration(task_duration))
+        .await
+        .expect("Should start mock server")
+}
+
+async fn add_prove(server: &MockServer, user_address: String, private_key: &str) -> crate::helper::Result<String> {
+    let res = server
+        .helper()
+        .add_prove(
+            user_address,
+            server.fixtures().image_md5.clone(),
+            vec!["0x1:i64".to_string()],
+            vec![],
+            ProofSubmitMode::Manual,
+            CustomContext::Without,
+            super::util::signer(private_key),
+        )
+        .await?;
+    Ok(res.id)
+}
+
+/// Posts a prove task for `user_address` signed with `private_key`, bypassing the helper's own signature check.
+async fn add_prove_unchecked(
+    server: &MockServer,
+    user_address: String,
+    private_key: &str,
+) -> crate::helper::Result<()> {
+    let params = ProvingParams {
+        base: BaseProvingParams {
+            user_address,
+            md5: server.fixtures().image_md5.clone(),
+            public_inputs: vec!["0x1:i64".to_string()],
+            private_inputs: vec![],
+            proof_submit_mode: ProofSubmitMode::Manual,
+        },
+        context: CustomContext::Without,
+    };
+    let signature = sign_object(&params, &super::util::signer(private_key)).await?;
+    ZkWasmServiceEndpoint::new(server.url().to_string())
+        .post::<_, serde_json::Value>(TaskEndpoint::Prove, params, Some(signature))
+        .await
+        .map(|_| ())
+}
+
+fn assert_rejected(err: &ServiceError, expected: reqwest::StatusCode) {
+    assert!(
+        matches!(err, ServiceError::ServerRejected { status, .. } if *status == expected),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_mock_rejects_signature_of_other_user() {
+    let server = start(Duration::from_secs(1)).await;
+    let err = add_prove_unchecked(&server, server.fixtures().user_address.clone(), OTHER_PRIVATE_KEY)
+        .await
+        .expect_err("Should reject signature");
+    assert_rejected(&err, reqwest::StatusCode::UNAUTHORIZED);
+}
+
+/// Posts the prove request of the fixed vector without going through the helper, signed with `signature`.
+async fn post_vector(server: &MockServer, signature: &str) -> reqwest::Response {
+    let form = reqwest::multipart::Form::new()
+        .text("user_address", VECTOR_USER_ADDRESS)
+        .text("md5", VECTOR_MD5)
+        .text("public_inputs", VECTOR_PUBLIC_INPUT)
+        .text("private_inputs", VECTOR_PRIVATE_INPUT)
+        .text("proof_submit_mode", VECTOR_PROOF_SUBMIT_MODE);
+    reqwest::Client::new()
+        .post(format!("{}/prove", server.url()))
+        .header("x-eth-signature", signature)
+        .multipart(form)
+        .send()
+        .await
+        .expect("Should send request")
+}
+
+#[tokio::test]
+async fn test_mock_checks_fixed_signature_vector() {
+    let server = start(Duration::from_secs(1)).await;
+    assert_eq!(server.fixtures().user_address, VECTOR_USER_ADDRESS);
+    assert_eq!(server.fixtures().image_md5, VECTOR_MD5);
+
+    // The signature is over the literal message, independently of how the helper builds messages.
+    let signature: ethers::types::Signature = VECTOR_SIGNATURE.parse().expect("Should parse signature");
+    let signer = signature.recover(VECTOR_MESSAGE).expect("Should recover signer");
+    assert_eq!(format!("{signer:#x}"), VECTOR_USER_ADDRESS);
+
+    let res = post_vector(&server, VECTOR_SIGNATURE).await;
+    assert_eq!(res.status(), reqwest::StatusCode::OK, "{:?}", res.text().await);
+    let res = post_vector(&server, VECTOR_MISORDERED_SIGNATURE).await;
+    assert_eq!(res.status(), reqwest::StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_mock_charges_credits() {
+    let server = start(Duration::from_secs(1)).await;
+    let fixtures = server.fixtures();
+    let credits = |user: Option<crate::interface::User>| user.expect("Should find user").credits;
+
+    let before = credits(
+        server
+            .helper()
+            .query_user(fixtures.user_address.clone())
+            .await
+            .expect("Should query user"),
+    );
+    add_prove(&server, fixtures.user_address.clone(), &fixtures.private_key)
+        .await
+        .expect("Should add prove task");
+    let after = credits(
+        server
+            .helper()
+            .query_user(fixtures.user_address.clone())
+            .await
+            .expect("Should query user"),
+    );
+    assert_eq!(
+        before.parse::<u128>().expect("Should parse credits") - 1000,
+        after.parse::<u128>().expect("Should parse credits")
+    );
+
+    let err = add_prove(&server, other_address(), OTHER_PRIVATE_KEY)
+        .await
+        .expect_err("Should have no credits");
+    assert_rejected(&err, reqwest::StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_mock_task_moves_from_pending_to_done() {
+    let server = start(Duration::from_millis(400)).await;
+    let fixtures = server.fixtures();
+    let id = add_prove(&server, fixtures.user_address.clone(), &fixtures.private_key)
+        .await
+        .expect("Should add prove task");
+
+    let status = || async {
+        let task = server.helper().query_task_from_id(id.clone()).await.expect("Should query task");
+        task.expect("Should find task").status
+    };
+    assert!(matches!(status().await, TaskStatus::Pending));
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(matches!(status().await, TaskStatus::Done));
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn test_mock_maintenance_mode_blocks_tasks() {
+    let server = start(Duration::from_secs(1)).await;
+    let fixtures = server.fixtures();
+
+    let err = server
+        .helper()
+        .set_maintenance_mode(
+            MaintenanceModeType::Enabled,
+            0,
+            AdminRequestType::MaintenanceMode,
+            other_address(),
+            super::util::signer(OTHER_PRIVATE_KEY),
+        )
+        .await
+        .expect_err("Should require an admin");
+    assert_rejected(&err, reqwest::StatusCode::FORBIDDEN);
+
+    server
+        .helper()
+        .set_maintenance_mode(
+            MaintenanceModeType::Enabled,
+            0,
+            AdminRequestType::MaintenanceMode,
+            fixtures.user_address.clone(),
+            super::util::signer(&fixtures.private_key),
+        )
+        .await
+        .expect("Should enable maintenance mode");
+    let err = add_prove(&server, fixtures.user_address.clone(), &fixtures.private_key)
+        .await
+        .expect_err("Should be in maintenance mode");
+    assert_rejected(&err, reqwest::StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_mock_claims_deposit_once() {
+    let server = start(Duration::from_secs(1)).await;
+    let txhash = server.fixtures().unclaimed_deposit_txhash.clone();
+
+    let deposit = server.helper().add_payment(txhash.clone()).await.expect("Should claim deposit");
+    assert_eq!(deposit.txhash, txhash);
+
+    let err = server
+        .helper()
+        .add_payment(txhash)
+        .await
+        .err()
+        .expect("Should be claimed already");
+    assert_rejected(&err, reqwest::StatusCode::BAD_REQUEST);
+}
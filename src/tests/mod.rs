@@ -3,9 +3,40 @@
 use super::helper::ZkWasmServiceHelper;
 
 mod archive;
+mod batch;
+mod builder;
+mod bulk;
+mod canonical;
+mod cassette;
+mod derive;
+mod errors;
+#[cfg(feature = "mock")]
+mod mock;
+mod monitor;
+mod multipart;
+mod paginate;
+mod pipeline;
 mod queries;
+mod query_builders;
+mod retry;
+mod session;
+mod signature;
+mod signer;
+mod stub;
+mod task_requests;
 mod tasks;
+mod telemetry;
+mod transport;
+mod upload;
 mod util;
+mod wasm_image;
+mod watch;
+
+#[cfg(feature = "mock")]
+static MOCK: once_cell::sync::Lazy<crate::mock::MockServer> = once_cell::sync::Lazy::new(|| {
+    let config = crate::mock::MockConfig::default().task_duration(std::time::Duration::from_secs(1));
+    crate::mock::MockServer::start_in_background(config).expect("Should start mock server")
+});
 
 static CONFIG: once_cell::sync::Lazy<util::TestConfig> = once_cell::sync::Lazy::new(util::TestConfig::init);
 static ZKH: once_cell::sync::Lazy<ZkWasmServiceHelper> =
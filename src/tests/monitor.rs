@@ -0,0 +1,155 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use reqwest::StatusCode;
+
+use super::util::recorded_task;
+use super::util::respond_tasks;
+use crate::helper::MockTransport;
+use crate::helper::MonitorOptions;
+use crate::helper::TaskEndpoint;
+use crate::helper::TaskMonitor;
+use crate::helper::ZkWasmServiceHelper;
+use crate::interface::ConciseTask;
+use crate::interface::PaginationResult;
+use crate::interface::Task;
+use crate::interface::TaskStatus;
+
+const FIRST: &str = "690000000000000000000001";
+const SECOND: &str = "690000000000000000000002";
+
+fn concise(task: &Task) -> ConciseTask {
+    ConciseTask {
+        _id: task._id.clone(),
+        user_address: task.user_address.clone(),
+        md5: task.md5.clone(),
+        task_type: task.task_type.clone(),
+        status: task.status.clone(),
+        submit_time: task.submit_time.clone(),
+        process_started: task.process_started.clone(),
+        process_finished: task.process_finished.clone(),
+        proof_submit_mode: task.proof_submit_mode.clone(),
+        auto_submit_status: task.auto_submit_status.clone(),
+    }
+}
+
+fn respond_concise(mock: &MockTransport, tasks: &[Task]) {
+    let data: Vec<_> = tasks.iter().map(concise).collect();
+    let total = data.len() as u64;
+    mock.respond_ok_to(&TaskEndpoint::ConciseTasks, &PaginationResult { data, total });
+}
+
+fn count(mock: &MockTransport, endpoint: &TaskEndpoint) -> usize {
+    mock.requests().iter().filter(|r| r.endpoint == *endpoint).count()
+}
+
+#[tokio::test]
+async fn test_monitor_only_fetches_changed_tasks() {
+    let mock = Arc::new(MockTransport::new());
+    let monitor = TaskMonitor::new(ZkWasmServiceHelper::with_transport(mock.clone()), MonitorOptions::default());
+    monitor.watch(SECOND);
+    monitor.watch(FIRST);
+    monitor.watch(FIRST);
+    assert_eq!(monitor.watched(), vec![FIRST, SECOND]);
+
+    // The first poll fetches every task in full.
+    respond_tasks(&mock, vec![recorded_task(FIRST, TaskStatus::Pending)]);
+    respond_tasks(&mock, vec![recorded_task(SECOND, TaskStatus::Processing)]);
+    let events: Vec<_> = monitor.poll().await.into_iter().map(|e| e.expect("Should poll")).collect();
+    assert_eq!(events.len(), 2);
+    assert!(events[0].id == FIRST && events[0].old_status.is_none() && events[0].new_status == TaskStatus::Pending);
+    assert!(events[1].id == SECOND && events[1].new_status == TaskStatus::Processing);
+
+    // Afterwards one listing per status, and only the task missing from its listing is fetched again.
+    respond_concise(&mock, &[recorded_task(FIRST, TaskStatus::Pending)]);
+    respond_concise(&mock, &[]);
+    respond_tasks(&mock, vec![recorded_task(SECOND, TaskStatus::Done)]);
+    let events: Vec<_> = monitor.poll().await.into_iter().map(|e| e.expect("Should poll")).collect();
+    assert_eq!(events.len(), 1);
+    assert!(events[0].id == SECOND);
+    assert!(events[0].old_status == Some(TaskStatus::Processing) && events[0].new_status == TaskStatus::Done);
+    assert!(events[0].task.status == TaskStatus::Done);
+
+    assert_eq!(mock.remaining(), 0);
+    assert_eq!(count(&mock, &TaskEndpoint::Tasks), 3);
+    assert_eq!(count(&mock, &TaskEndpoint::ConciseTasks), 2);
+    assert_eq!(monitor.watched(), vec![FIRST]);
+
+    monitor.unwatch(FIRST);
+    assert!(monitor.poll().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_monitor_reports_errors_and_continues() {
+    let mock = Arc::new(MockTransport::new());
+    let monitor = TaskMonitor::new(
+        ZkWasmServiceHelper::with_transport(mock.clone()),
+        MonitorOptions::default().interval(Duration::from_millis(1)),
+    );
+    monitor.watch(FIRST);
+
+    respond_tasks(&mock, vec![]);
+    mock.respond_error_to(&TaskEndpoint::Tasks, StatusCode::BAD_REQUEST, "Invalid id");
+    respond_tasks(&mock, vec![recorded_task(FIRST, TaskStatus::Pending)]);
+    respond_concise(&mock, &[]);
+    respond_tasks(&mock, vec![recorded_task(FIRST, TaskStatus::Fail)]);
+
+    // A task which is not found yet is fetched again, and a failed request does not end the stream.
+    let events: Vec<_> = monitor.events().take(3).collect().await;
+    assert!(events[0].as_ref().err().is_some_and(|e| e.to_string().contains("Invalid id")));
+    assert!(events[1].as_ref().is_ok_and(|e| e.new_status == TaskStatus::Pending));
+    assert!(events[2]
+        .as_ref()
+        .is_ok_and(|e| e.old_status == Some(TaskStatus::Pending) && e.new_status == TaskStatus::Fail));
+    assert!(monitor.watched().is_empty());
+    assert_eq!(mock.remaining(), 0);
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_monitor_mock_tasks() {
+    use crate::interface::CustomContext;
+    use crate::interface::ProofSubmitMode;
+
+    let server =
+        crate::mock::MockServer::start(crate::mock::MockConfig::default().task_duration(Duration::from_millis(200)))
+            .await
+            .expect("Should start mock server");
+    let helper = server.helper();
+    let monitor = TaskMonitor::new(helper.clone(), MonitorOptions::default().interval(Duration::from_millis(50)));
+    let mut ids = Vec::new();
+    for input in ["0x1:i64", "0x2:i64"] {
+        let res = helper
+            .add_prove(
+                server.fixtures().user_address.clone(),
+                server.fixtures().image_md5.clone(),
+                vec![input.to_string()],
+                vec![],
+                ProofSubmitMode::Manual,
+                CustomContext::Without,
+                super::util::signer(&server.fixtures().private_key),
+            )
+            .await
+            .expect("Should add prove task");
+        monitor.watch(res.id.clone());
+        ids.push(res.id);
+    }
+
+    let mut events = std::pin::pin!(monitor.events());
+    let mut done = Vec::new();
+    while done.len() < ids.len() {
+        let event = tokio::time::timeout(Duration::from_secs(10), events.next())
+            .await
+            .expect("Should finish the tasks in time")
+            .expect("Should keep streaming")
+            .expect("Should poll tasks");
+        if event.new_status == TaskStatus::Done {
+            done.push(event.id);
+        }
+    }
+    done.sort();
+    ids.sort();
+    assert_eq!(done, ids);
+    assert!(monitor.watched().is_empty());
+}
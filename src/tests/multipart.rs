@@ -0,0 +1,196 @@
+use serde::Serialize;
+
+use crate::helper::util::IntoMultipartForm;
+use crate::helper::MultipartField;
+use crate::helper::MultipartValue;
+use crate::helper::SerializationAttributes;
+use crate::interface::AddImageParams;
+use crate::interface::AddProveTaskRestrictions;
+use crate::interface::BaseAddImageParams;
+use crate::interface::InitialContext;
+use crate::interface::ProvePaymentSrc;
+use crate::interface::WithInitialContext;
+
+fn text_fields(fields: &[MultipartField]) -> Vec<(&str, String)> {
+    fields
+        .iter()
+        .map(|f| match &f.value {
+            MultipartValue::Text(v) => (f.name.as_str(), v.clone()),
+            MultipartValue::Bytes(v) => (f.name.as_str(), format!("{v:?}")),
+            MultipartValue::Stream(v) => (f.name.as_str(), format!("{v:?}")),
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct Window {
+    from: u64,
+    to: u64,
+}
+
+#[derive(Serialize)]
+enum Filter {
+    Md5(String),
+}
+
+#[derive(Serialize, SerializationAttributes)]
+struct NestedParams {
+    name: String,
+    window: Window,
+    filters: Vec<Filter>,
+    ratio: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+#[test]
+fn test_nested_values_are_sent_as_json_text() {
+    let params = NestedParams {
+        name: "stats".to_string(),
+        window: Window { from: 1, to: 2 },
+        filters: vec![Filter::Md5("abc".to_string())],
+        ratio: 0.5,
+        note: None,
+    };
+    let fields = IntoMultipartForm::into_multipart_fields(params).expect("Should encode nested values");
+    assert_eq!(
+        text_fields(&fields),
+        vec![
+            ("name", "stats".to_string()),
+            ("window", r#"{"from":1,"to":2}"#.to_string()),
+            ("filters", r#"{"Md5":"abc"}"#.to_string()),
+            ("ratio", "0.5".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_byte_fields_are_moved_into_the_form() {
+    let image = vec![0, 97, 115, 109];
+    let image_ptr = image.as_ptr();
+    let params = AddImageParams {
+        base: BaseAddImageParams {
+            name: "image".to_string(),
+            image_md5: "MD5".to_string(),
+            image,
+            user_address: "0x1".to_string(),
+            description_url: String::new(),
+            avator_url: String::new(),
+            circuit_size: 22,
+            prove_payment_src: ProvePaymentSrc::Default,
+            auto_submit_network_ids: vec![11_155_111],
+            add_prove_task_restrictions: Some(AddProveTaskRestrictions::Anyone),
+            inherited_merkle_data_md5: None,
+        },
+        context: InitialContext::With(WithInitialContext {
+            initial_context_md5: "CTX".to_string(),
+            initial_context: vec![7],
+        }),
+    };
+    let fields = IntoMultipartForm::into_multipart_fields(params).expect("Should encode image params");
+
+    let names: Vec<_> = fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "name",
+            "image_md5",
+            "image",
+            "user_address",
+            "description_url",
+            "avator_url",
+            "circuit_size",
+            "prove_payment_src",
+            "auto_submit_network_ids",
+            "add_prove_task_restrictions",
+            "initial_context_md5",
+            "initial_context",
+        ]
+    );
+    let MultipartValue::Bytes(bytes) = &fields[2].value else {
+        unreachable!("Expected image to be a binary field");
+    };
+    assert_eq!(bytes.as_ref(), &[0, 97, 115, 109]);
+    assert_eq!(bytes.as_ptr(), image_ptr);
+}
+
+#[derive(Serialize, SerializationAttributes)]
+struct OptionalUpload {
+    #[zkp(bytes)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Vec<u8>>,
+    name: String,
+}
+
+#[test]
+fn test_taken_optional_bytes_are_sent() {
+    let params = OptionalUpload {
+        data: Some(vec![1, 2]),
+        name: "upload".to_string(),
+    };
+    let fields = IntoMultipartForm::into_multipart_fields(params).expect("Should encode optional bytes");
+    assert_eq!(
+        text_fields(&fields),
+        vec![("name", "upload".to_string()), ("data", "b\"\\x01\\x02\"".to_string())]
+    );
+
+    let params = OptionalUpload {
+        data: None,
+        name: "upload".to_string(),
+    };
+    let fields = IntoMultipartForm::into_multipart_fields(params).expect("Should encode missing bytes");
+    assert_eq!(text_fields(&fields), vec![("name", "upload".to_string())]);
+}
+
+#[derive(Serialize)]
+struct ManualUpload {
+    image: Vec<u8>,
+}
+
+impl SerializationAttributes for ManualUpload {
+    fn fields_which_are_bytes() -> Vec<String> {
+        vec!["image".to_string()]
+    }
+}
+
+#[test]
+fn test_untaken_byte_fields_are_collected() {
+    let fields = IntoMultipartForm::into_multipart_fields(ManualUpload { image: vec![1, 2, 3] })
+        .expect("Should collect the bytes of the image");
+    let [MultipartField {
+        name,
+        value: MultipartValue::Bytes(bytes),
+    }] = fields.as_slice()
+    else {
+        unreachable!("Expected a single binary field");
+    };
+    assert_eq!(name, "image");
+    assert_eq!(bytes.as_ref(), &[1, 2, 3]);
+}
+
+#[derive(Serialize)]
+struct ManualNumbers {
+    image: Vec<u32>,
+}
+
+impl SerializationAttributes for ManualNumbers {
+    fn fields_which_are_bytes() -> Vec<String> {
+        vec!["image".to_string()]
+    }
+}
+
+#[derive(Serialize)]
+struct Inputs(Vec<String>);
+
+impl SerializationAttributes for Inputs {}
+
+#[test]
+fn test_invalid_inputs_are_rejected() {
+    let err = IntoMultipartForm::into_multipart_fields(ManualNumbers { image: vec![256] })
+        .expect_err("Should reject values which are not bytes");
+    assert!(err.to_string().contains("u8"), "{err}");
+
+    let err = IntoMultipartForm::into_multipart_fields(Inputs(vec!["1:i64".to_string()]))
+        .expect_err("Should reject top level sequences");
+    assert!(err.to_string().contains("Top level"), "{err}");
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures::TryStreamExt;
+
+use super::*;
+use crate::helper::MockTransport;
+use crate::helper::Paginator;
+use crate::helper::ServiceError;
+use crate::helper::TaskEndpoint;
+use crate::interface::ArchiveVolumeMetadata;
+use crate::interface::PaginationResult;
+use crate::interface::TaskQuery;
+
+fn volume(id: u64) -> ArchiveVolumeMetadata {
+    ArchiveVolumeMetadata {
+        version: "1".to_string(),
+        volume_name: format!("vol_{id}"),
+        original_coll_name: "tasks".to_string(),
+        range: None,
+        _id: crate::interface::ObjectId { oid: id.to_string() },
+        prev_last_ts: String::new(),
+        image_md5s: None,
+    }
+}
+
+fn page(ids: std::ops::Range<u64>, total: u64) -> PaginationResult<Vec<ArchiveVolumeMetadata>> {
+    PaginationResult {
+        data: ids.map(volume).collect(),
+        total,
+    }
+}
+
+fn names(volumes: &[ArchiveVolumeMetadata]) -> Vec<String> {
+    volumes.iter().map(|v| v.volume_name.clone()).collect()
+}
+
+fn queries(mock: &MockTransport) -> Vec<String> {
+    mock.requests().into_iter().map(|r| r.query).collect()
+}
+
+#[tokio::test]
+async fn test_stream_fetches_pages_lazily() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(0..2, 5))
+        .respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(2..4, 5))
+        .respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(4..5, 5));
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let mut stream = Box::pin(zkh.stream_archive_task_volume_list(Paginator::default().page_size(2)));
+    let first = stream.next().await.expect("Should have an item").expect("Should fetch");
+    assert_eq!(first.volume_name, "vol_0");
+    assert_eq!(mock.requests().len(), 1);
+
+    let rest: Vec<_> = stream.try_collect().await.expect("Should fetch all pages");
+    assert_eq!(names(&rest), ["vol_1", "vol_2", "vol_3", "vol_4"]);
+    assert_eq!(queries(&mock), ["start=0&limit=2", "start=2&limit=2", "start=4&limit=2"]);
+}
+
+#[tokio::test]
+async fn test_stream_stops_at_max_items() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(0..2, 10))
+        .respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(2..3, 10));
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let volumes: Vec<_> = zkh
+        .stream_archive_task_volume_list(Paginator::default().page_size(2).max_items(3))
+        .try_collect()
+        .await
+        .expect("Should fetch pages");
+    assert_eq!(names(&volumes), ["vol_0", "vol_1", "vol_2"]);
+    assert_eq!(queries(&mock), ["start=0&limit=2", "start=2&limit=1"]);
+}
+
+#[tokio::test]
+async fn test_stream_follows_changing_total() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(0..2, 3))
+        .respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(2..4, 6))
+        .respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(4..5, 5));
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let volumes: Vec<_> = zkh
+        .stream_archive_task_volume_list(Paginator::default().page_size(2))
+        .try_collect()
+        .await
+        .expect("Should fetch pages");
+    assert_eq!(names(&volumes), ["vol_0", "vol_1", "vol_2", "vol_3", "vol_4"]);
+    assert_eq!(mock.remaining(), 0);
+}
+
+#[tokio::test]
+async fn test_stream_ends_on_empty_page() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(0..2, 10))
+        .respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(0..0, 10));
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let volumes: Vec<_> = zkh
+        .stream_archive_task_volume_list(Paginator::default().page_size(2))
+        .try_collect()
+        .await
+        .expect("Should fetch pages");
+    assert_eq!(volumes.len(), 2);
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_stream_ends_after_error() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::ArchiveTaskVolumeList, &page(0..2, 10))
+        .respond_error_to(
+            &TaskEndpoint::ArchiveTaskVolumeList,
+            reqwest::StatusCode::FORBIDDEN,
+            "Not allowed",
+        );
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let items: Vec<_> = zkh
+        .stream_archive_task_volume_list(Paginator::default().page_size(2))
+        .collect()
+        .await;
+    assert_eq!(items.len(), 3);
+    assert!(matches!(items[2], Err(ServiceError::ServerRejected { .. })));
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_stream_tasks_passes_filters() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(
+        &TaskEndpoint::Tasks,
+        &PaginationResult::<Vec<crate::interface::Task>> {
+            data: vec![],
+            total: 0,
+        },
+    );
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let tasks: Vec<_> = zkh
+        .stream_tasks(TaskQuery::new().user("0xabc"), Paginator::default())
+        .try_collect()
+        .await
+        .expect("Should fetch tasks");
+    assert!(tasks.is_empty());
+    assert_eq!(queries(&mock), ["user_address=0xabc&start=0&total=50"]);
+}
+
+#[tokio::test]
+async fn test_stream_items_which_are_not_serializable() {
+    struct Row(u64);
+
+    let items: Vec<_> = Paginator::default()
+        .page_size(2)
+        .stream(|start, limit| async move {
+            Ok(PaginationResult {
+                data: (start..(start + limit).min(3)).map(Row).collect(),
+                total: 3,
+            })
+        })
+        .try_collect()
+        .await
+        .expect("Should stream pages");
+    assert_eq!(items.iter().map(|row| row.0).collect::<Vec<_>>(), [0, 1, 2]);
+}
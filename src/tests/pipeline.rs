@@ -0,0 +1,331 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::util::added;
+use super::util::fast_watch;
+use super::util::recorded_image;
+use super::util::recorded_task;
+use super::util::respond_tasks;
+use super::util::signer;
+use super::CONFIG;
+use super::ZKH;
+use crate::helper::ImagePipeline;
+use crate::helper::ImageSetupRequest;
+use crate::helper::MockTransport;
+use crate::helper::PipelineFailure;
+use crate::helper::PipelineStep;
+use crate::helper::ProveRequest;
+use crate::helper::TaskEndpoint;
+use crate::helper::WatchOptions;
+use crate::helper::ZkWasmServiceHelper;
+use crate::interface::Task;
+use crate::interface::TaskStatus;
+use crate::interface::TaskType;
+
+const PRIVATE_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+const MD5: &str = "5240DD2F4E1A2B3C4D5E6F708192A3B4";
+const TASK_ID: &str = "690000000000000000000001";
+const SETUP_TASK_ID: &str = "690000000000000000000002";
+const IMAGE: &[u8] = b"\0asm\x01\0\0\0";
+
+#[tokio::test]
+async fn test_pipeline_stops_at_failed_task() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Prove, &added(MD5, TASK_ID));
+    let failed = Task {
+        status_message: Some("out of gas".to_string()),
+        ..recorded_task(TASK_ID, TaskStatus::Fail)
+    };
+    respond_tasks(&mock, vec![failed]);
+
+    let signer = signer(PRIVATE_KEY);
+    let session = ZkWasmServiceHelper::with_transport(mock.clone())
+        .with_signer(signer)
+        .await
+        .expect("Should create session");
+    let err = ImagePipeline::new()
+        .md5(MD5)
+        .prove(ProveRequest::builder().public_input("0x1:i64"))
+        .prove(ProveRequest::builder().public_input("0x2:i64"))
+        .modify("https://example.com", "")
+        .watch_options(fast_watch())
+        .run(&session)
+        .await
+        .err()
+        .expect("Should stop at the failed task");
+
+    assert_eq!((err.step, err.kind), (0, PipelineStep::Prove));
+    assert!(err.completed.is_empty());
+    assert!(matches!(err.failure, PipelineFailure::TaskFailed { ref task_id, .. } if task_id == TASK_ID));
+    assert_eq!(
+        err.to_string(),
+        format!("pipeline step 0 (prove) failed: task {TASK_ID} ended with status Fail: out of gas")
+    );
+    assert_eq!(mock.remaining(), 0);
+    assert_eq!(mock.requests().len(), 2);
+}
+
+fn setup_task(status: TaskStatus) -> Task {
+    Task {
+        task_type: TaskType::Setup,
+        ..recorded_task(SETUP_TASK_ID, status)
+    }
+}
+
+fn setup_existing_image() -> ImagePipeline {
+    ImagePipeline::new()
+        .setup(
+            ImageSetupRequest::builder()
+                .name("image")
+                .image(IMAGE.to_vec())
+                .circuit_size(22),
+        )
+        .prove(ProveRequest::builder())
+        .watch_options(fast_watch())
+}
+
+#[tokio::test]
+async fn test_pipeline_waits_for_setup_task_of_existing_image() {
+    let md5 = format!("{:X}", md5::compute(IMAGE));
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Image, &vec![recorded_image(&md5)]);
+    respond_tasks(&mock, vec![setup_task(TaskStatus::Pending)]);
+    respond_tasks(&mock, vec![setup_task(TaskStatus::Processing)]);
+    respond_tasks(&mock, vec![setup_task(TaskStatus::Done)]);
+    mock.respond_ok_to(&TaskEndpoint::Prove, &added(&md5, TASK_ID));
+    respond_tasks(&mock, vec![recorded_task(TASK_ID, TaskStatus::Done)]);
+
+    let signer = signer(PRIVATE_KEY);
+    let session = ZkWasmServiceHelper::with_transport(mock.clone())
+        .with_signer(signer)
+        .await
+        .expect("Should create session");
+    let report = setup_existing_image().run(&session).await.expect("Should run the pipeline");
+
+    let ids: Vec<_> = report
+        .steps
+        .iter()
+        .map(|step| step.task.as_ref().map(|task| task._id.oid.as_str()))
+        .collect();
+    assert_eq!(ids, [Some(SETUP_TASK_ID), Some(TASK_ID)]);
+    assert_eq!(mock.remaining(), 0);
+    let requests = mock.requests();
+    assert!(requests.iter().all(|r| r.endpoint != TaskEndpoint::Setup));
+    assert!(requests[1].query.contains("tasktype=Setup") && requests[1].query.contains(&md5));
+}
+
+#[tokio::test]
+async fn test_pipeline_stops_at_failed_setup_task_of_existing_image() {
+    let md5 = format!("{:X}", md5::compute(IMAGE));
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Image, &vec![recorded_image(&md5)]);
+    respond_tasks(&mock, vec![setup_task(TaskStatus::Fail)]);
+    respond_tasks(&mock, vec![setup_task(TaskStatus::Fail)]);
+    mock.respond_ok_to(&TaskEndpoint::Image, &vec![recorded_image(&md5)]);
+    respond_tasks(&mock, vec![]);
+
+    let signer = signer(PRIVATE_KEY);
+    let session = ZkWasmServiceHelper::with_transport(mock.clone())
+        .with_signer(signer)
+        .await
+        .expect("Should create session");
+    let err = setup_existing_image()
+        .run(&session)
+        .await
+        .err()
+        .expect("Should stop at the failed setup task");
+    assert_eq!((err.step, err.kind), (0, PipelineStep::Setup));
+    assert!(matches!(err.failure, PipelineFailure::TaskFailed { ref task_id, .. } if task_id == SETUP_TASK_ID));
+
+    let err = setup_existing_image()
+        .run(&session)
+        .await
+        .err()
+        .expect("Should need a setup task");
+    assert!(err.to_string().contains("has no setup task"), "{err}");
+    assert_eq!(mock.remaining(), 0);
+    assert!(mock.requests().iter().all(|r| r.endpoint != TaskEndpoint::Prove));
+}
+
+#[tokio::test]
+async fn test_pipeline_requires_md5() {
+    let mock = Arc::new(MockTransport::new());
+    let signer = signer(PRIVATE_KEY);
+    let session = ZkWasmServiceHelper::with_transport(mock.clone())
+        .with_signer(signer)
+        .await
+        .expect("Should create session");
+
+    let err = ImagePipeline::new()
+        .prove(ProveRequest::builder())
+        .run(&session)
+        .await
+        .err()
+        .expect("Should need an md5");
+    assert!(matches!(err.failure, PipelineFailure::Request(_)));
+    assert!(err.to_string().contains("`md5` is required"), "{err}");
+
+    let err = ImagePipeline::new()
+        .modify("https://example.com", "")
+        .run(&session)
+        .await
+        .err()
+        .expect("Should need an md5");
+    assert!(err.to_string().contains("`md5` is required"), "{err}");
+    assert!(mock.requests().is_empty());
+}
+
+/// The configured test image with a custom section appended: a valid module with another md5 than the image which the
+/// task tests set up.
+async fn pipeline_image() -> Vec<u8> {
+    let mut image = tokio::fs::read(&CONFIG.tasks.image).await.expect("Should read image");
+    // Custom section (id 0) of 9 bytes: the length of its name and the name.
+    image.extend_from_slice(b"\0\x09\x08pipeline");
+    image
+}
+
+#[tokio::test]
+async fn test_pipeline_runs_image_operations() {
+    use crate::helper::ResetRequest;
+    use crate::helper::StepState;
+
+    let image = pipeline_image().await;
+    let md5 = format!("{:X}", md5::compute(&image));
+    let session = ZKH
+        .with_signer(CONFIG.signer())
+        .await
+        .expect("Should be able to create session");
+
+    let report = ImagePipeline::new()
+        .setup(
+            ImageSetupRequest::builder()
+                .name(md5.clone())
+                .image(image)
+                .description_url(format!("ZKP CLI pipeline test image {md5}"))
+                .circuit_size(22)
+                .auto_submit_network_ids(vec![CONFIG.details.chain_id]),
+        )
+        .prove(ProveRequest::builder())
+        .reset(
+            ResetRequest::builder()
+                .circuit_size(22)
+                .auto_submit_network_ids(vec![CONFIG.details.chain_id]),
+        )
+        .modify(format!("ZKP CLI pipeline test image {md5} -- modified"), String::new())
+        .watch_options(WatchOptions::default().initial_interval(Duration::from_secs(1)))
+        .on_progress(|p| {
+            if let StepState::Started = p.state {
+                println!("Running {} ...", p.kind);
+            }
+        })
+        .run(&session)
+        .await
+        .expect("Should be able to run image operations");
+
+    assert_eq!(report.md5, md5);
+    let kinds: Vec<_> = report.steps.iter().map(|step| step.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            PipelineStep::Setup,
+            PipelineStep::Prove,
+            PipelineStep::Reset,
+            PipelineStep::Modify
+        ]
+    );
+    assert!(report.steps[..3]
+        .iter()
+        .all(|step| step.task.as_ref().is_some_and(|task| task.status == TaskStatus::Done)));
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_pipeline_runs_image_lifecycle() {
+    use std::sync::Mutex;
+
+    use crate::helper::ResetRequest;
+    use crate::helper::StepState;
+
+    let server =
+        crate::mock::MockServer::start(crate::mock::MockConfig::default().task_duration(Duration::from_millis(100)))
+            .await
+            .expect("Should start mock server");
+    let session = server
+        .helper()
+        .with_signer(signer(&server.fixtures().private_key))
+        .await
+        .expect("Should create session");
+    let image = b"\0asm pipeline image".to_vec();
+    let md5 = format!("{:X}", md5::compute(&image));
+    let setup = || {
+        ImageSetupRequest::builder()
+            .name("pipeline image")
+            .image(image.clone())
+            .circuit_size(22)
+            .auto_submit_network_ids(vec![server.fixtures().chain_id])
+    };
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let recorded = progress.clone();
+    let report = ImagePipeline::new()
+        .setup(setup())
+        .prove_all(["0x1:i64", "0x2:i64"].map(|input| ProveRequest::builder().public_input(input)))
+        .reset(ResetRequest::builder().circuit_size(22))
+        .modify("https://example.com/pipeline", "")
+        .watch_options(WatchOptions::default().initial_interval(Duration::from_millis(20)))
+        .on_progress(move |p| {
+            let state = match &p.state {
+                StepState::Started => "started",
+                StepState::Skipped => "skipped",
+                StepState::Submitted { .. } => "submitted",
+                StepState::StatusChanged { .. } => "status",
+                StepState::Finished => "finished",
+            };
+            recorded.lock().expect("Should lock progress").push((p.step, state));
+        })
+        .run(&session)
+        .await
+        .expect("Should run the pipeline");
+
+    assert_eq!(report.md5, md5);
+    let kinds: Vec<_> = report.steps.iter().map(|step| step.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            PipelineStep::Setup,
+            PipelineStep::Prove,
+            PipelineStep::Prove,
+            PipelineStep::Reset,
+            PipelineStep::Modify
+        ]
+    );
+    assert!(report.steps[..4].iter().all(|step| step
+        .task
+        .as_ref()
+        .is_some_and(|task| task.status == TaskStatus::Done && task.md5 == md5)));
+    assert!(report.steps[4].task.is_none());
+
+    let progress = progress.lock().expect("Should lock progress").clone();
+    for step in 0..4 {
+        let states: Vec<_> = progress.iter().filter(|(s, _)| *s == step).map(|(_, state)| *state).collect();
+        assert_eq!(states.first(), Some(&"started"));
+        assert_eq!(states.get(1), Some(&"submitted"));
+        assert_eq!(states.last(), Some(&"finished"));
+        assert!(states.contains(&"status"));
+    }
+    assert_eq!(progress[progress.len() - 2..], [(4, "started"), (4, "finished")]);
+
+    // The image exists now, so running the setup again only waits for its Setup task.
+    let report = ImagePipeline::new()
+        .setup(setup())
+        .prove(ProveRequest::builder().public_input("0x3:i64"))
+        .watch_options(WatchOptions::default().initial_interval(Duration::from_millis(20)))
+        .run(&session)
+        .await
+        .expect("Should run the pipeline again");
+    assert!(report.steps[0]
+        .task
+        .as_ref()
+        .is_some_and(|task| task.task_type == TaskType::Setup && task.md5 == md5));
+    assert!(report.steps[1].task.as_ref().is_some_and(|task| task.md5 == md5));
+}
@@ -110,7 +110,7 @@ async fn test_query_logs() {
         ZkWasmServiceHelper::query_logs,
         CONFIG.query.task_id.clone(),
         CONFIG.user_address(),
-        CONFIG.details.private_key.clone()
+        CONFIG.signer()
     );
     assert!(!res.is_empty());
 }
@@ -254,10 +254,13 @@ mod task {
     #[tokio::test]
     async fn test_query_tasks_from_ids() {
         let ids = vec![CONFIG.query.task_id.clone(); 10];
-        let res = run_test!(ZkWasmServiceHelper::query_tasks_from_ids, ids.clone());
-        assert!(!res.is_empty());
+        let res = ZKH.query_tasks_from_ids(ids.clone()).await;
         assert_eq!(res.len(), ids.len());
-        assert_eq!(ids[0], res[0]._id.oid);
+        for (id, task) in res {
+            let task = util::check_and_print(task).expect("Task should exist");
+            assert_eq!(ids[0], id);
+            assert_eq!(ids[0], task._id.oid);
+        }
     }
 
     #[tokio::test]
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use super::*;
+use crate::helper::MockTransport;
+use crate::helper::TaskEndpoint;
+use crate::interface::ArchiveTaskQuery;
+use crate::interface::AutoSubmitProofStatus;
+use crate::interface::AutoSubmitQuery;
+use crate::interface::PaginationResult;
+use crate::interface::Round1Query;
+use crate::interface::Round1Status;
+use crate::interface::Round2Query;
+use crate::interface::TaskQuery;
+use crate::interface::TaskStatus;
+use crate::interface::TaskType;
+
+fn empty_page() -> PaginationResult<Vec<()>> {
+    PaginationResult {
+        data: vec![],
+        total: 0,
+    }
+}
+
+fn last_query(mock: &MockTransport) -> String {
+    mock.requests().pop().expect("Should have sent a request").query
+}
+
+#[tokio::test]
+async fn test_task_query_matches_positional_arguments() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Tasks, &empty_page())
+        .respond_ok_to(&TaskEndpoint::Tasks, &empty_page())
+        .respond_ok_to(&TaskEndpoint::ConciseTasks, &empty_page());
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let query = TaskQuery::new()
+        .user("0xabc")
+        .image("5240DD2F")
+        .kind(TaskType::Prove)
+        .status(TaskStatus::Done)
+        .page(10, 5);
+    zkh.query_tasks_with(query.clone()).await.expect("Should query tasks");
+    let built = last_query(&mock);
+    assert_eq!(
+        built,
+        "user_address=0xabc&md5=5240DD2F&tasktype=Prove&taskstatus=Done&start=10&total=5"
+    );
+
+    zkh.query_tasks(
+        Some("0xabc".to_string()),
+        Some("5240DD2F".to_string()),
+        None,
+        Some(TaskType::Prove),
+        Some(TaskStatus::Done),
+        Some(10),
+        Some(5),
+    )
+    .await
+    .expect("Should query tasks");
+    assert_eq!(last_query(&mock), built);
+
+    zkh.query_concise_tasks_with(query).await.expect("Should query concise tasks");
+    assert_eq!(last_query(&mock), built);
+}
+
+#[tokio::test]
+async fn test_batch_queries() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Round1Batch, &empty_page())
+        .respond_ok_to(&TaskEndpoint::Round2Batch, &empty_page())
+        .respond_ok_to(&TaskEndpoint::FinalBatch, &empty_page());
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    zkh.query_auto_submit_proofs_with(
+        AutoSubmitQuery::new()
+            .task("t1")
+            .status(AutoSubmitProofStatus::Batched)
+            .chain(97)
+            .page(0, 2),
+    )
+    .await
+    .expect("Should query auto submit proofs");
+    assert_eq!(last_query(&mock), "task_id=t1&status=Batched&chain_id=97&total=2&start=0");
+
+    zkh.query_round1_info_with(
+        Round1Query::new()
+            .auto_submit_proof("p1")
+            .status(Round1Status::Batched)
+            .circuit_size(22),
+    )
+    .await
+    .expect("Should query round 1 info");
+    assert_eq!(last_query(&mock), "round_1_id=p1&status=Batched&circuit_size=22");
+
+    zkh.query_round2_info_with(Round2Query::new().id("r2").round1_batch("r1"))
+        .await
+        .expect("Should query round 2 info");
+    assert_eq!(last_query(&mock), "id=r2&round_2_id=r1");
+}
+
+#[tokio::test]
+async fn test_archive_task_query() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::ArchiveArchiveQuery, &empty_page());
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    zkh.query_archive_with(
+        ArchiveTaskQuery::new()
+            .image("5240DD2F")
+            .since("2024-07-11T00:00:00Z")
+            .page(0, 20),
+    )
+    .await
+    .expect("Should query archive");
+    assert_eq!(
+        last_query(&mock),
+        "md5=5240DD2F&start_timestamp=2024-07-11T00%3A00%3A00Z&start=0&limit=20"
+    );
+}
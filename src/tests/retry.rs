@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use super::stub::StubResponse;
+use super::stub::StubServer;
+use super::*;
+use crate::helper::RetryPolicy;
+use crate::helper::ServiceError;
+
+fn statistics() -> StubResponse {
+    StubResponse::ok(&serde_json::json!({
+        "total_images": 1,
+        "total_proofs": 2,
+        "total_tasks": 3,
+        "total_deployed": 4,
+    }))
+}
+
+fn helper(url: &str, policy: RetryPolicy) -> ZkWasmServiceHelper {
+    ZkWasmServiceHelper::builder(url.to_string())
+        .retry_policy(policy)
+        .build()
+        .expect("Should build helper")
+}
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy::default()
+        .max_attempts(4)
+        .initial_backoff(Duration::from_millis(5))
+        .jitter(false)
+}
+
+#[tokio::test]
+async fn test_get_retries_transient_failures() {
+    let stub = StubServer::start(vec![
+        StubResponse::text(502, "Bad Gateway"),
+        StubResponse::Drop,
+        StubResponse::text(503, "Service Unavailable"),
+        statistics(),
+    ])
+    .await;
+
+    let res = helper(&stub.url, fast_policy())
+        .query_statistics()
+        .await
+        .expect("Should succeed after retries");
+    assert_eq!(res.total_tasks, 3);
+    assert_eq!(stub.requests().len(), 4);
+}
+
+#[tokio::test]
+async fn test_get_gives_up_after_max_attempts() {
+    let stub = StubServer::start(vec![StubResponse::text(504, "Gateway Timeout")]).await;
+
+    let err = helper(&stub.url, fast_policy().max_attempts(2))
+        .query_statistics()
+        .await
+        .err()
+        .expect("Should fail");
+    assert!(matches!(err, ServiceError::HttpStatus { .. }), "{err:?}");
+    assert_eq!(stub.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_non_transient_errors_are_not_retried() {
+    let stub = StubServer::start(vec![StubResponse::json(
+        400,
+        &serde_json::json!({ "success": false, "error": "Bad request" }),
+    )])
+    .await;
+
+    let err = helper(&stub.url, fast_policy())
+        .query_statistics()
+        .await
+        .err()
+        .expect("Should fail");
+    assert!(matches!(err, ServiceError::ServerRejected { .. }), "{err:?}");
+    assert_eq!(stub.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_retry_after_is_honoured() {
+    let stub = StubServer::start(vec![
+        StubResponse::text(429, "Too Many Requests").with_header("retry-after", "0"),
+        statistics(),
+    ])
+    .await;
+
+    // The backoff alone would wait far longer than the test allows, so finishing quickly means `Retry-After` was used.
+    let policy = fast_policy().initial_backoff(Duration::from_secs(600));
+    let res = tokio::time::timeout(Duration::from_secs(10), helper(&stub.url, policy).query_statistics())
+        .await
+        .expect("Should not wait for the backoff")
+        .expect("Should succeed after retry");
+    assert_eq!(res.total_images, 1);
+    assert_eq!(stub.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_posts_are_only_retried_when_enabled() {
+    let responses = vec![
+        StubResponse::text(502, "Bad Gateway"),
+        StubResponse::ok(&serde_json::json!("modified")),
+    ];
+    let modify = |zkh: ZkWasmServiceHelper| async move {
+        zkh.modify_image(
+            "MD5".to_string(),
+            "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23".to_string(),
+            String::new(),
+            String::new(),
+            super::util::signer("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"),
+        )
+        .await
+    };
+
+    let stub = StubServer::start(responses.clone()).await;
+    let err = modify(helper(&stub.url, fast_policy()))
+        .await
+        .expect_err("Should not retry POST");
+    assert!(matches!(err, ServiceError::HttpStatus { .. }), "{err:?}");
+    assert_eq!(stub.requests().len(), 1);
+
+    let stub = StubServer::start(responses).await;
+    let res = modify(helper(&stub.url, fast_policy().retry_posts(true)))
+        .await
+        .expect("Should retry POST");
+    assert_eq!(res, "modified");
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|r| r.method == "POST"));
+    assert_eq!(requests[0].header("x-eth-signature"), requests[1].header("x-eth-signature"));
+}
+
+#[test]
+fn test_backoff_delays() {
+    let policy = RetryPolicy::default()
+        .initial_backoff(Duration::from_millis(100))
+        .max_backoff(Duration::from_millis(350))
+        .max_retry_after(Duration::from_secs(5))
+        .jitter(false);
+    assert_eq!(policy.delay(1, None), Duration::from_millis(100));
+    assert_eq!(policy.delay(2, None), Duration::from_millis(200));
+    assert_eq!(policy.delay(3, None), Duration::from_millis(350));
+    assert_eq!(policy.delay(1, Some(Duration::from_secs(2))), Duration::from_secs(2));
+    assert_eq!(policy.delay(1, Some(Duration::from_secs(30))), Duration::from_secs(5));
+
+    let jittered = policy.jitter(true).delay(2, None);
+    assert!(jittered >= Duration::from_millis(100) && jittered <= Duration::from_millis(200));
+}
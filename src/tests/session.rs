@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use super::util::added;
+use super::*;
+use crate::helper::MockTransport;
+use crate::helper::MultipartValue;
+use crate::helper::ProveRequest;
+use crate::helper::RequestBody;
+use crate::helper::ServiceError;
+use crate::helper::TaskEndpoint;
+use crate::helper::TransportRequest;
+use crate::interface::AdminRequestType;
+use crate::interface::MaintenanceModeType;
+
+const PRIVATE_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+const ADDRESS: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+const MD5: &str = "5240DD2F4E1A2B3C4D5E6F708192A3B4";
+
+fn field(request: &TransportRequest, name: &str) -> Option<String> {
+    let RequestBody::Multipart(fields) = &request.body else {
+        return None;
+    };
+    fields.iter().find(|f| f.name == name).and_then(|f| match &f.value {
+        MultipartValue::Text(v) => Some(v.clone()),
+        MultipartValue::Bytes(_) | MultipartValue::Stream(_) => None,
+    })
+}
+
+#[tokio::test]
+async fn test_session_derives_user_address() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Prove, &added(MD5, "task"))
+        .respond_ok_to(&TaskEndpoint::Prove, &added(MD5, "task"));
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+    let signer = super::util::signer(PRIVATE_KEY);
+    let session = zkh.with_signer(signer).await.expect("Should create session");
+    assert_eq!(session.user_address(), ADDRESS);
+
+    session
+        .add_prove(ProveRequest::builder().md5(MD5).public_input("0x2a:i64"))
+        .await
+        .expect("Should add prove task");
+    session
+        .add_prove(
+            ProveRequest::builder()
+                .md5(MD5)
+                .user_address(ADDRESS.to_uppercase().replace("0X", "0x")),
+        )
+        .await
+        .expect("Should accept the signer's address in any case");
+
+    let requests = mock.requests();
+    assert_eq!(field(&requests[0], "user_address").as_deref(), Some(ADDRESS));
+    assert!(requests[0].signature.is_some());
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn test_session_signs_deploy_and_maintenance_mode() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Deploy, &())
+        .respond_ok_to(&TaskEndpoint::SetMaintenanceMode, &"Maintenance mode enabled");
+    let session = ZkWasmServiceHelper::with_transport(mock.clone())
+        .with_signer(super::util::signer(PRIVATE_KEY))
+        .await
+        .expect("Should create session");
+
+    session
+        .add_deploy(MD5.to_string(), 11_155_111)
+        .await
+        .expect("Should deploy image");
+    session
+        .set_maintenance_mode(MaintenanceModeType::Enabled, 0, AdminRequestType::MaintenanceMode)
+        .await
+        .expect("Should set maintenance mode");
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    for request in &requests {
+        assert_eq!(field(request, "user_address").as_deref(), Some(ADDRESS));
+        assert!(request.signature.is_some());
+    }
+    assert_eq!(field(&requests[0], "chain_id").as_deref(), Some("11155111"));
+}
+
+#[tokio::test]
+async fn test_session_refuses_other_address() {
+    let mock = Arc::new(MockTransport::new());
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+    let session = zkh
+        .with_signer(super::util::signer(PRIVATE_KEY))
+        .await
+        .expect("Should create session");
+
+    let other = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+    let err = session
+        .add_prove(ProveRequest::builder().md5(MD5).user_address(other))
+        .await
+        .err()
+        .expect("Should refuse to sign");
+    assert!(
+        matches!(&err, ServiceError::Signing(_)) && err.to_string().contains(other),
+        "{err:?}"
+    );
+    assert!(mock.requests().is_empty());
+}
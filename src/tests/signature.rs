@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use super::util::added;
+use super::*;
+use crate::helper::canonical_message;
+use crate::helper::verify_signed_request;
+use crate::helper::MockTransport;
+use crate::helper::ServiceError;
+use crate::helper::TaskEndpoint;
+use crate::interface::BaseProvingParams;
+use crate::interface::CustomContext;
+use crate::interface::InputContextType;
+use crate::interface::ProofSubmitMode;
+use crate::interface::ProvingParams;
+use crate::interface::WithCustomInputContext;
+
+const PRIVATE_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+const ADDRESS: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+const MD5: &str = "5240DD2F4E1A2B3C4D5E6F708192A3B4";
+
+fn proving_params(user_address: &str) -> ProvingParams {
+    ProvingParams {
+        base: BaseProvingParams {
+            user_address: user_address.to_string(),
+            md5: MD5.to_string(),
+            public_inputs: vec!["0x2a:i64".to_string()],
+            private_inputs: vec!["0x01:bytes".to_string()],
+            proof_submit_mode: ProofSubmitMode::Manual,
+        },
+        context: CustomContext::With(WithCustomInputContext {
+            input_context_type: InputContextType::Custom,
+            input_context: vec![7, 7],
+            input_context_md5: "CONTEXTMD5".to_string(),
+        }),
+    }
+}
+
+#[test]
+fn test_canonical_message_skips_ignored_fields() {
+    let message = canonical_message(&proving_params(ADDRESS)).expect("Should build message");
+    assert_eq!(
+        message,
+        format!("{ADDRESS}{MD5}0x2a:i640x01:bytesManualCustomCONTEXTMD5"),
+        "input_context should be ignored and fields kept in declaration order"
+    );
+}
+
+#[tokio::test]
+async fn test_verify_captured_request() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Prove, &added(MD5, "task"));
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+    let params = proving_params(ADDRESS);
+    zkh.add_prove(
+        params.base.user_address.clone(),
+        params.base.md5.clone(),
+        params.base.public_inputs.clone(),
+        params.base.private_inputs.clone(),
+        ProofSubmitMode::Manual,
+        params.context.clone(),
+        super::util::signer(PRIVATE_KEY),
+    )
+    .await
+    .expect("Should add prove task");
+
+    let signature = mock.requests()[0].signature.clone().expect("Should be signed");
+    let signer = verify_signed_request(&params, &signature).expect("Should recover signer");
+    assert_eq!(format!("{signer:#x}"), ADDRESS);
+
+    let mut tampered = params.clone();
+    tampered.base.public_inputs.push("0x1:i64".to_string());
+    let signer = verify_signed_request(&tampered, &signature).expect("Should recover some signer");
+    assert_ne!(format!("{signer:#x}"), ADDRESS);
+
+    let err = verify_signed_request(&params, "0x1234").expect_err("Should reject malformed signature");
+    assert!(matches!(err, ServiceError::Signing(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_signature_checked_before_sending() {
+    let mock = Arc::new(MockTransport::new());
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+    let other = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+    let signer = super::util::signer(PRIVATE_KEY);
+    let params = proving_params(other);
+    let err = zkh
+        .add_prove(
+            params.base.user_address,
+            params.base.md5,
+            params.base.public_inputs,
+            params.base.private_inputs,
+            ProofSubmitMode::Manual,
+            params.context,
+            &signer,
+        )
+        .await
+        .err()
+        .expect("Should not sign for another address");
+    assert!(
+        matches!(&err, ServiceError::Signing(_)) && err.to_string().contains(other),
+        "{err:?}"
+    );
+    assert!(mock.requests().is_empty());
+}
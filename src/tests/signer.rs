@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use ethers::signers::LocalWallet;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+
+use super::util::added;
+use super::*;
+use crate::helper::ExternalSigner;
+use crate::helper::MockTransport;
+use crate::helper::RequestSigner;
+use crate::helper::ServiceError;
+use crate::helper::SignerRequest;
+use crate::helper::SignerResponse;
+use crate::helper::TaskEndpoint;
+use crate::helper::WalletSigner;
+use crate::interface::AddTaskResult;
+use crate::interface::CustomContext;
+use crate::interface::ProofSubmitMode;
+
+/// The first account of the well known development mnemonic.
+const MNEMONIC: &str = "test test test test test test test test test test test junk";
+const DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+const PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("zkp-service-helper-{}-{name}", std::process::id()))
+}
+
+async fn add_prove(zkh: &ZkWasmServiceHelper, signer: impl RequestSigner) -> crate::helper::Result<AddTaskResult> {
+    zkh.add_prove(
+        "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".to_string(),
+        "5240DD2F4E1A2B3C4D5E6F708192A3B4".to_string(),
+        vec!["0x2a:i64".to_string()],
+        vec![],
+        ProofSubmitMode::Manual,
+        CustomContext::Without,
+        signer,
+    )
+    .await
+}
+
+/// Answers [`SignerRequest`]s on a Unix socket with `signer`, standing in for a separate signing process.
+fn serve_signer(path: &std::path::Path, signer: WalletSigner) {
+    let _ = std::fs::remove_file(path);
+    let listener = tokio::net::UnixListener::bind(path).expect("Should bind signer socket");
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let (read, mut write) = stream.into_split();
+            let mut line = String::new();
+            tokio::io::BufReader::new(read)
+                .read_line(&mut line)
+                .await
+                .expect("Should read request");
+            let response = match serde_json::from_str(&line).expect("Should decode request") {
+                SignerRequest::Address => SignerResponse::Result(format!("{:#x}", signer.wallet_address())),
+                SignerRequest::SignMessage { message } => {
+                    let message = ethers::utils::hex::decode(message).expect("Should decode message");
+                    let signature = signer.sign_message(&message).await.expect("Should sign");
+                    SignerResponse::Result(format!("0x{signature}"))
+                }
+            };
+            let mut reply = serde_json::to_string(&response).expect("Should encode response");
+            reply.push('\n');
+            write.write_all(reply.as_bytes()).await.expect("Should write response");
+        }
+    });
+}
+
+#[tokio::test]
+async fn test_wallet_signers_sign_like_private_key() {
+    let from_key = WalletSigner::from_private_key(PRIVATE_KEY).expect("Should parse private key");
+    let from_mnemonic = WalletSigner::from_mnemonic(MNEMONIC, DERIVATION_PATH).expect("Should derive key");
+    assert_eq!(from_key.wallet_address(), from_mnemonic.wallet_address());
+    let wallet: LocalWallet = PRIVATE_KEY.parse().expect("Should parse private key");
+    assert_eq!(ethers::signers::Signer::address(&wallet), from_key.wallet_address());
+
+    let dir = temp_path("keystore");
+    std::fs::create_dir_all(&dir).expect("Should create keystore dir");
+    let key = ethers::utils::hex::decode(PRIVATE_KEY).expect("Should decode private key");
+    LocalWallet::encrypt_keystore(&dir, &mut rand::thread_rng(), key, "password", Some("key.json"))
+        .expect("Should write keystore");
+    let from_keystore = WalletSigner::from_keystore(dir.join("key.json"), "password").expect("Should decrypt keystore");
+    let err = WalletSigner::from_keystore(dir.join("key.json"), "wrong").expect_err("Should reject password");
+    assert!(matches!(err, ServiceError::Signing(_)), "{err:?}");
+    let _ = std::fs::remove_dir_all(dir);
+
+    let mock = Arc::new(MockTransport::new());
+    for _ in 0..3 {
+        mock.respond_ok_to(&TaskEndpoint::Prove, &added("5240DD2F4E1A2B3C4D5E6F708192A3B4", "task"));
+    }
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+    add_prove(&zkh, &from_key).await.expect("Should sign with wallet");
+    add_prove(&zkh, from_mnemonic).await.expect("Should sign with mnemonic");
+    add_prove(&zkh, Arc::new(from_keystore))
+        .await
+        .expect("Should sign with keystore");
+
+    let signatures: Vec<_> = mock.requests().into_iter().map(|r| r.signature).collect();
+    assert!(signatures[0].is_some());
+    assert!(signatures.iter().all(|s| *s == signatures[0]), "{signatures:?}");
+}
+
+#[tokio::test]
+async fn test_external_signer_over_socket() {
+    let path = temp_path("signer.sock");
+    let wallet = WalletSigner::from_private_key(PRIVATE_KEY).expect("Should parse private key");
+    serve_signer(&path, wallet.clone());
+    let external = ExternalSigner::socket(&path);
+    assert_eq!(external.address().await.expect("Should query address"), wallet.wallet_address());
+
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Prove, &added("5240DD2F4E1A2B3C4D5E6F708192A3B4", "task"))
+        .respond_ok_to(&TaskEndpoint::Prove, &added("5240DD2F4E1A2B3C4D5E6F708192A3B4", "task"));
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+    add_prove(&zkh, &external).await.expect("Should sign externally");
+    add_prove(&zkh, &wallet).await.expect("Should sign with wallet");
+    let requests = mock.requests();
+    assert_eq!(requests[0].signature, requests[1].signature);
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn test_external_signer_command() {
+    let reply = |response: &str| {
+        ExternalSigner::command("sh", vec!["-c".to_string(), format!("cat > /dev/null; echo '{response}'")])
+    };
+
+    let address = reply(r#"{"result":"0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"}"#)
+        .address()
+        .await
+        .expect("Should query address");
+    assert_eq!(format!("{address:#x}"), "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+
+    let err = reply(r#"{"error":"denied by user"}"#)
+        .sign_message(b"message")
+        .await
+        .expect_err("Should be refused");
+    assert!(
+        matches!(&err, ServiceError::Signing(_)) && err.to_string().contains("denied by user"),
+        "{err:?}"
+    );
+
+    let failing = ExternalSigner::command(
+        "sh",
+        vec!["-c".to_string(), "cat > /dev/null; echo locked >&2; exit 1".to_string()],
+    );
+    let err = failing.sign_message(b"message").await.expect_err("Should fail");
+    assert!(err.to_string().contains("locked"), "{err:?}");
+}
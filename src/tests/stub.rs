@@ -0,0 +1,201 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+
+/// A request received by the [`StubServer`].
+#[derive(Clone)]
+pub(super) struct StubRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl StubRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A scripted response returned by the [`StubServer`].
+#[derive(Clone)]
+pub(super) enum StubResponse {
+    Reply {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: String,
+    },
+    /// Closes the connection without answering.
+    Drop,
+}
+
+impl StubResponse {
+    pub fn ok(result: &serde_json::Value) -> Self {
+        Self::json(200, &serde_json::json!({ "success": true, "result": result }))
+    }
+
+    pub fn json(status: u16, body: &serde_json::Value) -> Self {
+        Self::Reply {
+            status,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    pub fn text(status: u16, body: &str) -> Self {
+        Self::Reply {
+            status,
+            headers: vec![("content-type".to_string(), "text/html".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    pub fn with_header(self, name: &str, value: &str) -> Self {
+        match self {
+            Self::Reply {
+                status,
+                mut headers,
+                body,
+            } => {
+                headers.push((name.to_string(), value.to_string()));
+                Self::Reply {
+                    status,
+                    headers,
+                    body,
+                }
+            }
+            Self::Drop => Self::Drop,
+        }
+    }
+}
+
+/// Minimal HTTP/1.1 server on localhost which answers requests with a script of responses.
+///
+/// Responses are handed out in order, the last one is repeated once the script runs out. Every request is recorded so
+/// tests can inspect what the client sent.
+pub(super) struct StubServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<StubRequest>>>,
+    connections: Arc<AtomicUsize>,
+}
+
+impl StubServer {
+    pub async fn start(responses: Vec<StubResponse>) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Should bind stub server");
+        let url = format!("http://{}", listener.local_addr().expect("Should have local addr"));
+        let requests = Arc::new(Mutex::new(vec![]));
+        let connections = Arc::new(AtomicUsize::new(0));
+        let script = Arc::new(Mutex::new((responses, 0usize)));
+
+        let (reqs, conns) = (requests.clone(), connections.clone());
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                conns.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(Self::serve(stream, reqs.clone(), script.clone()));
+            }
+        });
+        Self {
+            url,
+            requests,
+            connections,
+        }
+    }
+
+    pub fn requests(&self) -> Vec<StubRequest> {
+        self.requests.lock().expect("Should lock").clone()
+    }
+
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    async fn serve(
+        mut stream: tokio::net::TcpStream,
+        requests: Arc<Mutex<Vec<StubRequest>>>,
+        script: Arc<Mutex<(Vec<StubResponse>, usize)>>,
+    ) {
+        let mut buf = Vec::new();
+        loop {
+            let Some(request) = Self::read_request(&mut stream, &mut buf).await else {
+                return;
+            };
+            requests.lock().expect("Should lock").push(request);
+
+            let response = {
+                let mut script = script.lock().expect("Should lock");
+                let idx = script.1.min(script.0.len() - 1);
+                script.1 += 1;
+                script.0[idx].clone()
+            };
+            let StubResponse::Reply {
+                status,
+                headers,
+                body,
+            } = response
+            else {
+                return;
+            };
+            let mut out = format!("HTTP/1.1 {status} Stub\r\ncontent-length: {}\r\n", body.len());
+            for (k, v) in headers {
+                out.push_str(&format!("{k}: {v}\r\n"));
+            }
+            out.push_str("\r\n");
+            out.push_str(&body);
+            if stream.write_all(out.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn read_request(stream: &mut tokio::net::TcpStream, buf: &mut Vec<u8>) -> Option<StubRequest> {
+        let head_end = loop {
+            if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+            let mut chunk = [0u8; 4096];
+            let n = stream.read(&mut chunk).await.ok()?;
+            if n == 0 {
+                return None;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        };
+
+        let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+        let mut lines = head.split("\r\n");
+        let mut start = lines.next()?.split(' ');
+        let method = start.next()?.to_string();
+        let path = start.next()?.to_string();
+        let headers = lines
+            .filter_map(|l| l.split_once(": "))
+            .map(|(k, v)| (k.to_lowercase(), v.to_string()))
+            .collect::<Vec<_>>();
+        let len = headers
+            .iter()
+            .find(|(k, _)| k == "content-length")
+            .and_then(|(_, v)| v.parse::<usize>().ok())
+            .unwrap_or(0);
+
+        while buf.len() < head_end + len {
+            let mut chunk = [0u8; 4096];
+            let n = stream.read(&mut chunk).await.ok()?;
+            if n == 0 {
+                return None;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        buf.drain(..head_end + len);
+        Some(StubRequest {
+            method,
+            path,
+            headers,
+        })
+    }
+}
@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use ethers::signers::LocalWallet;
+use ethers::signers::Signer;
+
+use super::util::added;
+use super::*;
+use crate::helper::ImageSetupRequest;
+use crate::helper::MockTransport;
+use crate::helper::ProveRequest;
+use crate::helper::ResetRequest;
+use crate::helper::ServiceError;
+use crate::helper::TaskEndpoint;
+use crate::interface::CustomContext;
+use crate::interface::InitialContext;
+use crate::interface::ProofSubmitMode;
+use crate::interface::ProvePaymentSrc;
+use crate::interface::ResetContext;
+
+/// A well known development key, requests are only sent to a [`MockTransport`].
+const PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcad5b9a2e2f4fa6f9";
+const MD5: &str = "5240DD2F4E1A2B3C4D5E6F708192A3B4";
+
+fn user_address() -> String {
+    let wallet: LocalWallet = PRIVATE_KEY.parse().expect("Private key should parse");
+    format!("{:#x}", wallet.address())
+}
+
+fn assert_invalid(res: crate::helper::Result<impl Sized>, field: &str) {
+    let Err(err) = res else {
+        unreachable!("Should reject invalid `{field}`");
+    };
+    assert!(
+        matches!(&err, ServiceError::Request(_)) && err.to_string().contains(field),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn test_task_requests_validate_fields() {
+    assert_invalid(ImageSetupRequest::builder().image(vec![1]).build(), "name");
+    assert_invalid(ImageSetupRequest::builder().name("image").build(), "image");
+    assert_invalid(
+        ImageSetupRequest::builder().name("image").image(vec![1]).image_md5(MD5).build(),
+        "image_md5",
+    );
+    assert_invalid(
+        ImageSetupRequest::builder()
+            .name("image")
+            .image(vec![1])
+            .user_address("0x123")
+            .circuit_size(22)
+            .build(),
+        "user_address",
+    );
+    assert_invalid(
+        ImageSetupRequest::builder()
+            .name("image")
+            .image(vec![1])
+            .user_address(user_address())
+            .build(),
+        "circuit_size",
+    );
+
+    assert_invalid(ProveRequest::builder().md5(MD5).build(), "user_address");
+    assert_invalid(ProveRequest::builder().user_address(user_address()).md5("5240").build(), "md5");
+    assert_invalid(
+        ProveRequest::builder()
+            .user_address(user_address())
+            .md5(MD5)
+            .public_input("0x2a:u64")
+            .build(),
+        "public_inputs",
+    );
+    assert_invalid(
+        ProveRequest::builder()
+            .user_address(user_address())
+            .md5(MD5)
+            .private_input("0x2a")
+            .build(),
+        "private_inputs",
+    );
+
+    assert_invalid(
+        ResetRequest::builder().user_address(user_address()).circuit_size(22).build(),
+        "md5",
+    );
+}
+
+#[test]
+fn test_task_requests_apply_defaults() {
+    let image = vec![0, 97, 115, 109];
+    let setup = ImageSetupRequest::builder()
+        .name("image")
+        .image(image.clone())
+        .user_address(user_address())
+        .circuit_size(22)
+        .build()
+        .expect("Should build setup request");
+    let base = &setup.params().base;
+    assert_eq!(base.image_md5, format!("{:X}", md5::compute(&image)));
+    assert!(matches!(base.prove_payment_src, ProvePaymentSrc::Default));
+    assert!(base.add_prove_task_restrictions.is_none() && base.auto_submit_network_ids.is_empty());
+    assert!(matches!(setup.params().context, InitialContext::Without));
+
+    let prove = ProveRequest::builder()
+        .user_address(user_address())
+        .md5(MD5)
+        .public_input("0x2a:i64")
+        .private_input("0x0102:bytes-packed")
+        .build()
+        .expect("Should build prove request");
+    assert!(matches!(prove.params().base.proof_submit_mode, ProofSubmitMode::Manual));
+    assert!(matches!(prove.params().context, CustomContext::Without));
+
+    let reset = ResetRequest::builder()
+        .md5(MD5)
+        .user_address(user_address())
+        .circuit_size(22)
+        .reset_context(vec![1, 2, 3])
+        .build()
+        .expect("Should build reset request");
+    assert!(matches!(reset.params().base.prove_payment_src, ProvePaymentSrc::Default));
+    let ResetContext::With(context) = &reset.params().context else {
+        unreachable!("Should have a reset context");
+    };
+    assert_eq!(context.reset_context_md5, format!("{:X}", md5::compute([1, 2, 3])));
+}
+
+#[test]
+fn test_task_requests_uppercase_md5s() {
+    let image = vec![0, 97, 115, 109];
+    let image_md5 = format!("{:x}", md5::compute(&image));
+    let setup = ImageSetupRequest::builder()
+        .name("image")
+        .image(image)
+        .image_md5(image_md5.clone())
+        .user_address(user_address())
+        .circuit_size(22)
+        .inherited_merkle_data_md5(MD5.to_lowercase())
+        .build()
+        .expect("Should build setup request");
+    let base = &setup.params().base;
+    assert_eq!(base.image_md5, image_md5.to_uppercase());
+    assert_eq!(base.inherited_merkle_data_md5.as_deref(), Some(MD5));
+
+    let prove = ProveRequest::builder()
+        .user_address(user_address())
+        .md5(MD5.to_lowercase())
+        .build()
+        .expect("Should build prove request");
+    assert_eq!(prove.params().base.md5, MD5);
+
+    let reset = ResetRequest::builder()
+        .md5(MD5.to_lowercase())
+        .user_address(user_address())
+        .circuit_size(22)
+        .build()
+        .expect("Should build reset request");
+    assert_eq!(reset.params().base.md5, MD5);
+}
+
+#[tokio::test]
+async fn test_task_requests_match_positional_arguments() {
+    let mock = Arc::new(MockTransport::new());
+    for endpoint in [
+        TaskEndpoint::Prove,
+        TaskEndpoint::Prove,
+        TaskEndpoint::Reset,
+        TaskEndpoint::Reset,
+    ] {
+        mock.respond_ok_to(&endpoint, &added(MD5, "task"));
+    }
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+
+    let prove = ProveRequest::builder()
+        .user_address(user_address())
+        .md5(MD5)
+        .public_input("0x2a:i64")
+        .input_context(vec![7])
+        .build()
+        .expect("Should build prove request");
+    let context = prove.params().context.clone();
+    zkh.add_prove_with(prove, super::util::signer(PRIVATE_KEY))
+        .await
+        .expect("Should add prove task");
+    zkh.add_prove(
+        user_address(),
+        MD5.to_string(),
+        vec!["0x2a:i64".to_string()],
+        vec![],
+        ProofSubmitMode::Manual,
+        context,
+        super::util::signer(PRIVATE_KEY),
+    )
+    .await
+    .expect("Should add prove task");
+
+    let reset = ResetRequest::builder()
+        .md5(MD5)
+        .user_address(user_address())
+        .circuit_size(22)
+        .auto_submit_network_ids(vec![11_155_111])
+        .build()
+        .expect("Should build reset request");
+    zkh.add_reset_with(reset, super::util::signer(PRIVATE_KEY))
+        .await
+        .expect("Should add reset task");
+    zkh.add_reset(
+        MD5.to_string(),
+        22,
+        user_address(),
+        ProvePaymentSrc::Default,
+        vec![11_155_111],
+        None,
+        ResetContext::Without,
+        super::util::signer(PRIVATE_KEY),
+    )
+    .await
+    .expect("Should add reset task");
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    for pair in requests.chunks(2) {
+        assert_eq!(format!("{:?}", pair[0].body), format!("{:?}", pair[1].body));
+        assert!(pair[0].signature.is_some());
+        assert_eq!(pair[0].signature, pair[1].signature);
+    }
+}
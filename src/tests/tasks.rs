@@ -1,17 +1,6 @@
 use super::*;
 use crate::run_test;
 
-fn read_wasm(image: String) -> anyhow::Result<(Vec<u8>, String)> {
-    use std::io::Read;
-
-    let mut file = std::fs::File::open(image)?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
-
-    let md5 = format!("{:X}", md5::compute(&buffer)).to_uppercase();
-    Ok((buffer, md5))
-}
-
 #[cfg(test)]
 mod payments {
     use super::*;
@@ -48,39 +37,29 @@ mod payments {
 }
 
 async fn wait_for_done_task(id: &str) {
-    loop {
-        let res = ZKH
-            .query_task_from_id(id.to_string())
-            .await
-            .ok()
-            .flatten()
-            .expect("Should be able to query task");
-        if let crate::interface::TaskStatus::Done = res.status {
-            break;
-        }
-        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-    }
+    let outcome = ZKH
+        .wait_for_task(
+            id.to_string(),
+            crate::helper::WatchOptions::default().initial_interval(std::time::Duration::from_secs(5)),
+        )
+        .await
+        .expect("Should be able to query task");
+    assert!(outcome.is_done(), "Task {id} should be done");
 }
 
 async fn run_setup_image() -> anyhow::Result<(String, String)> {
-    let (buffer, md5) = read_wasm(CONFIG.tasks.image.clone())?;
-
-    let res = run_test!(
-        ZkWasmServiceHelper::setup_image,
-        md5.clone(),
-        buffer,
-        md5.clone(),
-        CONFIG.user_address().clone(),
-        format!("ZKP CLI test image {md5}"),
-        String::new(),
-        22,
-        crate::interface::ProvePaymentSrc::Default,
-        vec![CONFIG.details.chain_id],
-        None,
-        None,
-        crate::interface::InitialContext::Without,
-        CONFIG.details.private_key.clone(),
-    );
+    let image = crate::helper::UploadSource::from_path(&CONFIG.tasks.image).await?;
+    let md5 = image.md5().to_string();
+    let request = crate::helper::ImageSetupRequest::builder()
+        .name(md5.clone())
+        .image_source(image)
+        .user_address(CONFIG.user_address().clone())
+        .description_url(format!("ZKP CLI test image {md5}"))
+        .circuit_size(22)
+        .auto_submit_network_ids(vec![CONFIG.details.chain_id])
+        .build()?;
+
+    let res = run_test!(ZkWasmServiceHelper::setup_image_with, request, CONFIG.signer(),);
     Ok((res.id, res.md5))
 }
 
@@ -93,7 +72,7 @@ async fn run_prove_image(md5: String) -> anyhow::Result<String> {
         vec![],
         crate::interface::ProofSubmitMode::Manual,
         crate::interface::CustomContext::Without,
-        CONFIG.details.private_key.clone(),
+        CONFIG.signer(),
     );
     Ok(res.id)
 }
@@ -108,7 +87,7 @@ async fn run_reset_image(md5: String) -> anyhow::Result<String> {
         vec![CONFIG.details.chain_id],
         None,
         crate::interface::ResetContext::Without,
-        CONFIG.details.private_key.clone(),
+        CONFIG.signer(),
     );
     Ok(res.id)
 }
@@ -120,7 +99,7 @@ async fn run_modify_image(md5: String) -> anyhow::Result<()> {
         CONFIG.user_address().clone(),
         format!("ZKP CLI test image {md5} -- modified"),
         String::new(),
-        CONFIG.details.private_key.clone(),
+        CONFIG.signer(),
     );
     Ok(())
 }
@@ -161,7 +140,7 @@ mod reprocess {
             ZkWasmServiceHelper::force_unprovable_to_reprocess,
             ids,
             CONFIG.user_address().clone(),
-            CONFIG.details.private_key.clone(),
+            CONFIG.signer(),
         );
         assert_eq!(n_ids, res.len());
     }
@@ -180,7 +159,7 @@ mod reprocess {
             ZkWasmServiceHelper::force_dryrun_fails_to_reprocess,
             ids,
             CONFIG.user_address().clone(),
-            CONFIG.details.private_key.clone(),
+            CONFIG.signer(),
         );
         assert_eq!(n_ids, res.len());
     }
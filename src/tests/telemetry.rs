@@ -0,0 +1,98 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Layer;
+
+use super::stub::StubResponse;
+use super::stub::StubServer;
+use super::*;
+
+/// Collects every span field and event field as `name=value` strings.
+#[derive(Clone, Default)]
+struct FieldCollector(Arc<Mutex<Vec<String>>>);
+
+impl Visit for FieldCollector {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.lock().expect("Should lock").push(format!("{}={value:?}", field.name()));
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for FieldCollector {
+    fn on_new_span(&self, attrs: &tracing::span::Attributes<'_>, _: &tracing::span::Id, _: Context<'_, S>) {
+        attrs.record(&mut self.clone());
+    }
+
+    fn on_record(&self, _: &tracing::span::Id, values: &tracing::span::Record<'_>, _: Context<'_, S>) {
+        values.record(&mut self.clone());
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+        event.record(&mut self.clone());
+    }
+}
+
+#[tokio::test]
+async fn test_request_span_fields() {
+    let collector = FieldCollector::default();
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(collector.clone()));
+
+    let stub = StubServer::start(vec![StubResponse::ok(&serde_json::json!("some logs"))]).await;
+    let zkh = ZkWasmServiceHelper::new(stub.url.clone());
+    zkh.query_logs(
+        "task".to_string(),
+        "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23".to_string(),
+        super::util::signer("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"),
+    )
+    .await
+    .expect("Should query logs");
+
+    let signature = stub.requests()[0]
+        .header("x-eth-signature")
+        .expect("Should be signed")
+        .to_string();
+    let response_size = serde_json::json!({ "success": true, "result": "some logs" }).to_string().len();
+    let fields = collector.0.lock().expect("Should lock").clone();
+    for expected in [
+        "method=\"GET\"".to_string(),
+        "endpoint=\"logs\"".to_string(),
+        "signed=true".to_string(),
+        "status=200".to_string(),
+        format!("response_size={response_size}"),
+        "retries=0".to_string(),
+    ] {
+        assert!(fields.contains(&expected), "Missing {expected} in {fields:?}");
+    }
+    assert!(fields.iter().any(|f| f.starts_with("latency_ms=")));
+    assert!(fields.iter().all(|f| !f.contains(&signature)));
+}
+
+#[cfg(feature = "log-bodies")]
+#[test]
+fn test_redacted_bodies() {
+    use crate::interface::BaseProvingParams;
+    use crate::interface::CustomContext;
+    use crate::interface::ProofSubmitMode;
+    use crate::interface::ProvingParams;
+
+    let body = crate::helper::redact::request(&ProvingParams {
+        base: BaseProvingParams {
+            user_address: "0xabc".to_string(),
+            md5: "MD5".to_string(),
+            public_inputs: vec!["1:i64".to_string()],
+            private_inputs: vec!["42:i64".to_string()],
+            proof_submit_mode: ProofSubmitMode::Manual,
+        },
+        context: CustomContext::Without,
+    });
+    assert!(body.contains("\"private_inputs\":\"<redacted>\""));
+    assert!(body.contains("1:i64"));
+    assert!(!body.contains("42:i64"));
+
+    let body = crate::helper::redact::response(r#"{"result":[{"private_inputs":["42:i64"],"md5":"MD5"}]}"#);
+    assert!(!body.contains("42:i64"));
+    assert!(body.contains("MD5"));
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::*;
+use crate::helper::endpoint::ZkWasmServiceEndpoint;
+use crate::helper::MockTransport;
+use crate::helper::MultipartValue;
+use crate::helper::RequestBody;
+use crate::helper::RetryPolicy;
+use crate::helper::SerializationAttributes;
+use crate::helper::ServiceError;
+use crate::helper::TaskEndpoint;
+use crate::helper::TransportResponse;
+use crate::interface::StatisticsInfo;
+
+fn statistics() -> StatisticsInfo {
+    StatisticsInfo {
+        total_images: 1,
+        total_proofs: 2,
+        total_tasks: 3,
+        total_deployed: 4,
+    }
+}
+
+#[derive(Serialize, SerializationAttributes)]
+struct UploadParams {
+    name: String,
+    #[zkp(bytes)]
+    image: Vec<u8>,
+    network_ids: Vec<u32>,
+}
+
+#[tokio::test]
+async fn test_mock_transport_answers_scripted_requests() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Statistics, &statistics());
+
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+    let stats = zkh.query_statistics().await.expect("Should query statistics");
+    assert_eq!(stats.total_tasks, 3);
+    assert_eq!(mock.remaining(), 0);
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, reqwest::Method::GET);
+    assert_eq!(requests[0].endpoint, TaskEndpoint::Statistics);
+    assert_eq!(requests[0].path(), "/statistics");
+    assert!(requests[0].signature.is_none());
+}
+
+#[tokio::test]
+async fn test_mock_transport_matches_endpoint() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_error_to(&TaskEndpoint::Config, reqwest::StatusCode::FORBIDDEN, "Not allowed")
+        .respond_ok_to(&TaskEndpoint::Statistics, &statistics());
+
+    let zkh = ZkWasmServiceHelper::with_transport(mock.clone());
+    let stats = zkh.query_statistics().await.expect("Should query statistics");
+    assert_eq!(stats.total_deployed, 4);
+
+    let err = zkh.query_config().await.err().expect("Should be rejected");
+    assert!(
+        matches!(&err, ServiceError::ServerRejected { status, message, .. } if *status == reqwest::StatusCode::FORBIDDEN && message == "Not allowed"),
+        "{err:?}"
+    );
+
+    let err = zkh.query_config().await.err().expect("Should not be scripted");
+    assert!(
+        matches!(&err, ServiceError::HttpStatus { status, .. } if *status == reqwest::StatusCode::NOT_IMPLEMENTED),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_mock_transport_records_query_and_signature() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Statistics, &statistics());
+
+    let endpoint = ZkWasmServiceEndpoint::with_transport(mock.clone());
+    endpoint
+        .get::<_, StatisticsInfo>(
+            TaskEndpoint::Statistics,
+            [("md5", "abc"), ("user_address", "0x1")],
+            Some("0xsig".to_string()),
+        )
+        .await
+        .expect("Should query statistics");
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].query, "md5=abc&user_address=0x1");
+    assert_eq!(requests[0].signature.as_deref(), Some("0xsig"));
+}
+
+#[tokio::test]
+async fn test_mock_transport_multipart_body() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond_ok_to(&TaskEndpoint::Setup, &"ok");
+
+    let endpoint = ZkWasmServiceEndpoint::with_transport(mock.clone());
+    let params = UploadParams {
+        name: "image".to_string(),
+        image: vec![0, 97, 115, 109],
+        network_ids: vec![1, 2],
+    };
+    endpoint
+        .post::<_, String>(TaskEndpoint::Setup, params, None)
+        .await
+        .expect("Should post");
+
+    let requests = mock.requests();
+    let RequestBody::Multipart(fields) = &requests[0].body else {
+        unreachable!("Expected multipart body, got {:?}", requests[0].body);
+    };
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|f| match &f.value {
+            MultipartValue::Text(v) => (f.name.as_str(), v.as_bytes().to_vec()),
+            MultipartValue::Bytes(v) => (f.name.as_str(), v.to_vec()),
+            MultipartValue::Stream(_) => unreachable!("Expected the image to be sent from memory"),
+        })
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("name", b"image".to_vec()),
+            ("image", vec![0, 97, 115, 109]),
+            ("network_ids", b"1".to_vec()),
+            ("network_ids", b"2".to_vec()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_builder_transport_keeps_retry_policy() {
+    let mock = Arc::new(MockTransport::new());
+    mock.respond(TransportResponse::new(reqwest::StatusCode::SERVICE_UNAVAILABLE, "busy"))
+        .respond_ok_to(&TaskEndpoint::Statistics, &statistics());
+
+    let zkh = ZkWasmServiceHelper::builder(String::new())
+        .transport(mock.clone())
+        .retry_policy(RetryPolicy::default().initial_backoff(Duration::from_millis(1)))
+        .build()
+        .expect("Should build helper");
+    zkh.query_statistics().await.expect("Should succeed after retry");
+    assert_eq!(mock.requests().len(), 2);
+}
@@ -0,0 +1,171 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use futures::TryStreamExt;
+
+use crate::helper::ImageSetupRequest;
+use crate::helper::UploadProgress;
+use crate::helper::UploadSource;
+
+const ADDRESS: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+/// Writes `content` to a file unique to this test run.
+fn temp_image(name: &str, content: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("zkp-service-helper-{}-{name}.wasm", std::process::id()));
+    std::fs::write(&path, content).expect("Should write temporary image");
+    path
+}
+
+/// An image larger than one chunk, so that it is streamed in several pieces.
+fn large_image() -> Vec<u8> {
+    (0..200_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+fn record_progress(source: UploadSource) -> (UploadSource, Arc<Mutex<Vec<UploadProgress>>>) {
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let recorded = progress.clone();
+    let source = source.on_progress(move |p| recorded.lock().expect("Should lock progress").push(p));
+    (source, progress)
+}
+
+#[tokio::test]
+async fn test_upload_from_path_streams_in_chunks() {
+    let image = large_image();
+    let path = temp_image("chunks", &image);
+    let source = UploadSource::from_path(&path).await.expect("Should read image");
+    assert_eq!(source.md5(), format!("{:X}", md5::compute(&image)));
+    assert_eq!(source.len(), Some(image.len() as u64));
+
+    let (source, progress) = record_progress(source);
+    let chunks: Vec<_> = source.stream().try_collect().await.expect("Should stream image");
+    assert!(chunks.len() > 1);
+    assert_eq!(chunks.concat(), image);
+
+    let progress = progress.lock().expect("Should lock progress").clone();
+    assert_eq!(progress.len(), chunks.len());
+    assert!(progress.windows(2).all(|w| w[0].sent < w[1].sent));
+    assert_eq!(
+        progress.last(),
+        Some(&UploadProgress {
+            sent: image.len() as u64,
+            total: Some(image.len() as u64),
+        })
+    );
+
+    // A file is reopened for every attempt, so it can be sent again.
+    let again: Vec<_> = source.stream().try_collect().await.expect("Should stream image again");
+    assert_eq!(again.concat(), image);
+    std::fs::remove_file(path).expect("Should remove temporary image");
+}
+
+#[tokio::test]
+async fn test_upload_checks_md5_while_streaming() {
+    let path = temp_image("changed", b"\0asm");
+    let source = UploadSource::from_path(&path).await.expect("Should read image");
+    std::fs::write(&path, b"\0asm changed").expect("Should change image");
+    let err = source
+        .stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .expect_err("Should notice the changed image");
+    assert!(err.to_string().contains("md5"), "{err}");
+    std::fs::remove_file(path).expect("Should remove temporary image");
+
+    let source = UploadSource::from_reader(&b"\0asm"[..], "00000000000000000000000000000000", None);
+    let err = source
+        .stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .expect_err("Should reject the wrong md5");
+    assert!(err.to_string().contains("md5"), "{err}");
+}
+
+#[tokio::test]
+async fn test_upload_from_reader_is_streamed_once() {
+    let md5 = format!("{:X}", md5::compute(b"\0asm"));
+    let source = UploadSource::from_reader(&b"\0asm"[..], md5, Some(4));
+    let chunks: Vec<_> = source.stream().try_collect().await.expect("Should stream reader");
+    assert_eq!(chunks.concat(), b"\0asm");
+
+    let err = source
+        .stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .expect_err("Should not stream the reader twice");
+    assert!(err.to_string().contains("already streamed"), "{err}");
+}
+
+#[test]
+fn test_setup_request_from_source() {
+    let md5 = format!("{:X}", md5::compute(b"\0asm"));
+    let builder = || {
+        ImageSetupRequest::builder()
+            .name("streamed")
+            .image_source(UploadSource::from_reader(&b"\0asm"[..], md5.clone(), Some(4)))
+            .user_address(ADDRESS)
+            .circuit_size(22)
+    };
+
+    let request = builder().build().expect("Should build streamed request");
+    assert_eq!(request.params().base.image_md5, md5);
+    assert!(request.params().base.image.is_empty());
+    assert_eq!(request.image_source().map(UploadSource::md5), Some(md5.as_str()));
+
+    let err = builder()
+        .image_md5("00000000000000000000000000000000")
+        .build()
+        .err()
+        .expect("Should reject an md5 which does not match the source");
+    assert!(err.to_string().contains("does not match"), "{err}");
+
+    let err = ImageSetupRequest::builder()
+        .name("empty")
+        .image_source(UploadSource::from_reader(&b""[..], md5.clone(), Some(0)))
+        .user_address(ADDRESS)
+        .circuit_size(22)
+        .build()
+        .err()
+        .expect("Should reject an empty image");
+    assert!(err.to_string().contains("must not be empty"), "{err}");
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_setup_image_from_path() {
+    let server = crate::mock::MockServer::start(crate::mock::MockConfig::default())
+        .await
+        .expect("Should start mock server");
+    let image = large_image();
+    let path = temp_image("setup", &image);
+    let (source, progress) = record_progress(UploadSource::from_path(&path).await.expect("Should read image"));
+
+    let session = server
+        .helper()
+        .with_signer(super::util::signer(&server.fixtures().private_key))
+        .await
+        .expect("Should create session");
+    let res = session
+        .setup_image(
+            ImageSetupRequest::builder()
+                .name("streamed image")
+                .image_source(source)
+                .circuit_size(22)
+                .auto_submit_network_ids(vec![server.fixtures().chain_id]),
+        )
+        .await
+        .expect("Should add streamed image");
+    assert_eq!(res.md5, format!("{:X}", md5::compute(&image)));
+
+    let image_info = server
+        .helper()
+        .query_image(res.md5.clone())
+        .await
+        .expect("Should query streamed image");
+    assert_eq!(image_info.map(|image| image.md5), Some(res.md5));
+    assert_eq!(
+        progress.lock().expect("Should lock progress").last().map(|p| p.sent),
+        Some(image.len() as u64)
+    );
+    std::fs::remove_file(path).expect("Should remove temporary image");
+}
@@ -1,6 +1,16 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::helper::MockTransport;
+use crate::helper::TaskEndpoint;
+use crate::helper::WalletSigner;
+use crate::helper::WatchOptions;
+use crate::interface::AddTaskResult;
+use crate::interface::PaginationResult;
+use crate::interface::Task;
+
 #[derive(Deserialize, Serialize)]
 pub(super) struct DetailsConfig {
     pub server_url: String,
@@ -54,6 +64,7 @@ pub(super) struct TestConfig {
 }
 
 impl TestConfig {
+    #[cfg(not(feature = "mock"))]
     fn read_config() -> anyhow::Result<Self> {
         let file = std::fs::File::open("test.json")?;
         let reader = std::io::BufReader::new(file);
@@ -61,18 +72,87 @@ impl TestConfig {
         Ok(data)
     }
 
+    #[cfg(not(feature = "mock"))]
     pub fn init() -> Self {
         Self::read_config().expect("Should be able to read test config")
     }
 
+    /// Points the tests at the in-memory mock server and its seeded records instead of `test.json`.
+    #[cfg(feature = "mock")]
+    pub fn init() -> Self {
+        let fixtures = super::MOCK.fixtures().clone();
+        let server_url = super::MOCK.url().to_string();
+        Self {
+            details: DetailsConfig {
+                server_url: server_url.clone(),
+                private_key: fixtures.private_key,
+                chain_id: fixtures.chain_id,
+                pedantic_checks: true,
+            },
+            verify: VerifyConfig {
+                provider_url: String::new(),
+                manual_task_id_to_verify: fixtures.task_id.clone(),
+            },
+            query: QueryConfig {
+                task_id: fixtures.task_id,
+                md5: fixtures.image_md5,
+                node_address: fixtures.node_address,
+            },
+            auto_submit: AutoSubmitConfig {
+                round1_id: fixtures.round1_id,
+                round2_id: fixtures.round2_id,
+                task_id_in_auto_submit_batch: fixtures.auto_submit_task_id,
+            },
+            archive: ArchiveConfig {
+                server_url,
+                id: fixtures.archive_id,
+                archived_task_id: fixtures.archived_task_id,
+                archive_volume_name: fixtures.archive_volume_name,
+                archive_auto_submit_volume_name: fixtures.archive_auto_submit_volume_name,
+            },
+            tasks: TasksConfig {
+                image: "./data/image.wasm".to_string(),
+            },
+        }
+    }
+
     pub fn user_address(&self) -> String {
         use ethers::signers::Signer;
         let wallet: ethers::signers::LocalWallet = self.details.private_key.parse().expect("Private key should parse");
         format!("{:#x}", wallet.address())
     }
+
+    pub fn signer(&self) -> WalletSigner {
+        signer(&self.details.private_key)
+    }
+}
+
+pub(super) fn signer(private_key: &str) -> WalletSigner {
+    WalletSigner::from_private_key(private_key).expect("Should parse private key")
+}
+
+/// The response to adding the task `id` for the image with the given md5.
+pub(super) fn added(md5: &str, id: &str) -> AddTaskResult {
+    AddTaskResult {
+        md5: md5.to_string(),
+        id: id.to_string(),
+    }
+}
+
+/// Scripts `mock` to answer the next task query with `tasks`.
+pub(super) fn respond_tasks(mock: &MockTransport, tasks: Vec<Task>) {
+    let total = tasks.len() as u64;
+    mock.respond_ok_to(&TaskEndpoint::Tasks, &PaginationResult { data: tasks, total });
+}
+
+/// Watch options which poll every few milliseconds, for tests against a [`MockTransport`].
+pub(super) fn fast_watch() -> WatchOptions {
+    WatchOptions::default()
+        .initial_interval(Duration::from_millis(1))
+        .max_interval(Duration::from_millis(4))
 }
 
-pub(super) fn check_and_print<T: for<'de> Deserialize<'de> + Serialize>(result: anyhow::Result<T>) -> T {
+pub(super) fn check_and_print<T: for<'de> Deserialize<'de> + Serialize, E: std::fmt::Debug>(result: Result<T, E>) -> T {
     result
         .inspect(|inp| {
             let s = serde_json::to_string(&inp).expect("Should be serializable");
@@ -81,3 +161,45 @@ pub(super) fn check_and_print<T: for<'de> Deserialize<'de> + Serialize>(result:
         })
         .expect("Result should be valid")
 }
+
+/// The `result` of the response recorded for `path` in the server cassette.
+fn recorded_result(path: &str) -> serde_json::Value {
+    use crate::helper::Cassette;
+    use crate::helper::RecordedBody;
+
+    let cassette = Cassette::load("./data/cassettes/server.json").expect("Should load server cassette");
+    let interaction = cassette
+        .interactions
+        .into_iter()
+        .find(|interaction| interaction.path == path)
+        .expect("Should have recorded the path");
+    let RecordedBody::Json(mut body) = interaction.body else {
+        unreachable!("Recorded responses should be JSON");
+    };
+    body["result"].take()
+}
+
+/// The task recorded in the server cassette, with the given id and status.
+pub(super) fn recorded_task(id: &str, status: crate::interface::TaskStatus) -> Task {
+    let page: PaginationResult<Vec<Task>> =
+        serde_json::from_value(recorded_result("/tasks")).expect("Should decode recorded task");
+    let task = page.data.into_iter().next().expect("Should have recorded a task");
+    Task {
+        _id: crate::interface::ObjectId { oid: id.to_string() },
+        status,
+        ..task
+    }
+}
+
+/// The image recorded in the server cassette, with the given md5.
+pub(super) fn recorded_image(md5: &str) -> crate::interface::Image {
+    use crate::interface::Image;
+
+    let images: Vec<Option<Image>> =
+        serde_json::from_value(recorded_result("/image")).expect("Should decode recorded image");
+    let image = images.into_iter().flatten().next().expect("Should have recorded an image");
+    Image {
+        md5: md5.to_string(),
+        ..image
+    }
+}
@@ -0,0 +1,100 @@
+use crate::helper::ImageSetupRequest;
+use crate::helper::WasmImage;
+use crate::helper::WasmValidator;
+
+const IMAGE: &str = "./data/image.wasm";
+
+/// A module which only consists of an export section with the given `(name, kind)` exports.
+fn module(exports: &[(&str, u8)]) -> Vec<u8> {
+    let mut section = vec![u8::try_from(exports.len()).expect("Should have few exports")];
+    for (name, kind) in exports {
+        section.push(u8::try_from(name.len()).expect("Should have a short name"));
+        section.extend_from_slice(name.as_bytes());
+        section.extend_from_slice(&[*kind, 0]);
+    }
+    let mut module = b"\0asm\x01\0\0\0".to_vec();
+    module.push(7);
+    module.push(u8::try_from(section.len()).expect("Should have a short section"));
+    module.extend(section);
+    module
+}
+
+fn assert_invalid(bytes: Vec<u8>, expected: &str) {
+    let err = WasmImage::from_bytes(bytes).expect_err("Should reject the image");
+    assert!(err.to_string().contains(expected), "{err}");
+}
+
+#[tokio::test]
+async fn test_wasm_image_from_path() {
+    let image = WasmImage::from_path(IMAGE).await.expect("Should load the test image");
+    let bytes = std::fs::read(IMAGE).expect("Should read the test image");
+    assert_eq!(image.bytes(), bytes.as_slice());
+    assert_eq!(image.md5(), format!("{:X}", md5::compute(&bytes)));
+    assert_eq!(image, WasmImage::from_bytes(bytes).expect("Should load the test image bytes"));
+
+    let request = ImageSetupRequest::builder()
+        .name("image")
+        .wasm_image(image.clone())
+        .user_address("0x70997970c51812dc3a010c7d01b50e0d17dc79c8")
+        .circuit_size(22)
+        .build()
+        .expect("Should build request from image");
+    assert_eq!(request.params().base.image_md5, image.md5());
+    assert_eq!(request.params().base.image, image.bytes());
+}
+
+#[tokio::test]
+async fn test_wasm_image_size_limit() {
+    let len = std::fs::metadata(IMAGE).expect("Should stat the test image").len();
+    let limit = usize::try_from(len).expect("Should fit usize") - 1;
+    let err = WasmValidator::default()
+        .max_size(limit)
+        .load_path(IMAGE)
+        .await
+        .expect_err("Should reject a large image");
+    assert!(err.to_string().contains("exceeding the limit"), "{err}");
+
+    let err = WasmValidator::default()
+        .max_size(8)
+        .load(module(&[("zkmain", 0)]))
+        .expect_err("Should reject a large module");
+    assert!(err.to_string().contains("exceeds the limit"), "{err}");
+}
+
+#[test]
+fn test_wasm_image_checks_header() {
+    assert_invalid(b"\x7fELF\x02\x01\x01\0".to_vec(), "magic");
+    assert_invalid(b"\0as".to_vec(), "magic");
+    assert_invalid(b"\0asm\x0d\0\x01\0".to_vec(), "unsupported wasm version");
+    assert_invalid(b"\0asm\x01\0".to_vec(), "truncated");
+}
+
+#[test]
+fn test_wasm_image_checks_sections() {
+    let mut truncated = module(&[("zkmain", 0)]);
+    truncated.pop();
+    assert_invalid(truncated, "truncated");
+
+    let mut unknown = b"\0asm\x01\0\0\0".to_vec();
+    unknown.extend_from_slice(&[42, 0]);
+    assert_invalid(unknown, "unknown section id 42");
+
+    let mut overlong = b"\0asm\x01\0\0\0".to_vec();
+    overlong.extend_from_slice(&[0, 0xff, 0xff, 0xff, 0xff, 0x7f]);
+    assert_invalid(overlong, "32 bits");
+}
+
+#[test]
+fn test_wasm_image_requires_entrypoint() {
+    WasmImage::from_bytes(module(&[("memory", 2), ("zkmain", 0)])).expect("Should accept the entrypoint");
+    assert_invalid(
+        module(&[("memory", 2)]),
+        "does not export the function `zkmain`, exports: [memory]",
+    );
+    assert_invalid(module(&[("zkmain", 2)]), "does not export the function `zkmain`");
+
+    WasmValidator::default()
+        .entrypoint("main")
+        .load(module(&[("main", 0)]))
+        .expect("Should accept a custom entrypoint");
+}
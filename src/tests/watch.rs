@@ -0,0 +1,139 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::util::fast_watch;
+use super::util::respond_tasks;
+use crate::helper::MockTransport;
+use crate::helper::TaskOutcome;
+use crate::helper::ZkWasmServiceHelper;
+use crate::interface::Task;
+use crate::interface::TaskStatus;
+
+fn task_with(status: TaskStatus) -> Task {
+    super::util::recorded_task("690000000000000000000002", status)
+}
+
+#[tokio::test]
+async fn test_wait_reports_failure_and_transitions() {
+    let mock = Arc::new(MockTransport::new());
+    respond_tasks(&mock, vec![]);
+    respond_tasks(&mock, vec![task_with(TaskStatus::Pending)]);
+    respond_tasks(&mock, vec![task_with(TaskStatus::Processing)]);
+    respond_tasks(&mock, vec![task_with(TaskStatus::Processing)]);
+    respond_tasks(
+        &mock,
+        vec![Task {
+            status_message: Some("out of gas".to_string()),
+            internal_message: Some("trap at 0x2a".to_string()),
+            ..task_with(TaskStatus::Fail)
+        }],
+    );
+
+    let transitions = Arc::new(Mutex::new(Vec::new()));
+    let recorded = transitions.clone();
+    let options = fast_watch().on_transition(move |t| {
+        recorded
+            .lock()
+            .expect("Should lock transitions")
+            .push((t.previous.clone(), t.task.status.clone()));
+    });
+    let outcome = ZkWasmServiceHelper::with_transport(mock.clone())
+        .wait_for_task("690000000000000000000002".to_string(), options)
+        .await
+        .expect("Should wait for task");
+
+    let TaskOutcome::Failed(failure) = outcome else {
+        unreachable!("Expected the task to fail");
+    };
+    assert!(failure.status == TaskStatus::Fail);
+    assert_eq!(failure.status_message.as_deref(), Some("out of gas"));
+    assert_eq!(failure.internal_message.as_deref(), Some("trap at 0x2a"));
+    assert_eq!(mock.remaining(), 0);
+
+    let transitions = transitions.lock().expect("Should lock transitions").clone();
+    assert!(
+        transitions
+            == vec![
+                (None, TaskStatus::Pending),
+                (Some(TaskStatus::Pending), TaskStatus::Processing),
+                (Some(TaskStatus::Processing), TaskStatus::Fail),
+            ]
+    );
+}
+
+#[tokio::test]
+async fn test_wait_treats_all_failures_as_terminal() {
+    for status in [TaskStatus::Unprovable, TaskStatus::DryRunFailed, TaskStatus::Stale] {
+        let mock = Arc::new(MockTransport::new());
+        respond_tasks(&mock, vec![task_with(TaskStatus::DryRunSuccess)]);
+        respond_tasks(&mock, vec![task_with(status.clone())]);
+        let outcome = ZkWasmServiceHelper::with_transport(mock)
+            .wait_for_task("690000000000000000000002".to_string(), fast_watch())
+            .await
+            .expect("Should wait for task");
+        assert!(matches!(outcome, TaskOutcome::Failed(ref f) if f.status == status));
+        assert!(outcome.task().is_some_and(|task| task.status == status));
+    }
+
+    let mock = Arc::new(MockTransport::new());
+    respond_tasks(&mock, vec![task_with(TaskStatus::Done)]);
+    let outcome = ZkWasmServiceHelper::with_transport(mock)
+        .wait_for_task("690000000000000000000002".to_string(), fast_watch())
+        .await
+        .expect("Should wait for task");
+    assert!(outcome.is_done());
+}
+
+#[tokio::test]
+async fn test_wait_times_out() {
+    let mock = Arc::new(MockTransport::new());
+    for _ in 0..100 {
+        respond_tasks(&mock, vec![task_with(TaskStatus::Processing)]);
+    }
+    let outcome = ZkWasmServiceHelper::with_transport(mock)
+        .wait_for_task(
+            "690000000000000000000002".to_string(),
+            fast_watch().timeout(Duration::from_millis(20)),
+        )
+        .await
+        .expect("Should wait for task");
+    assert!(matches!(
+        outcome,
+        TaskOutcome::TimedOut {
+            last_status: Some(TaskStatus::Processing)
+        }
+    ));
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_wait_for_mock_task() {
+    use crate::helper::WatchOptions;
+    use crate::interface::CustomContext;
+    use crate::interface::ProofSubmitMode;
+
+    let server =
+        crate::mock::MockServer::start(crate::mock::MockConfig::default().task_duration(Duration::from_millis(200)))
+            .await
+            .expect("Should start mock server");
+    let helper = server.helper();
+    let res = helper
+        .add_prove(
+            server.fixtures().user_address.clone(),
+            server.fixtures().image_md5.clone(),
+            vec!["0x1:i64".to_string()],
+            vec![],
+            ProofSubmitMode::Manual,
+            CustomContext::Without,
+            super::util::signer(&server.fixtures().private_key),
+        )
+        .await
+        .expect("Should add prove task");
+
+    let outcome = helper
+        .wait_for_task(res.id, WatchOptions::default().initial_interval(Duration::from_millis(50)))
+        .await
+        .expect("Should wait for task");
+    assert!(outcome.is_done());
+}